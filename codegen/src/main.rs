@@ -10,7 +10,7 @@ mod jet;
 
 /// Write a SimplicityHL jet as a Rust function to the sink.
 fn write_jet<W: io::Write>(jet: ElementsExtension, w: &mut W) -> io::Result<()> {
-    for line in jet::documentation(jet).lines() {
+    for line in simplicityhl::jet::documentation(jet).lines() {
         match line.is_empty() {
             true => writeln!(w, "///")?,
             false => writeln!(w, "/// {line}")?,
@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+#
+# Fail if the snapshots of the example programs changed without an entry in CHANGELOG.md.
+#
+# A changed snapshot means that the CMR or the encoding of an example program changed,
+# which affects contracts that are already deployed. Such changes must be announced.
+#
+# Usage: check_snapshot_changelog.sh [BASE_REF]
+
+set -euo pipefail
+
+BASE_REF=${1:-origin/master}
+
+changed=$(git diff --name-only "$BASE_REF"...HEAD)
+
+if grep -q '^examples/snapshots/' <<< "$changed" && ! grep -qx 'CHANGELOG.md' <<< "$changed"; then
+    echo "The snapshots in examples/snapshots changed compared to $BASE_REF:" >&2
+    grep '^examples/snapshots/' <<< "$changed" >&2
+    echo "The compiler output of the examples changed. Explain the change in CHANGELOG.md." >&2
+    exit 1
+fi
+
+echo "check_snapshot_changelog: ok"
@@ -0,0 +1,129 @@
+/*
+ * C interface of the SimplicityHL compiler.
+ *
+ * Opaque handles are owned by the caller and must be released exactly once
+ * with the matching *_free function. String arguments are borrowed for the
+ * duration of the call. Error strings and byte buffers that are written by
+ * the library are owned by the caller and must be released with
+ * simplicityhl_error_clear and simplicityhl_bytes_free, respectively.
+ *
+ * Every pointer to a SimplicityHlError may be null if the caller is not
+ * interested in error details.
+ */
+
+#ifndef SIMPLICITYHL_H
+#define SIMPLICITYHL_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum SimplicityHlStatus {
+    SIMPLICITYHL_OK = 0,
+    SIMPLICITYHL_NULL_POINTER = 1,
+    SIMPLICITYHL_INVALID_UTF8 = 2,
+    SIMPLICITYHL_PARSE = 3,
+    SIMPLICITYHL_ARGUMENTS_JSON = 4,
+    SIMPLICITYHL_COMPILE = 5,
+    SIMPLICITYHL_WITNESS_JSON = 6,
+    SIMPLICITYHL_SATISFY = 7,
+    SIMPLICITYHL_PANIC = 8,
+} SimplicityHlStatus;
+
+/*
+ * Initialize with {0} before passing it to the library. The error may be
+ * reused across calls; the strings of a previous failure are released before
+ * the next failure is written.
+ *
+ * code is a stable error code such as "E0021", or null if the failure has no
+ * code. The location of the error in the source file is given by start_line,
+ * start_col, end_line and end_col, which start at 1. They are 0 if the error
+ * has no location.
+ */
+typedef struct SimplicityHlError {
+    SimplicityHlStatus status;
+    char *message;
+    char *code;
+    size_t start_line;
+    size_t start_col;
+    size_t end_line;
+    size_t end_col;
+} SimplicityHlError;
+
+/* Initialize with {0} before passing it to the library. */
+typedef struct SimplicityHlBytes {
+    uint8_t *data;
+    size_t len;
+} SimplicityHlBytes;
+
+typedef struct SimplicityHlTemplate SimplicityHlTemplate;
+typedef struct SimplicityHlCompiled SimplicityHlCompiled;
+typedef struct SimplicityHlSatisfied SimplicityHlSatisfied;
+
+void simplicityhl_error_clear(SimplicityHlError *error);
+void simplicityhl_bytes_free(SimplicityHlBytes *bytes);
+
+SimplicityHlStatus simplicityhl_template_new(
+    const char *source,
+    SimplicityHlTemplate **out,
+    SimplicityHlError *error);
+
+void simplicityhl_template_free(SimplicityHlTemplate *template_);
+
+/* arguments_json uses the format of .args files and may be null. */
+SimplicityHlStatus simplicityhl_template_instantiate(
+    const SimplicityHlTemplate *template_,
+    const char *arguments_json,
+    bool include_debug_symbols,
+    SimplicityHlCompiled **out,
+    SimplicityHlError *error);
+
+/* arguments_json uses the format of .args files and may be null. */
+SimplicityHlStatus simplicityhl_compile(
+    const char *source,
+    const char *arguments_json,
+    bool include_debug_symbols,
+    SimplicityHlCompiled **out,
+    SimplicityHlError *error);
+
+void simplicityhl_compiled_free(SimplicityHlCompiled *compiled);
+
+SimplicityHlStatus simplicityhl_compiled_cmr(
+    const SimplicityHlCompiled *compiled,
+    uint8_t out[32],
+    SimplicityHlError *error);
+
+SimplicityHlStatus simplicityhl_compiled_program_bytes(
+    const SimplicityHlCompiled *compiled,
+    SimplicityHlBytes *out,
+    SimplicityHlError *error);
+
+/* witness_json uses the format of .wit files. */
+SimplicityHlStatus simplicityhl_compiled_satisfy(
+    const SimplicityHlCompiled *compiled,
+    const char *witness_json,
+    SimplicityHlSatisfied **out,
+    SimplicityHlError *error);
+
+void simplicityhl_satisfied_free(SimplicityHlSatisfied *satisfied);
+
+SimplicityHlStatus simplicityhl_satisfied_cmr(
+    const SimplicityHlSatisfied *satisfied,
+    uint8_t out[32],
+    SimplicityHlError *error);
+
+SimplicityHlStatus simplicityhl_satisfied_bytes(
+    const SimplicityHlSatisfied *satisfied,
+    SimplicityHlBytes *program_out,
+    SimplicityHlBytes *witness_out,
+    SimplicityHlError *error);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SIMPLICITYHL_H */
@@ -0,0 +1,692 @@
+//! C ABI for embedding the SimplicityHL compiler.
+//!
+//! The declarations in `include/simplicityhl.h` mirror the functions of this crate.
+//!
+//! ## Ownership
+//!
+//! Programs are handed out as opaque pointers that own a Rust value.
+//! Every pointer returned by this library must be released exactly once
+//! with the matching `*_free` function.
+//!
+//! Strings that are passed into the library are borrowed for the duration of the call.
+//! The library copies them into its own reference-counted strings,
+//! so the caller may release its buffers as soon as the call returns.
+//!
+//! ## Errors
+//!
+//! Every fallible function returns a [`SimplicityHlStatus`].
+//! If the caller passes a non-null [`SimplicityHlError`],
+//! then the status, a human-readable message, the error code
+//! and the location in the source file are written into it.
+//! The strings must be released with [`simplicityhl_error_clear`].
+//! An error may be reused across calls: Strings of a previous failure are released
+//! before the next failure is written.
+//!
+//! Panics never cross the FFI boundary. They are reported as [`SimplicityHlStatus::Panic`].
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use simplicityhl::error::Span;
+use simplicityhl::{
+    Arguments, CompiledProgram, Error, SatisfiedProgram, TemplateProgram, WitnessValues,
+};
+
+/// Status code of a library call.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SimplicityHlStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// The program could not be parsed or type-checked.
+    Parse = 3,
+    /// The arguments JSON could not be parsed.
+    ArgumentsJson = 4,
+    /// The program could not be compiled with the given arguments.
+    Compile = 5,
+    /// The witness JSON could not be parsed.
+    WitnessJson = 6,
+    /// The program could not be satisfied with the given witness.
+    Satisfy = 7,
+    /// The library panicked. This is a bug.
+    Panic = 8,
+}
+
+/// Error details of a failed library call.
+///
+/// Initialize with `{0}` before passing it to the library.
+#[repr(C)]
+#[derive(Debug)]
+pub struct SimplicityHlError {
+    /// Status of the failed call.
+    pub status: SimplicityHlStatus,
+    /// Owned, nul-terminated error message, or null.
+    pub message: *mut c_char,
+    /// Owned, nul-terminated error code, such as `E0021`, or null.
+    ///
+    /// Codes are stable and explained by `simc --explain CODE`.
+    pub code: *mut c_char,
+    /// Line where the error starts in the source file, or zero if the error has no location.
+    ///
+    /// Lines and columns start at 1.
+    pub start_line: usize,
+    /// Column where the error starts in the source file, or zero.
+    pub start_col: usize,
+    /// Line where the error ends in the source file, or zero.
+    pub end_line: usize,
+    /// Column where the error ends in the source file, or zero.
+    pub end_col: usize,
+}
+
+/// Owned byte buffer.
+///
+/// Release with [`simplicityhl_bytes_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct SimplicityHlBytes {
+    /// Pointer to the first byte, or null if the buffer is empty.
+    pub data: *mut u8,
+    /// Number of bytes.
+    pub len: usize,
+}
+
+impl SimplicityHlBytes {
+    fn empty() -> Self {
+        Self {
+            data: ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        if bytes.is_empty() {
+            return Self::empty();
+        }
+        let boxed = bytes.into_boxed_slice();
+        let len = boxed.len();
+        let data = Box::into_raw(boxed).cast::<u8>();
+        Self { data, len }
+    }
+}
+
+/// Opaque handle of a [`TemplateProgram`].
+pub struct SimplicityHlTemplate(TemplateProgram);
+
+/// Opaque handle of a [`CompiledProgram`].
+pub struct SimplicityHlCompiled(CompiledProgram);
+
+/// Opaque handle of a [`SatisfiedProgram`].
+pub struct SimplicityHlSatisfied(SatisfiedProgram);
+
+/// Failure of a library call, before it is written into a [`SimplicityHlError`].
+struct Failure {
+    status: SimplicityHlStatus,
+    message: String,
+    code: Option<&'static str>,
+    span: Option<Span>,
+}
+
+impl Failure {
+    fn new<S: ToString>(status: SimplicityHlStatus, message: S) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+            code: None,
+            span: None,
+        }
+    }
+
+    /// Report an error of the SimplicityHL library with its code and location, if known.
+    fn from_error(status: SimplicityHlStatus, error: &Error) -> Self {
+        let (code, span) = match error {
+            Error::Program(error) => (Some(error.error().code()), Some(*error.span())),
+            Error::Arguments(error) | Error::Witness(error) | Error::Delegates(error) => {
+                (Some(error.code()), None)
+            }
+            _ => (None, None),
+        };
+        Self {
+            status,
+            message: error.to_string(),
+            code,
+            span,
+        }
+    }
+
+    fn null_pointer(name: &str) -> Self {
+        Self::new(
+            SimplicityHlStatus::NullPointer,
+            format!("Argument `{name}` must not be null"),
+        )
+    }
+}
+
+/// Run the body of a library call.
+///
+/// Catch panics and report failures via the optional `error` out-parameter.
+fn guard<F>(error: *mut SimplicityHlError, f: F) -> SimplicityHlStatus
+where
+    F: FnOnce() -> Result<(), Failure>,
+{
+    let failure = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => return SimplicityHlStatus::Ok,
+        Ok(Err(failure)) => failure,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Failure::new(SimplicityHlStatus::Panic, message)
+        }
+    };
+
+    // Safety: The caller guarantees that `error` is either null or valid for writes.
+    if let Some(error) = unsafe { error.as_mut() } {
+        // Release the strings of a previous failure
+        // Safety: `error` was initialized with zeros or filled by this library.
+        unsafe { simplicityhl_error_clear(error) };
+        let message = CString::new(failure.message.replace('\0', "\\0"))
+            .expect("interior nul bytes were escaped");
+        error.status = failure.status;
+        error.message = message.into_raw();
+        if let Some(code) = failure.code {
+            error.code = CString::new(code).expect("codes are ASCII").into_raw();
+        }
+        if let Some(span) = failure.span {
+            error.start_line = span.start.line.get();
+            error.start_col = span.start.col.get();
+            error.end_line = span.end.line.get();
+            error.end_col = span.end.col.get();
+        }
+    }
+    failure.status
+}
+
+/// Borrow a nul-terminated UTF-8 string.
+///
+/// ## Safety
+///
+/// `ptr` is either null or points to a nul-terminated string that outlives `'a`.
+unsafe fn borrow_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, Failure> {
+    if ptr.is_null() {
+        return Err(Failure::null_pointer(name));
+    }
+    CStr::from_ptr(ptr).to_str().map_err(|error| {
+        Failure::new(
+            SimplicityHlStatus::InvalidUtf8,
+            format!("Argument `{name}` is not valid UTF-8: {error}"),
+        )
+    })
+}
+
+/// Borrow an optional nul-terminated UTF-8 string.
+///
+/// ## Safety
+///
+/// Same as [`borrow_str`].
+unsafe fn borrow_opt_str<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, Failure> {
+    if ptr.is_null() {
+        Ok(None)
+    } else {
+        borrow_str(ptr, name).map(Some)
+    }
+}
+
+/// Parse the arguments JSON, or return empty arguments if there is none.
+fn parse_arguments(json: Option<&str>) -> Result<Arguments, Failure> {
+    match json {
+        None => Ok(Arguments::default()),
+        Some(s) => serde_json::from_str::<Arguments>(s)
+            .map_err(|error| Failure::new(SimplicityHlStatus::ArgumentsJson, error)),
+    }
+}
+
+/// Parse the witness JSON.
+fn parse_witness(json: &str) -> Result<WitnessValues, Failure> {
+    serde_json::from_str::<WitnessValues>(json)
+        .map_err(|error| Failure::new(SimplicityHlStatus::WitnessJson, error))
+}
+
+/// Write `value` into the out-parameter `out`.
+///
+/// ## Safety
+///
+/// `out` is either null or valid for writes.
+unsafe fn write_out<T>(out: *mut T, value: T, name: &str) -> Result<(), Failure> {
+    if out.is_null() {
+        return Err(Failure::null_pointer(name));
+    }
+    out.write(value);
+    Ok(())
+}
+
+/// Borrow the value behind an opaque handle.
+///
+/// ## Safety
+///
+/// `ptr` is either null or was returned by this library and not yet freed.
+unsafe fn borrow_handle<'a, T>(ptr: *const T, name: &str) -> Result<&'a T, Failure> {
+    ptr.as_ref().ok_or_else(|| Failure::null_pointer(name))
+}
+
+/// Release the message and code of an error and reset it to zeros.
+///
+/// ## Safety
+///
+/// `error` is either null or points to an error that was initialized with zeros
+/// or filled by this library.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_error_clear(error: *mut SimplicityHlError) {
+    if let Some(error) = error.as_mut() {
+        if !error.message.is_null() {
+            drop(CString::from_raw(error.message));
+        }
+        if !error.code.is_null() {
+            drop(CString::from_raw(error.code));
+        }
+        *error = SimplicityHlError {
+            status: SimplicityHlStatus::Ok,
+            message: ptr::null_mut(),
+            code: ptr::null_mut(),
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+        };
+    }
+}
+
+/// Release a byte buffer and reset it to empty.
+///
+/// ## Safety
+///
+/// `bytes` is either null or points to a buffer that was filled by this library.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_bytes_free(bytes: *mut SimplicityHlBytes) {
+    if let Some(bytes) = bytes.as_mut() {
+        if !bytes.data.is_null() {
+            let slice = ptr::slice_from_raw_parts_mut(bytes.data, bytes.len);
+            drop(Box::from_raw(slice));
+        }
+        *bytes = SimplicityHlBytes::empty();
+    }
+}
+
+/// Parse and type-check the template of a SimplicityHL program.
+///
+/// ## Safety
+///
+/// - `source` is a nul-terminated string.
+/// - `out` is valid for writes.
+/// - `error` is either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_template_new(
+    source: *const c_char,
+    out: *mut *mut SimplicityHlTemplate,
+    error: *mut SimplicityHlError,
+) -> SimplicityHlStatus {
+    guard(error, || {
+        let source = borrow_str(source, "source")?;
+        let template = TemplateProgram::new(source)
+            .map_err(|error| Failure::from_error(SimplicityHlStatus::Parse, &error))?;
+        let handle = Box::into_raw(Box::new(SimplicityHlTemplate(template)));
+        write_out(out, handle, "out").inspect_err(|_| drop(Box::from_raw(handle)))
+    })
+}
+
+/// Release a template.
+///
+/// ## Safety
+///
+/// `template` is either null or was returned by this library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_template_free(template: *mut SimplicityHlTemplate) {
+    if !template.is_null() {
+        drop(Box::from_raw(template));
+    }
+}
+
+/// Instantiate a template with the given arguments.
+///
+/// `arguments_json` uses the format of `.args` files.
+/// Pass null if the program has no parameters.
+///
+/// ## Safety
+///
+/// - `template` was returned by this library and not yet freed.
+/// - `arguments_json` is either null or a nul-terminated string.
+/// - `out` is valid for writes.
+/// - `error` is either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_template_instantiate(
+    template: *const SimplicityHlTemplate,
+    arguments_json: *const c_char,
+    include_debug_symbols: bool,
+    out: *mut *mut SimplicityHlCompiled,
+    error: *mut SimplicityHlError,
+) -> SimplicityHlStatus {
+    guard(error, || {
+        let template = borrow_handle(template, "template")?;
+        let arguments = parse_arguments(borrow_opt_str(arguments_json, "arguments_json")?)?;
+        let compiled = template
+            .0
+            .instantiate(arguments, include_debug_symbols)
+            .map_err(|error| Failure::from_error(SimplicityHlStatus::Compile, &error))?;
+        let handle = Box::into_raw(Box::new(SimplicityHlCompiled(compiled)));
+        write_out(out, handle, "out").inspect_err(|_| drop(Box::from_raw(handle)))
+    })
+}
+
+/// Parse, type-check and compile a SimplicityHL program.
+///
+/// Shorthand for [`simplicityhl_template_new`] followed by [`simplicityhl_template_instantiate`].
+///
+/// ## Safety
+///
+/// - `source` is a nul-terminated string.
+/// - `arguments_json` is either null or a nul-terminated string.
+/// - `out` is valid for writes.
+/// - `error` is either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_compile(
+    source: *const c_char,
+    arguments_json: *const c_char,
+    include_debug_symbols: bool,
+    out: *mut *mut SimplicityHlCompiled,
+    error: *mut SimplicityHlError,
+) -> SimplicityHlStatus {
+    guard(error, || {
+        let source = borrow_str(source, "source")?;
+        let arguments = parse_arguments(borrow_opt_str(arguments_json, "arguments_json")?)?;
+        let template = TemplateProgram::new(source)
+            .map_err(|error| Failure::from_error(SimplicityHlStatus::Parse, &error))?;
+        let compiled = template
+            .instantiate(arguments, include_debug_symbols)
+            .map_err(|error| Failure::from_error(SimplicityHlStatus::Compile, &error))?;
+        let handle = Box::into_raw(Box::new(SimplicityHlCompiled(compiled)));
+        write_out(out, handle, "out").inspect_err(|_| drop(Box::from_raw(handle)))
+    })
+}
+
+/// Release a compiled program.
+///
+/// ## Safety
+///
+/// `compiled` is either null or was returned by this library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_compiled_free(compiled: *mut SimplicityHlCompiled) {
+    if !compiled.is_null() {
+        drop(Box::from_raw(compiled));
+    }
+}
+
+/// Write the 32-byte commitment Merkle root (CMR) of a compiled program into `out`.
+///
+/// ## Safety
+///
+/// - `compiled` was returned by this library and not yet freed.
+/// - `out` is valid for writes of 32 bytes.
+/// - `error` is either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_compiled_cmr(
+    compiled: *const SimplicityHlCompiled,
+    out: *mut [u8; 32],
+    error: *mut SimplicityHlError,
+) -> SimplicityHlStatus {
+    guard(error, || {
+        let compiled = borrow_handle(compiled, "compiled")?;
+        let cmr = compiled.0.commit().cmr().to_byte_array();
+        write_out(out, cmr, "out")
+    })
+}
+
+/// Write the encoding of a compiled program, without witness data, into `out`.
+///
+/// ## Safety
+///
+/// - `compiled` was returned by this library and not yet freed.
+/// - `out` is valid for writes.
+/// - `error` is either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_compiled_program_bytes(
+    compiled: *const SimplicityHlCompiled,
+    out: *mut SimplicityHlBytes,
+    error: *mut SimplicityHlError,
+) -> SimplicityHlStatus {
+    guard(error, || {
+        let compiled = borrow_handle(compiled, "compiled")?;
+        let bytes = compiled.0.commit().to_vec_without_witness();
+        write_out(out, SimplicityHlBytes::from_vec(bytes), "out")
+    })
+}
+
+/// Satisfy a compiled program with the given witness values.
+///
+/// `witness_json` uses the format of `.wit` files.
+///
+/// ## Safety
+///
+/// - `compiled` was returned by this library and not yet freed.
+/// - `witness_json` is a nul-terminated string.
+/// - `out` is valid for writes.
+/// - `error` is either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_compiled_satisfy(
+    compiled: *const SimplicityHlCompiled,
+    witness_json: *const c_char,
+    out: *mut *mut SimplicityHlSatisfied,
+    error: *mut SimplicityHlError,
+) -> SimplicityHlStatus {
+    guard(error, || {
+        let compiled = borrow_handle(compiled, "compiled")?;
+        let witness_values = parse_witness(borrow_str(witness_json, "witness_json")?)?;
+        let satisfied = compiled
+            .0
+            .satisfy(witness_values)
+            .map_err(|error| Failure::from_error(SimplicityHlStatus::Satisfy, &error))?;
+        let handle = Box::into_raw(Box::new(SimplicityHlSatisfied(satisfied)));
+        write_out(out, handle, "out").inspect_err(|_| drop(Box::from_raw(handle)))
+    })
+}
+
+/// Release a satisfied program.
+///
+/// ## Safety
+///
+/// `satisfied` is either null or was returned by this library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_satisfied_free(satisfied: *mut SimplicityHlSatisfied) {
+    if !satisfied.is_null() {
+        drop(Box::from_raw(satisfied));
+    }
+}
+
+/// Write the 32-byte commitment Merkle root (CMR) of a satisfied program into `out`.
+///
+/// ## Safety
+///
+/// - `satisfied` was returned by this library and not yet freed.
+/// - `out` is valid for writes of 32 bytes.
+/// - `error` is either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_satisfied_cmr(
+    satisfied: *const SimplicityHlSatisfied,
+    out: *mut [u8; 32],
+    error: *mut SimplicityHlError,
+) -> SimplicityHlStatus {
+    guard(error, || {
+        let satisfied = borrow_handle(satisfied, "satisfied")?;
+        let cmr = satisfied.0.redeem().cmr().to_byte_array();
+        write_out(out, cmr, "out")
+    })
+}
+
+/// Write the encodings of a satisfied program and its witness data
+/// into `program_out` and `witness_out`, respectively.
+///
+/// ## Safety
+///
+/// - `satisfied` was returned by this library and not yet freed.
+/// - `program_out` and `witness_out` are valid for writes.
+/// - `error` is either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn simplicityhl_satisfied_bytes(
+    satisfied: *const SimplicityHlSatisfied,
+    program_out: *mut SimplicityHlBytes,
+    witness_out: *mut SimplicityHlBytes,
+    error: *mut SimplicityHlError,
+) -> SimplicityHlStatus {
+    guard(error, || {
+        let satisfied = borrow_handle(satisfied, "satisfied")?;
+        if program_out.is_null() {
+            return Err(Failure::null_pointer("program_out"));
+        }
+        if witness_out.is_null() {
+            return Err(Failure::null_pointer("witness_out"));
+        }
+        let (program_bytes, witness_bytes) = satisfied.0.redeem().to_vec_with_witness();
+        write_out(
+            program_out,
+            SimplicityHlBytes::from_vec(program_bytes),
+            "program_out",
+        )?;
+        write_out(
+            witness_out,
+            SimplicityHlBytes::from_vec(witness_bytes),
+            "witness_out",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = r#"fn main() {
+    assert!(jet::eq_32(witness::A, param::B));
+}"#;
+    const ARGUMENTS: &str = r#"{ "B": { "value": "42", "type": "u32" } }"#;
+    const WITNESS: &str = r#"{ "A": { "value": "42", "type": "u32" } }"#;
+
+    fn new_error() -> SimplicityHlError {
+        SimplicityHlError {
+            status: SimplicityHlStatus::Ok,
+            message: ptr::null_mut(),
+            code: ptr::null_mut(),
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+        }
+    }
+
+    #[test]
+    fn compile_satisfy_roundtrip() {
+        let source = CString::new(PROGRAM).unwrap();
+        let arguments = CString::new(ARGUMENTS).unwrap();
+        let witness = CString::new(WITNESS).unwrap();
+        let mut error = new_error();
+
+        unsafe {
+            let mut compiled = ptr::null_mut();
+            let status = simplicityhl_compile(
+                source.as_ptr(),
+                arguments.as_ptr(),
+                false,
+                &mut compiled,
+                &mut error,
+            );
+            assert_eq!(status, SimplicityHlStatus::Ok);
+
+            let mut cmr = [0u8; 32];
+            let status = simplicityhl_compiled_cmr(compiled, &mut cmr, &mut error);
+            assert_eq!(status, SimplicityHlStatus::Ok);
+
+            let mut satisfied = ptr::null_mut();
+            let status = simplicityhl_compiled_satisfy(
+                compiled,
+                witness.as_ptr(),
+                &mut satisfied,
+                &mut error,
+            );
+            assert_eq!(status, SimplicityHlStatus::Ok);
+
+            let mut satisfied_cmr = [0u8; 32];
+            let status = simplicityhl_satisfied_cmr(satisfied, &mut satisfied_cmr, &mut error);
+            assert_eq!(status, SimplicityHlStatus::Ok);
+            assert_eq!(cmr, satisfied_cmr);
+
+            let mut program = SimplicityHlBytes::empty();
+            let mut witness = SimplicityHlBytes::empty();
+            let status =
+                simplicityhl_satisfied_bytes(satisfied, &mut program, &mut witness, &mut error);
+            assert_eq!(status, SimplicityHlStatus::Ok);
+            assert!(0 < program.len);
+
+            simplicityhl_bytes_free(&mut program);
+            simplicityhl_bytes_free(&mut witness);
+            simplicityhl_satisfied_free(satisfied);
+            simplicityhl_compiled_free(compiled);
+        }
+    }
+
+    #[test]
+    fn structured_errors() {
+        let source = CString::new("fn main() { let x: u32 = 1 }").unwrap();
+        let mut error = new_error();
+
+        unsafe {
+            let mut template = ptr::null_mut();
+            let status = simplicityhl_template_new(source.as_ptr(), &mut template, &mut error);
+            assert_eq!(status, SimplicityHlStatus::Parse);
+            assert_eq!(error.status, SimplicityHlStatus::Parse);
+            assert!(!error.message.is_null());
+            assert_eq!(CStr::from_ptr(error.code).to_str().unwrap(), "E0007");
+            assert_eq!(error.start_line, 1);
+            assert!(0 < error.start_col && error.start_col <= error.end_col);
+            assert!(template.is_null());
+
+            // Reusing the error releases the previous strings and resets the location
+            let status = simplicityhl_template_new(ptr::null(), &mut template, &mut error);
+            assert_eq!(status, SimplicityHlStatus::NullPointer);
+            assert!(error.code.is_null());
+            assert_eq!(error.start_line, 0);
+            simplicityhl_error_clear(&mut error);
+            assert!(error.message.is_null());
+
+            let source = CString::new(PROGRAM).unwrap();
+            let status = simplicityhl_template_new(source.as_ptr(), &mut template, &mut error);
+            assert_eq!(status, SimplicityHlStatus::Ok);
+
+            let bad_arguments = CString::new("{").unwrap();
+            let mut compiled = ptr::null_mut();
+            let status = simplicityhl_template_instantiate(
+                template,
+                bad_arguments.as_ptr(),
+                false,
+                &mut compiled,
+                &mut error,
+            );
+            assert_eq!(status, SimplicityHlStatus::ArgumentsJson);
+            simplicityhl_error_clear(&mut error);
+
+            let status = simplicityhl_template_instantiate(
+                template,
+                ptr::null(),
+                false,
+                &mut compiled,
+                &mut error,
+            );
+            assert_eq!(status, SimplicityHlStatus::Compile);
+            simplicityhl_error_clear(&mut error);
+
+            simplicityhl_template_free(template);
+        }
+    }
+}
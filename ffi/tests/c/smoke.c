@@ -0,0 +1,76 @@
+/*
+ * Compile, satisfy and encode a program through the C interface.
+ *
+ * Exits with status 0 on success.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "simplicityhl.h"
+
+static const char *PROGRAM =
+    "fn main() {\n"
+    "    assert!(jet::eq_32(witness::A, param::B));\n"
+    "}\n";
+static const char *ARGUMENTS = "{ \"B\": { \"value\": \"42\", \"type\": \"u32\" } }";
+static const char *WITNESS = "{ \"A\": { \"value\": \"42\", \"type\": \"u32\" } }";
+static const char *BAD_WITNESS = "{ \"A\": { \"value\": \"42\", \"type\": \"u16\" } }";
+
+#define CHECK(cond)                                                        \
+    do {                                                                   \
+        if (!(cond)) {                                                     \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,         \
+                    __LINE__, #cond);                                      \
+            return 1;                                                      \
+        }                                                                  \
+    } while (0)
+
+int main(void) {
+    SimplicityHlError error = {0};
+    SimplicityHlTemplate *template_ = NULL;
+    SimplicityHlCompiled *compiled = NULL;
+    SimplicityHlSatisfied *satisfied = NULL;
+    SimplicityHlBytes program = {0};
+    SimplicityHlBytes witness = {0};
+    uint8_t compiled_cmr[32];
+    uint8_t satisfied_cmr[32];
+
+    CHECK(simplicityhl_template_new(PROGRAM, &template_, &error) == SIMPLICITYHL_OK);
+    CHECK(simplicityhl_template_instantiate(template_, ARGUMENTS, false, &compiled, &error) == SIMPLICITYHL_OK);
+    simplicityhl_template_free(template_);
+
+    CHECK(simplicityhl_compiled_cmr(compiled, compiled_cmr, &error) == SIMPLICITYHL_OK);
+    CHECK(simplicityhl_compiled_program_bytes(compiled, &program, &error) == SIMPLICITYHL_OK);
+    CHECK(program.data != NULL && program.len > 0);
+    simplicityhl_bytes_free(&program);
+    CHECK(program.data == NULL && program.len == 0);
+
+    CHECK(simplicityhl_compiled_satisfy(compiled, BAD_WITNESS, &satisfied, &error) == SIMPLICITYHL_SATISFY);
+    CHECK(error.status == SIMPLICITYHL_SATISFY);
+    CHECK(error.message != NULL && strlen(error.message) > 0);
+    simplicityhl_error_clear(&error);
+    CHECK(error.message == NULL);
+
+    CHECK(simplicityhl_compiled_satisfy(compiled, WITNESS, &satisfied, &error) == SIMPLICITYHL_OK);
+    CHECK(simplicityhl_satisfied_cmr(satisfied, satisfied_cmr, &error) == SIMPLICITYHL_OK);
+    CHECK(memcmp(compiled_cmr, satisfied_cmr, sizeof(compiled_cmr)) == 0);
+    CHECK(simplicityhl_satisfied_bytes(satisfied, &program, &witness, &error) == SIMPLICITYHL_OK);
+    CHECK(program.len > 0);
+
+    simplicityhl_bytes_free(&program);
+    simplicityhl_bytes_free(&witness);
+    simplicityhl_satisfied_free(satisfied);
+    simplicityhl_compiled_free(compiled);
+
+    CHECK(simplicityhl_compile("fn main() {", NULL, false, &compiled, &error) == SIMPLICITYHL_PARSE);
+    CHECK(error.code != NULL && strcmp(error.code, "E0007") == 0);
+    CHECK(error.start_line == 1 && error.start_col > 0);
+    /* Reuse the error without clearing it in between */
+    CHECK(simplicityhl_compile(NULL, NULL, false, &compiled, &error) == SIMPLICITYHL_NULL_POINTER);
+    CHECK(error.code == NULL && error.start_line == 0);
+    simplicityhl_error_clear(&error);
+    CHECK(simplicityhl_compile("fn main() {", NULL, false, &compiled, NULL) == SIMPLICITYHL_PARSE);
+
+    return 0;
+}
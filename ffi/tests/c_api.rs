@@ -0,0 +1,74 @@
+//! Build the C smoke test against the shared library and run it.
+//!
+//! The test requires a C compiler, so it is ignored by default. Run it with
+//!
+//! ```text
+//! cargo build -p simplicityhl-ffi
+//! cargo test -p simplicityhl-ffi --test c_api -- --ignored
+//! ```
+//!
+//! The C compiler is taken from the environment variable `CC`, or `cc` by default.
+//! The shared library is looked up in the directory `SIMPLICITYHL_FFI_LIB_DIR`, if it is set.
+//! Otherwise, it is looked up in the profile directory of the cargo target directory,
+//! which is `CARGO_TARGET_DIR` if it is set and the `target` directory of the workspace otherwise.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the cargo profile that this test was built with.
+const PROFILE: &str = if cfg!(debug_assertions) {
+    "debug"
+} else {
+    "release"
+};
+
+/// Directory that contains the shared library.
+fn library_dir(manifest_dir: &Path) -> PathBuf {
+    if let Some(dir) = std::env::var_os("SIMPLICITYHL_FFI_LIB_DIR") {
+        return PathBuf::from(dir);
+    }
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| manifest_dir.join("../target"), PathBuf::from);
+    target_dir.join(PROFILE)
+}
+
+#[test]
+#[ignore = "requires a C compiler and the shared library; run with --ignored"]
+fn c_smoke_test() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = library_dir(manifest_dir);
+    let lib_name = format!(
+        "{}simplicityhl_ffi{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    assert!(
+        lib_dir.join(&lib_name).is_file(),
+        "Shared library {lib_name} not found in {}. \
+        Run `cargo build -p simplicityhl-ffi` or set SIMPLICITYHL_FFI_LIB_DIR",
+        lib_dir.display()
+    );
+    let out_dir = lib_dir.join("simplicityhl_ffi_c_test");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let exe = out_dir.join("smoke");
+
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(&compiler)
+        .arg(manifest_dir.join("tests/c/smoke.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lsimplicityhl_ffi")
+        .arg("-o")
+        .arg(&exe)
+        .status()
+        .unwrap_or_else(|error| panic!("Cannot run C compiler `{compiler}`: {error}"));
+    assert!(status.success(), "Failed to build C smoke test");
+
+    let status = Command::new(&exe).status().unwrap();
+    assert!(status.success(), "C smoke test failed");
+}
@@ -5,6 +5,7 @@ fn do_test(data: &[u8]) {
     use arbitrary::Arbitrary;
 
     use simplicityhl::error::WithFile;
+    use simplicityhl::simplicity_unchained::jets::unchained::ElementsExtension;
     use simplicityhl::{ast, named, parse, ArbitraryOfType, Arguments};
 
     let mut u = arbitrary::Unstructured::new(data);
@@ -21,7 +22,7 @@ fn do_test(data: &[u8]) {
         Err(..) => return,
     };
     let simplicity_named_construct = ast_program
-        .compile(arguments, false)
+        .compile::<ElementsExtension>(arguments, false)
         .with_file("")
         .expect("AST should compile with given arguments");
     let _simplicity_commit = named::forget_names(&simplicity_named_construct);
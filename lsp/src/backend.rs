@@ -8,25 +8,21 @@ use tokio::sync::RwLock;
 
 use tower_lsp_server::jsonrpc::Result;
 use tower_lsp_server::lsp_types::{
-    CompletionOptions, CompletionParams, CompletionResponse, Diagnostic,
-    DidChangeConfigurationParams, DidChangeTextDocumentParams, DidChangeWatchedFilesParams,
-    DidChangeWorkspaceFoldersParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
-    DidSaveTextDocumentParams, ExecuteCommandParams, GotoDefinitionParams, GotoDefinitionResponse,
-    Hover, HoverParams, HoverProviderCapability, InitializeParams, InitializeResult,
-    InitializedParams, Location, MarkupContent, MarkupKind, MessageType, OneOf, Range,
-    ReferenceParams, SaveOptions, SemanticTokensParams, SemanticTokensResult, ServerCapabilities,
+    CodeDescription, CompletionOptions, CompletionParams, CompletionResponse, Diagnostic,
+    DiagnosticRelatedInformation, DiagnosticSeverity, DidChangeConfigurationParams,
+    DidChangeTextDocumentParams, DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    ExecuteCommandParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams,
+    HoverProviderCapability, InitializeParams, InitializeResult, InitializedParams, Location,
+    MarkupContent, MarkupKind, MessageType, NumberOrString, OneOf, Range, ReferenceParams,
+    SaveOptions, SemanticTokensParams, SemanticTokensResult, ServerCapabilities,
     TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
     TextDocumentSyncSaveOptions, Uri, WorkDoneProgressOptions, WorkspaceFoldersServerCapabilities,
     WorkspaceServerCapabilities,
 };
 use tower_lsp_server::{Client, LanguageServer};
 
-use simplicityhl::{
-    ast,
-    error::{RichError, WithFile},
-    parse,
-    parse::ParseFromStr,
-};
+use simplicityhl::{diagnostic, parse, parse::ParseFromStr};
 
 use crate::completion::{self, CompletionProvider};
 use crate::error::LspError;
@@ -203,7 +199,7 @@ impl LanguageServer for Backend {
 
         let description = match call.name() {
             parse::CallName::Jet(jet) => {
-                let element = simplicityhl::simplicity::jet::ElementsExtension::from_str(
+                let element = simplicityhl::simplicity_unchained::jets::unchained::ElementsExtension::from_str(
                     format!("{jet}").as_str(),
                 )
                 .map_err(|err| LspError::ConversionFailed(err.to_string()))?;
@@ -377,7 +373,7 @@ impl Backend {
 
     /// Function which executed on change of file (`did_save`, `did_open` or `did_change` methods)
     async fn on_change(&self, params: TextDocumentItem<'_>) {
-        let (err, document) = parse_program(params.text);
+        let (compiler_diagnostics, document) = parse_program(params.text);
 
         let mut documents = self.document_map.write().await;
         if let Some(doc) = document {
@@ -386,39 +382,83 @@ impl Backend {
             doc.text = Rope::from_str(params.text);
         }
 
-        match err {
-            None => {
-                self.client
-                    .publish_diagnostics(params.uri.clone(), vec![], params.version)
-                    .await;
-            }
-            Some(err) => {
-                let (start, end) = match span_to_positions(err.span()) {
-                    Ok(result) => result,
-                    Err(err) => {
-                        self.client
-                            .log_message(
-                                MessageType::ERROR,
-                                format!("Catch error while parsing span: {err}"),
-                            )
-                            .await;
-                        return;
-                    }
-                };
-
+        let diagnostics = match compiler_diagnostics
+            .iter()
+            .map(|diagnostic| to_lsp_diagnostic(diagnostic, &params.uri))
+            .collect::<std::result::Result<Vec<_>, LspError>>()
+        {
+            Ok(diagnostics) => diagnostics,
+            Err(err) => {
                 self.client
-                    .publish_diagnostics(
-                        params.uri.clone(),
-                        vec![Diagnostic::new_simple(
-                            Range::new(start, end),
-                            err.error().to_string(),
-                        )],
-                        params.version,
+                    .log_message(
+                        MessageType::ERROR,
+                        format!("Catch error while parsing span: {err}"),
                     )
                     .await;
+                return;
             }
+        };
+
+        self.client
+            .publish_diagnostics(params.uri.clone(), diagnostics, params.version)
+            .await;
+    }
+}
+
+/// Convert a compiler [`diagnostic::Diagnostic`] to an LSP [`Diagnostic`].
+fn to_lsp_diagnostic(
+    diagnostic: &diagnostic::Diagnostic,
+    uri: &Uri,
+) -> std::result::Result<Diagnostic, LspError> {
+    let range = match diagnostic.span {
+        Some(span) => {
+            let (start, end) = span_to_positions(&span)?;
+            Range::new(start, end)
         }
+        None => Range::default(),
+    };
+    let severity = match diagnostic.severity {
+        diagnostic::Severity::Error => DiagnosticSeverity::ERROR,
+        diagnostic::Severity::Warning => DiagnosticSeverity::WARNING,
+        diagnostic::Severity::Note => DiagnosticSeverity::INFORMATION,
+    };
+    let related_information = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            let (start, end) = span_to_positions(&label.span)?;
+            Ok(DiagnosticRelatedInformation {
+                location: Location::new(uri.clone(), Range::new(start, end)),
+                message: label.message.clone(),
+            })
+        })
+        .collect::<std::result::Result<Vec<_>, LspError>>()?;
+
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push_str("\n\nnote: ");
+        message.push_str(note);
     }
+    for fix in &diagnostic.fixes {
+        message.push_str(&format!("\n\nhelp: {}: `{}`", fix.message, fix.replacement));
+    }
+
+    Ok(Diagnostic {
+        range,
+        severity: Some(severity),
+        code: diagnostic
+            .code
+            .map(|code| NumberOrString::String(code.to_string())),
+        code_description: diagnostic
+            .code
+            .and_then(simplicityhl::error::explanation_url)
+            .and_then(|url| Uri::from_str(&url).ok())
+            .map(|href| CodeDescription { href }),
+        source: Some("simplicityhl".to_string()),
+        message,
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        ..Diagnostic::default()
+    })
 }
 
 /// Create [`Document`] using parsed program and code.
@@ -451,18 +491,16 @@ fn create_document(program: &simplicityhl::parse::Program, text: &str) -> Docume
     document
 }
 
-/// Parse program using [`simplicityhl`] compiler and return [`RichError`],
+/// Parse program using [`simplicityhl`] compiler and return its errors or lint warnings,
 /// which used in Diagnostic. Also create [`Document`] from parsed program.
-fn parse_program(text: &str) -> (Option<RichError>, Option<Document>) {
+fn parse_program(text: &str) -> (Vec<diagnostic::Diagnostic>, Option<Document>) {
     let program = match parse::Program::parse_from_str(text) {
         Ok(p) => p,
-        Err(e) => return (Some(e), None),
+        Err(e) => return (vec![diagnostic::Diagnostic::from(&e)], None),
     };
 
-    (
-        ast::Program::analyze(&program).with_file(text).err(),
-        Some(create_document(&program, text)),
-    )
+    let diagnostics = diagnostic::check_program(text);
+    (diagnostics, Some(create_document(&program, text)))
 }
 
 #[cfg(test)]
@@ -484,18 +522,18 @@ mod tests {
 
     #[test]
     fn test_parse_program_valid() {
-        let (err, doc) = parse_program(sample_program());
-        assert!(err.is_none(), "Expected no parsing error");
+        let (diagnostics, doc) = parse_program(sample_program());
+        assert!(diagnostics.is_empty(), "Expected no parsing error");
         let doc = doc.expect("Expected Some(Document)");
         assert_eq!(doc.functions.map.len(), 2);
     }
 
     #[test]
     fn test_parse_program_invalid_ast() {
-        let (err, doc) = parse_program(invalid_program_on_ast());
+        let (diagnostics, doc) = parse_program(invalid_program_on_ast());
         assert!(
-            err.unwrap()
-                .to_string()
+            diagnostics[0]
+                .message
                 .contains("Expected expression of type `u32`, found type `()`"),
             "Expected error on return type"
         );
@@ -504,11 +542,50 @@ mod tests {
 
     #[test]
     fn test_parse_program_invalid_parse() {
-        let (err, doc) = parse_program(invalid_program_on_parsing());
+        let (diagnostics, doc) = parse_program(invalid_program_on_parsing());
         assert!(
-            err.unwrap().to_string().contains("Grammar error"),
+            diagnostics[0].message.contains("Grammar error"),
             "Expected `Grammar error`"
         );
         assert!(doc.is_none(), "Expected no document to return");
     }
+
+    #[test]
+    fn test_parse_program_with_stdlib() {
+        let (diagnostics, doc) = parse_program(
+            "use std::script;\nfn main() { assert!(jet::eq_8(op_checksig(), 0xac)); }",
+        );
+        assert!(diagnostics.is_empty(), "Expected the module to be linked");
+        assert!(doc.is_some());
+    }
+
+    #[test]
+    fn test_lsp_diagnostic_code() {
+        let (diagnostics, _) = parse_program(invalid_program_on_ast());
+        let uri = Uri::from_str("file:///main.simf").unwrap();
+        let diagnostic = to_lsp_diagnostic(&diagnostics[0], &uri).unwrap();
+        assert_eq!(
+            diagnostic.code,
+            Some(NumberOrString::String("E0021".to_string()))
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert!(diagnostic
+            .code_description
+            .unwrap()
+            .href
+            .as_str()
+            .ends_with("/book/src/error_codes/E0021.md"));
+    }
+
+    #[test]
+    fn test_lsp_lint_warning() {
+        let (diagnostics, _) = parse_program("fn unused() {} fn main() {}");
+        let uri = Uri::from_str("file:///main.simf").unwrap();
+        let diagnostic = to_lsp_diagnostic(&diagnostics[0], &uri).unwrap();
+        assert_eq!(
+            diagnostic.code,
+            Some(NumberOrString::String("unused_functions".to_string()))
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::WARNING));
+    }
 }
@@ -3,7 +3,7 @@ use std::num::NonZero;
 use simplicityhl::{
     num::NonZeroPow2Usize,
     parse::CallName,
-    str::{AliasName, FunctionName},
+    str::{AliasName, FunctionName, WitnessName},
     types::AliasedType,
 };
 
@@ -13,6 +13,7 @@ use crate::completion::types::FunctionTemplate;
 pub fn get_builtin_functions() -> Vec<FunctionTemplate> {
     let ty = AliasedType::from(AliasName::from_str_unchecked("T"));
     let function_name = FunctionName::from_str_unchecked("fn");
+    let delegate_name = WitnessName::from_str_unchecked("NAME");
     let Some(some) = NonZero::new(1) else {
         return vec![];
     };
@@ -28,6 +29,18 @@ pub fn get_builtin_functions() -> Vec<FunctionTemplate> {
         CallName::Fold(function_name.clone(), NonZeroPow2Usize::TWO),
         CallName::ArrayFold(function_name.clone(), some),
         CallName::ForWhile(function_name.clone()),
+        CallName::Disconnect(function_name.clone(), delegate_name),
+        CallName::Sha256(some),
+        CallName::Sha256Add(some),
+        CallName::ArrayConcat(some, some),
+        CallName::ArrayMap(function_name.clone(), some),
+        CallName::ArrayZip,
+        CallName::ArrayReverse,
+        CallName::ArrayContains(ty.clone(), some),
+        CallName::ArrayGet(ty.clone(), some),
+        CallName::ListLen(ty.clone(), NonZeroPow2Usize::TWO),
+        CallName::ListPush,
+        CallName::ListGet(NonZeroPow2Usize::TWO),
         CallName::TypeCast(ty.clone()),
     ];
 
@@ -114,6 +127,87 @@ pub fn match_callname(call: &CallName) -> Option<FunctionTemplate> {
             "Either<B, A>",
             doc,
         )),
+        CallName::Disconnect(_, _) => Some(FunctionTemplate::new(
+            "disconnect",
+            vec!["f".to_string(), "NAME".to_string()],
+            vec!["input: A".to_string()],
+            "(B, D)",
+            doc,
+        )),
+        CallName::Sha256(_) => Some(FunctionTemplate::new(
+            "sha256",
+            vec!["N".to_string()],
+            vec!["bytes: [u8; N]".to_string()],
+            "u256",
+            doc,
+        )),
+        CallName::Sha256Add(_) => Some(FunctionTemplate::new(
+            "sha256_add",
+            vec!["N".to_string()],
+            vec!["hasher: Ctx8".to_string(), "bytes: [u8; N]".to_string()],
+            "Ctx8",
+            doc,
+        )),
+        CallName::ArrayConcat(_, _) => Some(FunctionTemplate::new(
+            "array_concat",
+            vec!["N".to_string(), "M".to_string()],
+            vec!["left: [E; N]".to_string(), "right: [E; M]".to_string()],
+            "[E; N + M]",
+            doc,
+        )),
+        CallName::ArrayMap(_, _) => Some(FunctionTemplate::new(
+            "array_map",
+            vec!["f".to_string(), "N".to_string()],
+            vec!["array: [A; N]".to_string()],
+            "[B; N]",
+            doc,
+        )),
+        CallName::ArrayZip => Some(FunctionTemplate::simple(
+            "array_zip",
+            vec!["left: [A; N]".to_string(), "right: [B; N]".to_string()],
+            "[(A, B); N]",
+            doc,
+        )),
+        CallName::ArrayReverse => Some(FunctionTemplate::simple(
+            "array_reverse",
+            vec!["array: [E; N]".to_string()],
+            "[E; N]",
+            doc,
+        )),
+        CallName::ArrayContains(_, _) => Some(FunctionTemplate::new(
+            "array_contains",
+            vec!["T".to_string(), "N".to_string()],
+            vec!["array: [T; N]".to_string(), "element: T".to_string()],
+            "bool",
+            doc,
+        )),
+        CallName::ArrayGet(_, _) => Some(FunctionTemplate::new(
+            "array_get",
+            vec!["I".to_string(), "N".to_string()],
+            vec!["array: [E; N]".to_string(), "index: I".to_string()],
+            "Option<E>",
+            doc,
+        )),
+        CallName::ListLen(_, _) => Some(FunctionTemplate::new(
+            "list_len",
+            vec!["E".to_string(), "N".to_string()],
+            vec!["list: List<E, N>".to_string()],
+            "u32",
+            doc,
+        )),
+        CallName::ListPush => Some(FunctionTemplate::simple(
+            "list_push",
+            vec!["list: List<E, N>".to_string(), "element: E".to_string()],
+            "List<E, N>",
+            doc,
+        )),
+        CallName::ListGet(_) => Some(FunctionTemplate::new(
+            "list_get",
+            vec!["N".to_string()],
+            vec!["list: List<E, N>".to_string(), "index: u32".to_string()],
+            "Option<E>",
+            doc,
+        )),
 
         // The `into` function has a different structure compared to the other built-ins,
         // so we defined a different snippet for it.
@@ -215,6 +309,107 @@ fn main() {
     let out: Either<u8, ()> = for_while::<stop_at_10>((), ());
     assert!(jet::eq_8(10, unwrap_left::<()>(out)));
 }
+```",
+        CallName::Disconnect(_, _) =>
+    "Call a function with the CMR of a delegated program and run the delegated program on the output of the function.
+The delegated program is not part of the commitment. It is supplied under the name `NAME` when the program is satisfied.\n
+- Signature: `disconnect::<f, NAME>(input: A) -> (B, D)`
+- Function: `fn f(cmr: u256, input: A) -> (B, C)`
+- Delegated program: `C → D`\n
+Example: let the owner authorize any program by signing its CMR.\n
+```simplicityhl
+fn authorize(cmr: u256, sig: Signature) -> ((), ()) {
+    jet::bip_0340_verify((param::OWNER, cmr), sig);
+    ((), ())
+}
+
+fn main() {
+    let (_, _): ((), ()) = disconnect::<authorize, DELEGATE>(witness::SIG);
+}
+```",
+        CallName::Sha256(_) =>
+    "Compute the SHA256 hash of a byte array of size `N`.\n
+- Signature: `sha256::<N>(bytes: [u8; N]) -> u256`\n
+```simplicityhl
+let hash: u256 = sha256::<3>(b\"abc\");
+assert!(jet::eq_256(hash, 0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad));
+```",
+        CallName::Sha256Add(_) =>
+    "Add a byte array of size `N` to a SHA256 hash context.\n
+- Signature: `sha256_add::<N>(hasher: Ctx8, bytes: [u8; N]) -> Ctx8`\n
+```simplicityhl
+let hasher: Ctx8 = jet::sha_256_ctx_8_init();
+let hasher: Ctx8 = sha256_add::<5>(hasher, b\"hello\");
+let hasher: Ctx8 = sha256_add::<6>(hasher, b\" world\");
+let hash: u256 = jet::sha_256_ctx_8_finalize(hasher);
+```",
+        CallName::ArrayConcat(_, _) =>
+    "Concatenate an array of size `N` and an array of size `M`.\n
+- Signature: `array_concat::<N, M>(left: [E; N], right: [E; M]) -> [E; N + M]`\n
+```simplicityhl
+let xs: [u8; 5] = array_concat::<2, 3>([1, 2], [3, 4, 5]);
+```",
+        CallName::ArrayMap(_, _) =>
+    "Apply a function to each element of an array of size `N`.\n
+- Signature: `array_map::<f, N>(array: [A; N]) -> [B; N]`
+- Map step: `fn f(element: A) -> B`\n
+```simplicityhl
+fn double(x: u8) -> u8 {
+    let (_, y): (bool, u8) = jet::add_8(x, x);
+    y
+}
+
+let ys: [u8; 3] = array_map::<double, 3>([1, 2, 3]);
+```",
+        CallName::ArrayZip =>
+    "Pair up the elements of two arrays of the same size.\n
+- Signature: `array_zip(left: [A; N], right: [B; N]) -> [(A, B); N]`\n
+```simplicityhl
+let pairs: [(u8, bool); 2] = array_zip([1, 2], [true, false]);
+```",
+        CallName::ArrayReverse =>
+    "Reverse the order of the elements of an array.\n
+- Signature: `array_reverse(array: [E; N]) -> [E; N]`\n
+```simplicityhl
+let xs: [u8; 3] = array_reverse([1, 2, 3]); // [3, 2, 1]
+```",
+        CallName::ArrayContains(_, _) =>
+    "Check if an array of integers contains a given element.\n
+- Signature: `array_contains::<T, N>(array: [T; N], element: T) -> bool`
+- Note: `T` is an unsigned integer type.\n
+```simplicityhl
+assert!(array_contains::<u8, 3>([1, 2, 3], 2));
+```",
+        CallName::ArrayGet(_, _) =>
+    "Return the element of an array at a runtime index, or `None` if the index is out of range.\n
+- Signature: `array_get::<I, N>(array: [E; N], index: I) -> Option<E>`
+- Note: `I` is an unsigned integer type.\n
+```simplicityhl
+let keys: [u8; 3] = [1, 2, 3];
+let i: u8 = witness::INDEX;
+let key: Option<u8> = array_get::<u8, 3>(keys, i);
+```",
+        CallName::ListLen(_, _) =>
+    "Return the number of elements of a list.\n
+- Signature: `list_len::<E, N>(list: List<E, N>) -> u32`\n
+```simplicityhl
+let xs: List<u8, 4> = list![1, 2, 3];
+assert!(jet::eq_32(list_len::<u8, 4>(xs), 3));
+```",
+        CallName::ListPush =>
+    "Append an element to the end of a list. Panics if the list is full.\n
+- Signature: `list_push(list: List<E, N>, element: E) -> List<E, N>`
+- Note: A list of bound `N` holds at most `N - 1` elements.\n
+```simplicityhl
+let xs: List<u8, 4> = list![1, 2];
+let xs: List<u8, 4> = list_push(xs, 3);
+```",
+        CallName::ListGet(_) =>
+    "Return the element of a list at the given index, or `None` if the index is out of range.\n
+- Signature: `list_get::<N>(list: List<E, N>, index: u32) -> Option<E>`\n
+```simplicityhl
+let xs: List<u8, 4> = list![1, 2, 3];
+let x: Option<u8> = list_get::<4>(xs, 1); // Some(2)
 ```",
         CallName::TypeCast(_) => type_casting_documentation(),
         CallName::Jet(_) | CallName::Custom(_) => "",
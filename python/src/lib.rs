@@ -0,0 +1,504 @@
+//! Python bindings for the SimplicityHL compiler.
+//!
+//! The module is built with [maturin](https://www.maturin.rs) and imported as `simplicityhl`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use simplicityhl::elements;
+use simplicityhl::elements::hex::FromHex;
+use simplicityhl::parse::ParseFromStr;
+use simplicityhl::simplicity_unchained::jets::environments::UnchainedEnv;
+use simplicityhl::str::WitnessName;
+use simplicityhl::tracker::DefaultTracker;
+use simplicityhl::{dummy_env, Arguments, ResolvedType, Value, WitnessValues};
+
+create_exception!(
+    simplicityhl,
+    SimplicityHlError,
+    PyException,
+    "Error raised by the SimplicityHL compiler."
+);
+
+fn to_py_err<E: ToString>(error: E) -> PyErr {
+    SimplicityHlError::new_err(error.to_string())
+}
+
+/// A SimplicityHL type without aliases.
+#[pyclass(name = "ResolvedType", module = "simplicityhl", frozen, eq)]
+#[derive(Clone, PartialEq, Eq)]
+struct PyResolvedType(ResolvedType);
+
+#[pymethods]
+impl PyResolvedType {
+    /// Parse a type from a string, such as `(u32, bool)`.
+    #[staticmethod]
+    fn parse(s: &str) -> PyResult<Self> {
+        ResolvedType::parse_from_str(s).map(Self).map_err(to_py_err)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ResolvedType('{}')", self.0)
+    }
+}
+
+/// A SimplicityHL value together with its type.
+#[pyclass(name = "Value", module = "simplicityhl", frozen, eq)]
+#[derive(Clone, PartialEq, Eq)]
+struct PyValue(Value);
+
+#[pymethods]
+impl PyValue {
+    /// Parse a value of the given type from a string, such as `(42, true)`.
+    #[staticmethod]
+    fn parse(s: &str, ty: &PyResolvedType) -> PyResult<Self> {
+        Value::parse_from_str(s, &ty.0).map(Self).map_err(to_py_err)
+    }
+
+    /// The type of the value.
+    #[getter]
+    fn ty(&self) -> PyResolvedType {
+        PyResolvedType(self.0.ty().clone())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Value('{}', '{}')", self.0, self.0.ty())
+    }
+}
+
+fn name_value_map(values: HashMap<String, PyValue>) -> PyResult<HashMap<WitnessName, Value>> {
+    values
+        .into_iter()
+        .map(|(name, value)| {
+            WitnessName::parse_from_str(&name)
+                .map(|name| (name, value.0))
+                .map_err(to_py_err)
+        })
+        .collect()
+}
+
+/// Values of the witness module of a program.
+#[pyclass(name = "WitnessValues", module = "simplicityhl", frozen)]
+#[derive(Clone)]
+struct PyWitnessValues(WitnessValues);
+
+#[pymethods]
+impl PyWitnessValues {
+    /// Create witness values from a map of names to values.
+    #[new]
+    #[pyo3(signature = (values=HashMap::new()))]
+    fn new(values: HashMap<String, PyValue>) -> PyResult<Self> {
+        name_value_map(values).map(|map| Self(WitnessValues::from(map)))
+    }
+
+    /// Parse witness values in the format of `.wit` files.
+    #[staticmethod]
+    fn from_json(s: &str) -> PyResult<Self> {
+        serde_json::from_str::<WitnessValues>(s)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// Serialize the witness values in the format of `.wit` files.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.0).map_err(to_py_err)
+    }
+
+    fn __getitem__(&self, name: &str) -> PyResult<PyValue> {
+        let name = WitnessName::parse_from_str(name).map_err(to_py_err)?;
+        self.0
+            .get(&name)
+            .cloned()
+            .map(PyValue)
+            .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(name.to_string()))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Arguments for the parameters of a template program.
+#[pyclass(name = "Arguments", module = "simplicityhl", frozen)]
+#[derive(Clone)]
+struct PyArguments(Arguments);
+
+#[pymethods]
+impl PyArguments {
+    /// Create arguments from a map of names to values.
+    #[new]
+    #[pyo3(signature = (values=HashMap::new()))]
+    fn new(values: HashMap<String, PyValue>) -> PyResult<Self> {
+        name_value_map(values).map(|map| Self(Arguments::from(map)))
+    }
+
+    /// Parse arguments in the format of `.args` files.
+    #[staticmethod]
+    fn from_json(s: &str) -> PyResult<Self> {
+        serde_json::from_str::<Arguments>(s)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// Serialize the arguments in the format of `.args` files.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.0).map_err(to_py_err)
+    }
+
+    fn __getitem__(&self, name: &str) -> PyResult<PyValue> {
+        let name = WitnessName::parse_from_str(name).map_err(to_py_err)?;
+        self.0
+            .get(&name)
+            .cloned()
+            .map(PyValue)
+            .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(name.to_string()))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Description of the transaction environment, in JSON.
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EnvironmentConfig {
+    #[serde(default)]
+    lock_time: u32,
+    #[serde(default = "max_sequence")]
+    sequence: u32,
+    #[serde(default)]
+    include_fee_output: bool,
+    #[serde(default)]
+    script: Option<String>,
+}
+
+fn max_sequence() -> u32 {
+    u32::MAX
+}
+
+/// Transaction environment for executing programs.
+///
+/// The environment is based on [`dummy_env`].
+#[pyclass(name = "Environment", module = "simplicityhl", frozen)]
+#[derive(Clone)]
+struct PyEnvironment {
+    lock_time: u32,
+    sequence: u32,
+    include_fee_output: bool,
+    script: elements::Script,
+}
+
+#[pymethods]
+impl PyEnvironment {
+    /// Create a dummy environment with the given lock time, sequence
+    /// and companion Bitcoin script.
+    ///
+    /// The script is given in hex. It defaults to [`dummy_env::default_script`].
+    #[new]
+    #[pyo3(signature = (lock_time=0, sequence=u32::MAX, include_fee_output=false, script=None))]
+    fn new(
+        lock_time: u32,
+        sequence: u32,
+        include_fee_output: bool,
+        script: Option<&str>,
+    ) -> PyResult<Self> {
+        let script = match script {
+            Some(hex) => elements::Script::from_hex(hex)
+                .map_err(|error| to_py_err(format!("Invalid script: {error}")))?,
+            None => dummy_env::default_script(),
+        };
+        Ok(Self {
+            lock_time,
+            sequence,
+            include_fee_output,
+            script,
+        })
+    }
+
+    /// Parse an environment from JSON.
+    ///
+    /// All fields are optional:
+    /// `{"lock_time": 0, "sequence": 4294967295, "include_fee_output": false, "script": "51"}`
+    #[staticmethod]
+    fn from_json(s: &str) -> PyResult<Self> {
+        let config = serde_json::from_str::<EnvironmentConfig>(s).map_err(to_py_err)?;
+        Self::new(
+            config.lock_time,
+            config.sequence,
+            config.include_fee_output,
+            config.script.as_deref(),
+        )
+    }
+
+    /// The companion Bitcoin script, in hex.
+    #[getter]
+    fn script(&self) -> String {
+        format!("{:x}", self.script)
+    }
+}
+
+impl PyEnvironment {
+    fn build(&self) -> UnchainedEnv {
+        dummy_env::dummy_with_script(
+            elements::LockTime::from_consensus(self.lock_time),
+            elements::Sequence::from_consensus(self.sequence),
+            self.include_fee_output,
+            self.script.clone(),
+        )
+    }
+}
+
+impl Default for PyEnvironment {
+    fn default() -> Self {
+        Self {
+            lock_time: 0,
+            sequence: u32::MAX,
+            include_fee_output: false,
+            script: dummy_env::default_script(),
+        }
+    }
+}
+
+/// The template of a SimplicityHL program.
+#[pyclass(name = "TemplateProgram", module = "simplicityhl", frozen)]
+struct PyTemplateProgram(simplicityhl::TemplateProgram);
+
+#[pymethods]
+impl PyTemplateProgram {
+    /// Parse the template of a SimplicityHL program.
+    #[new]
+    fn new(source: &str) -> PyResult<Self> {
+        simplicityhl::TemplateProgram::new(source)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// The parameters of the program, mapping names to types.
+    #[getter]
+    fn parameters(&self) -> HashMap<String, PyResolvedType> {
+        self.0
+            .parameters()
+            .iter()
+            .map(|(name, ty)| (name.to_string(), PyResolvedType(ty.clone())))
+            .collect()
+    }
+
+    /// Instantiate the template with the given arguments.
+    #[pyo3(signature = (arguments=None, include_debug_symbols=false))]
+    fn instantiate(
+        &self,
+        arguments: Option<&PyArguments>,
+        include_debug_symbols: bool,
+    ) -> PyResult<PyCompiledProgram> {
+        let arguments = arguments.map(|x| x.0.clone()).unwrap_or_default();
+        self.0
+            .instantiate(arguments, include_debug_symbols)
+            .map(PyCompiledProgram)
+            .map_err(to_py_err)
+    }
+}
+
+/// A SimplicityHL program, compiled to Simplicity.
+#[pyclass(name = "CompiledProgram", module = "simplicityhl", frozen)]
+struct PyCompiledProgram(simplicityhl::CompiledProgram);
+
+#[pymethods]
+impl PyCompiledProgram {
+    /// Parse and compile a SimplicityHL program.
+    #[new]
+    #[pyo3(signature = (source, arguments=None, include_debug_symbols=false))]
+    fn new(
+        source: &str,
+        arguments: Option<&PyArguments>,
+        include_debug_symbols: bool,
+    ) -> PyResult<Self> {
+        let arguments = arguments.map(|x| x.0.clone()).unwrap_or_default();
+        simplicityhl::CompiledProgram::new(source, arguments, include_debug_symbols)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// The commitment Merkle root of the program.
+    #[getter]
+    fn cmr<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.0.commit().cmr().to_byte_array())
+    }
+
+    /// The encoding of the program, without witness data.
+    fn program_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.0.commit().to_vec_without_witness())
+    }
+
+    /// Satisfy the program with the given witness values.
+    ///
+    /// If an environment is given, then the program is pruned in this environment.
+    #[pyo3(signature = (witness_values=None, env=None))]
+    fn satisfy(
+        &self,
+        witness_values: Option<&PyWitnessValues>,
+        env: Option<&PyEnvironment>,
+    ) -> PyResult<PySatisfiedProgram> {
+        let witness_values = witness_values.map(|x| x.0.clone()).unwrap_or_default();
+        let env = env.map(PyEnvironment::build);
+        self.0
+            .satisfy_with_env(witness_values, env.as_ref())
+            .map(PySatisfiedProgram)
+            .map_err(to_py_err)
+    }
+}
+
+/// Outcome of executing a satisfied program.
+#[pyclass(name = "ExecutionResult", module = "simplicityhl", frozen, get_all)]
+struct PyExecutionResult {
+    /// Whether the program executed successfully.
+    success: bool,
+    /// Error message if the program failed.
+    error: Option<String>,
+    /// Output of `dbg!` calls as pairs of expression text and value.
+    debug: Vec<(String, String)>,
+    /// Jet calls as triples of jet name, arguments (if decoded) and result (if successful).
+    jets: Vec<(String, Option<Vec<String>>, Option<String>)>,
+    /// Warnings that were emitted during execution.
+    warnings: Vec<String>,
+}
+
+#[pymethods]
+impl PyExecutionResult {
+    fn __repr__(&self) -> String {
+        match &self.error {
+            None => "ExecutionResult(success=True)".to_string(),
+            Some(error) => format!("ExecutionResult(success=False, error='{error}')"),
+        }
+    }
+}
+
+/// A SimplicityHL program, compiled to Simplicity and satisfied with witness data.
+#[pyclass(name = "SatisfiedProgram", module = "simplicityhl", frozen)]
+struct PySatisfiedProgram(simplicityhl::SatisfiedProgram);
+
+#[pymethods]
+impl PySatisfiedProgram {
+    /// Parse, compile and satisfy a SimplicityHL program.
+    #[new]
+    #[pyo3(signature = (source, arguments=None, witness_values=None, include_debug_symbols=false))]
+    fn new(
+        source: &str,
+        arguments: Option<&PyArguments>,
+        witness_values: Option<&PyWitnessValues>,
+        include_debug_symbols: bool,
+    ) -> PyResult<Self> {
+        let arguments = arguments.map(|x| x.0.clone()).unwrap_or_default();
+        let witness_values = witness_values.map(|x| x.0.clone()).unwrap_or_default();
+        simplicityhl::SatisfiedProgram::new(
+            source,
+            arguments,
+            witness_values,
+            include_debug_symbols,
+        )
+        .map(Self)
+        .map_err(to_py_err)
+    }
+
+    /// The commitment Merkle root of the program.
+    #[getter]
+    fn cmr<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.0.redeem().cmr().to_byte_array())
+    }
+
+    /// The encoding of the program.
+    fn program_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        let (program_bytes, _) = self.0.redeem().to_vec_with_witness();
+        PyBytes::new_bound(py, &program_bytes)
+    }
+
+    /// The encoding of the witness data.
+    fn witness_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        let (_, witness_bytes) = self.0.redeem().to_vec_with_witness();
+        PyBytes::new_bound(py, &witness_bytes)
+    }
+
+    /// Execute the program in the given environment, or in the dummy environment.
+    ///
+    /// The output of `dbg!` calls requires the program to be compiled with debug symbols.
+    /// Jet calls are recorded if `trace_jets` is set.
+    #[pyo3(signature = (env=None, trace_jets=false))]
+    fn run(&self, env: Option<&PyEnvironment>, trace_jets: bool) -> PyExecutionResult {
+        let env = env.cloned().unwrap_or_default().build();
+
+        let debug = Rc::new(RefCell::new(Vec::new()));
+        let jets = Rc::new(RefCell::new(Vec::new()));
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+
+        let debug_sink = Rc::clone(&debug);
+        let warning_sink = Rc::clone(&warnings);
+        let mut tracker = DefaultTracker::new(self.0.debug_symbols())
+            .with_debug_sink(move |label, value| {
+                debug_sink
+                    .borrow_mut()
+                    .push((label.to_string(), value.to_string()));
+            })
+            .with_warning_sink(move |message| {
+                warning_sink.borrow_mut().push(message.to_string());
+            });
+        if trace_jets {
+            let jet_sink = Rc::clone(&jets);
+            tracker = tracker.with_jet_trace_sink(move |jet, args, result| {
+                jet_sink.borrow_mut().push((
+                    jet.to_string(),
+                    args.map(|args| args.iter().map(Value::to_string).collect()),
+                    result.map(|value| value.to_string()),
+                ));
+            });
+        }
+
+        let error = self
+            .0
+            .run(&env, &mut tracker)
+            .err()
+            .map(|failure| failure.to_string());
+        drop(tracker);
+
+        PyExecutionResult {
+            success: error.is_none(),
+            error,
+            debug: debug.take(),
+            jets: jets.take(),
+            warnings: warnings.take(),
+        }
+    }
+}
+
+/// Python bindings for the SimplicityHL compiler.
+#[pymodule]
+#[pyo3(name = "simplicityhl")]
+fn simplicityhl_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add(
+        "SimplicityHlError",
+        m.py().get_type_bound::<SimplicityHlError>(),
+    )?;
+    m.add_class::<PyResolvedType>()?;
+    m.add_class::<PyValue>()?;
+    m.add_class::<PyWitnessValues>()?;
+    m.add_class::<PyArguments>()?;
+    m.add_class::<PyEnvironment>()?;
+    m.add_class::<PyTemplateProgram>()?;
+    m.add_class::<PyCompiledProgram>()?;
+    m.add_class::<PySatisfiedProgram>()?;
+    m.add_class::<PyExecutionResult>()?;
+    Ok(())
+}
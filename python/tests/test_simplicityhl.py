@@ -0,0 +1,73 @@
+import pytest
+
+import simplicityhl
+
+PROGRAM = """
+fn main() {
+    let x: u32 = dbg!(witness::A);
+    assert!(jet::eq_32(x, param::B));
+}
+"""
+
+
+def test_types_and_values():
+    ty = simplicityhl.ResolvedType.parse("(u32, bool)")
+    assert str(ty) == "(u32, bool)"
+    value = simplicityhl.Value.parse("(42, true)", ty)
+    assert str(value) == "(42, true)"
+    assert value.ty == ty
+
+    with pytest.raises(simplicityhl.SimplicityHlError):
+        simplicityhl.Value.parse("true", simplicityhl.ResolvedType.parse("u8"))
+
+
+def test_witness_json_roundtrip():
+    u32 = simplicityhl.ResolvedType.parse("u32")
+    witness = simplicityhl.WitnessValues({"A": simplicityhl.Value.parse("42", u32)})
+    parsed = simplicityhl.WitnessValues.from_json(witness.to_json())
+    assert str(parsed["A"]) == "42"
+
+
+def test_compile_satisfy_run():
+    template = simplicityhl.TemplateProgram(PROGRAM)
+    assert str(template.parameters["B"]) == "u32"
+
+    arguments = simplicityhl.Arguments.from_json('{"B": {"value": "42", "type": "u32"}}')
+    compiled = template.instantiate(arguments, include_debug_symbols=True)
+    assert len(compiled.cmr) == 32
+
+    witness = simplicityhl.WitnessValues.from_json('{"A": {"value": "42", "type": "u32"}}')
+    satisfied = compiled.satisfy(witness)
+    assert satisfied.cmr == compiled.cmr
+
+    result = satisfied.run(trace_jets=True)
+    assert result.success
+    assert result.debug == [("witness::A", "42")]
+    assert any(jet == "eq_32" for jet, _, _ in result.jets)
+
+
+def test_run_failure():
+    arguments = simplicityhl.Arguments.from_json('{"B": {"value": "42", "type": "u32"}}')
+    witness = simplicityhl.WitnessValues.from_json('{"A": {"value": "43", "type": "u32"}}')
+    satisfied = simplicityhl.SatisfiedProgram(PROGRAM, arguments, witness)
+    env = simplicityhl.Environment.from_json('{"lock_time": 0}')
+
+    result = satisfied.run(env)
+    assert not result.success
+    assert result.error is not None
+
+
+def test_environment_script():
+    env = simplicityhl.Environment.from_json('{"script": "51"}')
+    assert env.script == "51"
+    assert simplicityhl.Environment().script == simplicityhl.Environment.from_json("{}").script
+
+    satisfied = simplicityhl.SatisfiedProgram(
+        PROGRAM,
+        simplicityhl.Arguments.from_json('{"B": {"value": "42", "type": "u32"}}'),
+        simplicityhl.WitnessValues.from_json('{"A": {"value": "42", "type": "u32"}}'),
+    )
+    assert satisfied.run(simplicityhl.Environment(script="51")).success
+
+    with pytest.raises(simplicityhl.SimplicityHlError):
+        simplicityhl.Environment(script="not hex")
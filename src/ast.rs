@@ -6,20 +6,22 @@ use std::sync::Arc;
 
 use either::Either;
 use miniscript::iter::{Tree, TreeLike};
-use simplicity::jet::Elements;
+use simplicity::jet::{Core, Elements};
 use simplicity_unchained::jets::unchained::ElementsExtension;
 
 use crate::debug::{CallTracker, DebugSymbols, TrackedCallName};
 use crate::error::{Error, RichError, Span, WithSpan};
+use crate::jet::JetSet;
 use crate::num::{NonZeroPow2Usize, Pow2Usize};
 use crate::parse::MatchPattern;
 use crate::pattern::Pattern;
 use crate::str::{AliasName, FunctionName, Identifier, ModuleName, WitnessName};
 use crate::types::{
-    AliasedType, ResolvedType, StructuralType, TypeConstructible, TypeDeconstructible, UIntType,
+    AliasedType, BuiltinAlias, ResolvedType, StructuralType, TypeConstructible,
+    TypeDeconstructible, UIntType,
 };
 use crate::value::{UIntValue, Value};
-use crate::witness::{Parameters, WitnessTypes, WitnessValues};
+use crate::witness::{DelegateTypes, Parameters, WitnessTypes, WitnessValues};
 use crate::{impl_eq_hash, parse};
 
 /// A program consists of the main function.
@@ -31,6 +33,7 @@ pub struct Program {
     main: Expression,
     parameters: Parameters,
     witness_types: WitnessTypes,
+    delegate_types: DelegateTypes,
     call_tracker: Arc<CallTracker>,
 }
 
@@ -52,6 +55,11 @@ impl Program {
         &self.witness_types
     }
 
+    /// Access the types of the delegated programs.
+    pub fn delegate_types(&self) -> &DelegateTypes {
+        &self.delegate_types
+    }
+
     /// Access the debug symbols of the program.
     pub fn debug_symbols(&self, file: &str) -> DebugSymbols {
         self.call_tracker.with_file(file)
@@ -74,8 +82,16 @@ pub enum Item {
     TypeAlias,
     /// A function.
     Function(Function),
+    /// A constant.
+    ///
+    /// A stub because the value of the constant was copied into its uses in the main function.
+    Const,
     /// A module, which is ignored.
     Module,
+    /// An import of a module of the standard library.
+    ///
+    /// A stub because the module is linked before the creation of the AST.
+    Use,
 }
 
 /// Definition of a function.
@@ -240,6 +256,13 @@ pub enum SingleExpressionInner {
     Call(Call),
     /// Match expression.
     Match(Match),
+    /// Early return from the enclosing function.
+    ///
+    /// Returns nothing (unit) if there is no expression.
+    Return(Option<Arc<Expression>>),
+    /// Early return from the enclosing function on `None` or `Left`,
+    /// otherwise the value inside `Some` or `Right`.
+    Try(Arc<Expression>),
 }
 
 /// Call of a user-defined or of a builtin function.
@@ -301,16 +324,69 @@ pub enum CallName {
     ArrayFold(CustomFunction, NonZeroUsize),
     /// Loop over the given function a bounded number of times until it returns success.
     ForWhile(CustomFunction, Pow2Usize),
+    /// Call the given function with the CMR of the delegated program of the given name,
+    /// and run the delegated program on the output of the function.
+    Disconnect(CustomFunction, WitnessName),
+    /// SHA256 hash of a byte array of the given size.
+    Sha256(NonZeroUsize),
+    /// Add a byte array of the given size to a SHA256 hash context.
+    Sha256Add(NonZeroUsize),
+    /// Element of an array of the given size at the given constant index.
+    ArrayIndex(usize, usize),
+    /// Element of an array of the given size at a runtime index of the given type.
+    ///
+    /// Fails if the index is out of bounds.
+    ArrayIndexDynamic(usize, UIntType),
+    /// Subarray of an array of the given size over the given constant range of indices.
+    ArraySlice(usize, usize, usize),
+    /// Concatenation of two arrays of the given sizes.
+    ArrayConcat(NonZeroUsize, NonZeroUsize),
+    /// Map of an array of the given size with the given function.
+    ArrayMap(CustomFunction, NonZeroUsize),
+    /// Zip of two arrays of the given size into an array of pairs.
+    ArrayZip(usize),
+    /// Reversal of an array of the given size.
+    ArrayReverse(usize),
+    /// Check if an array of the given size contains an integer of the given type.
+    ArrayContains(UIntType, NonZeroUsize),
+    /// Element of an array of the given size at a runtime index of the given type,
+    /// or nothing if the index is out of bounds.
+    ArrayGet(UIntType, NonZeroUsize),
+    /// Length of a bounded list of the given element type and bound.
+    ListLen(ResolvedType, NonZeroPow2Usize),
+    /// Append an element to a bounded list with the given bound.
+    ListPush(NonZeroPow2Usize),
+    /// Element of a bounded list with the given bound at a runtime index.
+    ListGet(NonZeroPow2Usize),
 }
 
 /// Definition of a custom function.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct CustomFunction {
+    name: FunctionName,
+    span: Span,
     params: Arc<[FunctionParam]>,
     body: Arc<Expression>,
+    is_const: bool,
+    witnesses: Arc<[WitnessName]>,
 }
 
 impl CustomFunction {
+    /// Access the name of the function.
+    pub fn name(&self) -> &FunctionName {
+        &self.name
+    }
+
+    /// Access the span of the function definition.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Check if the function is a `const fn`.
+    pub fn is_const(&self) -> bool {
+        self.is_const
+    }
+
     /// Access the identifiers of the parameters of the function.
     pub fn params(&self) -> &[FunctionParam] {
         &self.params
@@ -321,6 +397,14 @@ impl CustomFunction {
         &self.body
     }
 
+    /// Access the names of the witnesses that the function uses,
+    /// either in its body or through the functions that it calls.
+    ///
+    /// Each call of the function uses these witnesses once.
+    pub fn witnesses(&self) -> &[WitnessName] {
+        &self.witnesses
+    }
+
     /// Return a pattern for the parameters of the function.
     pub fn params_pattern(&self) -> Pattern {
         Pattern::tuple(
@@ -493,11 +577,14 @@ impl TreeLike for ExprTree<'_> {
                 | S::Witness(_)
                 | S::Parameter(_)
                 | S::Variable(_)
-                | S::Option(None) => Tree::Nullary,
+                | S::Option(None)
+                | S::Return(None) => Tree::Nullary,
                 S::Expression(l)
                 | S::Either(Either::Left(l))
                 | S::Either(Either::Right(l))
-                | S::Option(Some(l)) => Tree::Unary(Self::Expression(l)),
+                | S::Option(Some(l))
+                | S::Return(Some(l))
+                | S::Try(l) => Tree::Unary(Self::Expression(l)),
                 S::Tuple(elements) | S::Array(elements) | S::List(elements) => {
                     Tree::Nary(elements.iter().map(Self::Expression).collect())
                 }
@@ -521,14 +608,21 @@ impl TreeLike for ExprTree<'_> {
 /// 2. Resolving type aliases
 /// 3. Assigning types to each witness expression
 /// 4. Resolving calls to custom functions
+/// 5. Typing early returns from the current function
+/// 6. Resolving constants to their values
+/// 7. Checking that each witness is used at most once on each path through the current function
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 struct Scope {
     variables: Vec<HashMap<Identifier, ResolvedType>>,
+    constants: HashMap<Identifier, Value>,
     aliases: HashMap<AliasName, ResolvedType>,
     parameters: HashMap<WitnessName, ResolvedType>,
     witnesses: HashMap<WitnessName, ResolvedType>,
+    witness_uses: Vec<WitnessName>,
+    delegates: HashMap<WitnessName, (ResolvedType, ResolvedType)>,
     functions: HashMap<FunctionName, CustomFunction>,
     is_main: bool,
+    return_ty: Option<ResolvedType>,
     call_tracker: CallTracker,
 }
 
@@ -581,6 +675,22 @@ impl Scope {
         )
     }
 
+    /// Set the return type of the function whose body is analyzed next.
+    ///
+    /// Set `None` after the body has been analyzed.
+    pub fn set_return_type(&mut self, ty: Option<ResolvedType>) {
+        self.return_ty = ty;
+    }
+
+    /// Get the return type of the function whose body is currently analyzed.
+    ///
+    /// ## Errors
+    ///
+    /// The current scope is not inside a function body.
+    pub fn return_type(&self) -> Result<&ResolvedType, Error> {
+        self.return_ty.as_ref().ok_or(Error::ReturnOutsideFunction)
+    }
+
     /// Push a variable onto the current stack.
     ///
     /// ## Panics
@@ -601,6 +711,31 @@ impl Scope {
             .find_map(|scope| scope.get(identifier))
     }
 
+    /// Insert a constant into the global map.
+    ///
+    /// ## Errors
+    ///
+    /// A constant of the same name has already been defined.
+    pub fn insert_constant(&mut self, name: Identifier, value: Value) -> Result<(), Error> {
+        match self.constants.entry(name.clone()) {
+            Entry::Occupied(_) => Err(Error::ConstantRedefined(name)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the value of a constant.
+    ///
+    /// Variables shadow constants of the same name.
+    pub fn get_constant(&self, identifier: &Identifier) -> Option<&Value> {
+        match self.get_variable(identifier) {
+            Some(_) => None,
+            None => self.constants.get(identifier),
+        }
+    }
+
     /// Resolve a type with aliases to a type without aliases.
     ///
     /// ## Errors
@@ -641,19 +776,114 @@ impl Scope {
 
     /// Insert a witness into the global map.
     ///
+    /// The witness is used by the function whose body is currently analyzed.
+    /// Uses of the same witness on mutually exclusive paths refer to the same value.
+    ///
     /// ## Errors
     ///
-    /// - The current scope is not inside the main function.
-    /// - A witness with the same name has already been defined.
+    /// - The witness has already been used on the current path.
+    /// - The witness has already been defined with a different type.
     pub fn insert_witness(&mut self, name: WitnessName, ty: ResolvedType) -> Result<(), Error> {
-        if !self.is_main {
-            return Err(Error::WitnessOutsideMain);
+        if self.delegates.contains_key(&name) || self.witness_uses.contains(&name) {
+            return Err(Error::WitnessReused(name));
         }
 
         match self.witnesses.entry(name.clone()) {
-            Entry::Occupied(_) => Err(Error::WitnessReused(name)),
+            Entry::Occupied(entry) if entry.get() != &ty => {
+                return Err(Error::ExpressionTypeMismatch(entry.get().clone(), ty));
+            }
+            Entry::Occupied(_) => {}
             Entry::Vacant(entry) => {
                 entry.insert(ty);
+            }
+        }
+        self.witness_uses.push(name);
+        Ok(())
+    }
+
+    /// Start analyzing the arms of a match expression.
+    ///
+    /// Return the number of witnesses that are used before the arms.
+    pub fn start_match_arms(&self) -> usize {
+        self.witness_uses.len()
+    }
+
+    /// Finish analyzing the left arm of a match expression.
+    ///
+    /// Return the witnesses that the left arm uses and forget about them,
+    /// so the right arm may use the same witnesses.
+    pub fn finish_left_arm(&mut self, start: usize) -> Vec<WitnessName> {
+        self.witness_uses.split_off(start)
+    }
+
+    /// Finish analyzing the right arm of a match expression.
+    ///
+    /// After the match expression, the witnesses of both arms count as used.
+    pub fn finish_right_arm(&mut self, left_uses: Vec<WitnessName>) {
+        for witness in left_uses {
+            if !self.witness_uses.contains(&witness) {
+                self.witness_uses.push(witness);
+            }
+        }
+    }
+
+    /// Use the witnesses of the given custom `function`,
+    /// which is called once by the function whose body is currently analyzed.
+    ///
+    /// ## Errors
+    ///
+    /// The current function already uses one of the witnesses,
+    /// for instance because it calls the given function twice.
+    pub fn use_function_witnesses(
+        &mut self,
+        name: &FunctionName,
+        function: &CustomFunction,
+    ) -> Result<(), Error> {
+        for witness in function.witnesses() {
+            if self.witness_uses.contains(witness) {
+                return Err(Error::WitnessFunctionCalledTwice(
+                    name.clone(),
+                    witness.clone(),
+                ));
+            }
+        }
+        self.witness_uses
+            .extend(function.witnesses().iter().cloned());
+        Ok(())
+    }
+
+    /// Take the witnesses that the function whose body was analyzed last uses.
+    ///
+    /// The next function body starts without any used witnesses.
+    pub fn take_witness_uses(&mut self) -> Arc<[WitnessName]> {
+        std::mem::take(&mut self.witness_uses).into()
+    }
+
+    /// Insert a delegated program of the given source and target type into the global map.
+    ///
+    /// Delegated programs share their namespace with witnesses.
+    ///
+    /// ## Errors
+    ///
+    /// - The current scope is not inside the main function.
+    /// - A witness or delegated program with the same name has already been defined.
+    pub fn insert_delegate(
+        &mut self,
+        name: WitnessName,
+        source: ResolvedType,
+        target: ResolvedType,
+    ) -> Result<(), Error> {
+        if !self.is_main {
+            return Err(Error::DisconnectOutsideMain);
+        }
+        if self.witnesses.contains_key(&name) {
+            return Err(Error::WitnessReused(name));
+        }
+
+        match self.delegates.entry(name.clone()) {
+            Entry::Occupied(_) => Err(Error::WitnessReused(name)),
+            Entry::Vacant(entry) => {
+                entry.insert((source, target));
                 Ok(())
             }
         }
@@ -663,11 +893,13 @@ impl Scope {
     ///
     /// 1. The map of parameter types.
     /// 2. The map of witness types.
-    /// 3. The function call tracker.
-    pub fn destruct(self) -> (Parameters, WitnessTypes, CallTracker) {
+    /// 3. The map of delegate types.
+    /// 4. The function call tracker.
+    pub fn destruct(self) -> (Parameters, WitnessTypes, DelegateTypes, CallTracker) {
         (
             Parameters::from(self.parameters),
             WitnessTypes::from(self.witnesses),
+            DelegateTypes::from(self.delegates),
             self.call_tracker,
         )
     }
@@ -725,7 +957,7 @@ impl Program {
             .map(|s| Item::analyze(s, &unit, &mut scope))
             .collect::<Result<Vec<Item>, RichError>>()?;
         debug_assert!(scope.is_topmost());
-        let (parameters, witness_types, call_tracker) = scope.destruct();
+        let (parameters, witness_types, delegate_types, call_tracker) = scope.destruct();
         let mut iter = items.into_iter().filter_map(|item| match item {
             Item::Function(Function::Main(expr)) => Some(expr),
             _ => None,
@@ -738,9 +970,37 @@ impl Program {
             main,
             parameters,
             witness_types,
+            delegate_types,
             call_tracker: Arc::new(call_tracker),
         })
     }
+
+    /// Analyze the program for the jet set `J`.
+    ///
+    /// Calls of jets that are not part of the jet set are rejected.
+    pub fn analyze_for<J: JetSet>(from: &parse::Program) -> Result<Self, RichError> {
+        let program = Self::analyze(from)?;
+        let calls = from
+            .items()
+            .iter()
+            .filter_map(|item| match item {
+                parse::Item::Function(function) => Some(function),
+                _ => None,
+            })
+            .flat_map(|function| parse::ExprTree::Expression(function.body()).pre_order_iter())
+            .filter_map(|node| match node {
+                parse::ExprTree::Call(call) => Some(call),
+                _ => None,
+            });
+        for call in calls {
+            if let parse::CallName::Jet(name) = call.name() {
+                if J::parse_name(name.as_inner()).is_none() {
+                    return Err(Error::JetNotSupported(name.clone(), J::NAME)).with_span(call);
+                }
+            }
+        }
+        Ok(program)
+    }
 }
 
 impl AbstractSyntaxTree for Item {
@@ -760,7 +1020,21 @@ impl AbstractSyntaxTree for Item {
             parse::Item::Function(function) => {
                 Function::analyze(function, ty, scope).map(Self::Function)
             }
+            parse::Item::Const(constant) => {
+                let ty_const = scope.resolve(constant.ty()).with_span(constant)?;
+                check_const(constant.expression(), scope)?;
+                let expression = Expression::analyze(constant.expression(), &ty_const, scope)?;
+                let value = match Value::from_const_expr(&expression) {
+                    Some(value) => value,
+                    None => expression.eval_const()?,
+                };
+                scope
+                    .insert_constant(constant.name().clone(), value)
+                    .with_span(constant)?;
+                Ok(Self::Const)
+            }
             parse::Item::Module => Ok(Self::Module),
+            parse::Item::Use(_) => Ok(Self::Use),
         }
     }
 }
@@ -789,14 +1063,26 @@ impl AbstractSyntaxTree for Function {
                 .map(|aliased| scope.resolve(aliased).with_span(from))
                 .transpose()?
                 .unwrap_or_else(ResolvedType::unit);
+            if from.is_const() {
+                check_const(from.body(), scope)?;
+            }
             scope.push_scope();
             for param in params.iter() {
                 scope.insert_variable(param.identifier().clone(), param.ty().clone());
             }
+            scope.set_return_type(Some(ret.clone()));
             let body = Expression::analyze(from.body(), &ret, scope).map(Arc::new)?;
+            scope.set_return_type(None);
             scope.pop_scope();
             debug_assert!(scope.is_topmost());
-            let function = CustomFunction { params, body };
+            let function = CustomFunction {
+                name: from.name().clone(),
+                span: *from.as_ref(),
+                params,
+                body,
+                is_const: from.is_const(),
+                witnesses: scope.take_witness_uses(),
+            };
             scope
                 .insert_function(from.name().clone(), function)
                 .with_span(from)?;
@@ -815,8 +1101,12 @@ impl AbstractSyntaxTree for Function {
         }
 
         scope.push_main_scope();
+        scope.set_return_type(Some(ty.clone()));
         let body = Expression::analyze(from.body(), ty, scope)?;
+        scope.set_return_type(None);
         scope.pop_main_scope();
+        // Start the next function body without the witnesses of main
+        scope.take_witness_uses();
         Ok(Self::Main(body))
     }
 }
@@ -967,6 +1257,19 @@ impl AbstractSyntaxTree for SingleExpression {
                     .with_span(from)?;
                 SingleExpressionInner::Parameter(name.shallow_clone())
             }
+            parse::SingleExpressionInner::Variable(identifier)
+                if scope.get_constant(identifier).is_some() =>
+            {
+                let value = scope.get_constant(identifier).unwrap();
+                if ty != value.ty() {
+                    return Err(Error::ExpressionTypeMismatch(
+                        ty.clone(),
+                        value.ty().clone(),
+                    ))
+                    .with_span(from);
+                }
+                SingleExpressionInner::Constant(value.clone())
+            }
             parse::SingleExpressionInner::Variable(identifier) => {
                 let bound_ty = scope
                     .get_variable(identifier)
@@ -1055,11 +1358,104 @@ impl AbstractSyntaxTree for SingleExpression {
                 .map(SingleExpressionInner::Option)?
             }
             parse::SingleExpressionInner::Call(call) => {
-                Call::analyze(call, ty, scope).map(SingleExpressionInner::Call)?
+                let call = Call::analyze(call, ty, scope)?;
+                match call.eval_if_const(ty)? {
+                    Some(value) => SingleExpressionInner::Constant(value),
+                    None => SingleExpressionInner::Call(call),
+                }
             }
             parse::SingleExpressionInner::Match(match_) => {
                 Match::analyze(match_, ty, scope).map(SingleExpressionInner::Match)?
             }
+            parse::SingleExpressionInner::Return(maybe_expr) => {
+                // The expression returns from the function,
+                // so it never produces a value and fits any expected type.
+                let ret = scope.return_type().with_span(from)?.clone();
+                match maybe_expr {
+                    Some(expr) => Expression::analyze(expr, &ret, scope)
+                        .map(Arc::new)
+                        .map(Some),
+                    None if ret.is_unit() => Ok(None),
+                    None => Err(Error::ExpressionTypeMismatch(ret, ResolvedType::unit()))
+                        .with_span(from),
+                }
+                .map(SingleExpressionInner::Return)?
+            }
+            parse::SingleExpressionInner::Try(expr) => {
+                // `expr?` returns `None` or `Left(e)` from a function that returns the same,
+                // so `expr` has the expected type inside `Some` or `Right`.
+                let ret = scope.return_type().with_span(from)?;
+                let expr_ty = if ret.as_option().is_some() {
+                    ResolvedType::option(ty.clone())
+                } else if let Some((left_ty, _)) = ret.as_either() {
+                    ResolvedType::either(left_ty.clone(), ty.clone())
+                } else {
+                    return Err(Error::TryUnsupportedReturnType(ret.clone())).with_span(from);
+                };
+                Expression::analyze(expr, &expr_ty, scope)
+                    .map(Arc::new)
+                    .map(SingleExpressionInner::Try)?
+            }
+            parse::SingleExpressionInner::Index(array, index) => {
+                let (array_ty, size) = infer_array_type(array, scope)?;
+                let element_ty = array_ty.as_array().unwrap().0;
+                if ty != element_ty {
+                    return Err(Error::ExpressionTypeMismatch(
+                        ty.clone(),
+                        element_ty.clone(),
+                    ))
+                    .with_span(from);
+                }
+                match constant_index(index) {
+                    Some(index) => {
+                        if size <= index {
+                            return Err(Error::ArrayIndexOutOfBounds(index, size)).with_span(from);
+                        }
+                        let array = Expression::analyze(array, &array_ty, scope)?;
+                        SingleExpressionInner::Call(Call {
+                            name: CallName::ArrayIndex(size, index),
+                            args: Arc::new([array]),
+                            span: *from.as_ref(),
+                        })
+                    }
+                    None => {
+                        let index_ty = infer_type(index, scope)
+                            .ok_or(Error::ExpressionTypeUnknown)
+                            .with_span(index.as_ref())?;
+                        let int_ty = index_ty
+                            .as_integer()
+                            .ok_or(Error::ArrayIndexNotInteger(index_ty.clone()))
+                            .with_span(index.as_ref())?;
+                        let args_ty = ResolvedType::tuple([array_ty.clone(), index_ty.clone()]);
+                        scope.track_call(from, TrackedCallName::ArrayIndex(args_ty));
+                        let array = Expression::analyze(array, &array_ty, scope)?;
+                        let index = Expression::analyze(index, &index_ty, scope)?;
+                        SingleExpressionInner::Call(Call {
+                            name: CallName::ArrayIndexDynamic(size, int_ty),
+                            args: Arc::new([array, index]),
+                            span: *from.as_ref(),
+                        })
+                    }
+                }
+            }
+            parse::SingleExpressionInner::Slice(array, start, end) => {
+                let (array_ty, size) = infer_array_type(array, scope)?;
+                if end < start || size < *end {
+                    return Err(Error::ArraySliceOutOfBounds(*start, *end, size)).with_span(from);
+                }
+                let element_ty = array_ty.as_array().unwrap().0;
+                let slice_ty = ResolvedType::array(element_ty.clone(), end - start);
+                if ty != &slice_ty {
+                    return Err(Error::ExpressionTypeMismatch(ty.clone(), slice_ty))
+                        .with_span(from);
+                }
+                let array = Expression::analyze(array, &array_ty, scope)?;
+                SingleExpressionInner::Call(Call {
+                    name: CallName::ArraySlice(size, *start, *end),
+                    args: Arc::new([array]),
+                    span: *from.as_ref(),
+                })
+            }
         };
 
         Ok(Self {
@@ -1070,6 +1466,161 @@ impl AbstractSyntaxTree for SingleExpression {
     }
 }
 
+/// Return the value of an array index that is a decimal literal.
+///
+/// Literals that exceed `usize` saturate, so they are reported as out of bounds.
+/// Return `None` if the index is computed at runtime.
+fn constant_index(index: &parse::Expression) -> Option<usize> {
+    let parse::ExpressionInner::Single(single) = index.inner() else {
+        return None;
+    };
+    let parse::SingleExpressionInner::Decimal(decimal) = single.inner() else {
+        return None;
+    };
+    Some(decimal.as_inner().parse::<usize>().unwrap_or(usize::MAX))
+}
+
+/// Infer the type of an array that is indexed or sliced, and return the type and the array size.
+///
+/// ## Errors
+///
+/// - The type of the expression cannot be inferred.
+/// - The expression is not an array.
+fn infer_array_type(
+    from: &parse::Expression,
+    scope: &Scope,
+) -> Result<(ResolvedType, usize), RichError> {
+    let ty = infer_type(from, scope)
+        .ok_or(Error::ExpressionTypeUnknown)
+        .with_span(from)?;
+    let size = ty
+        .as_array()
+        .map(|(_, size)| size)
+        .ok_or(Error::ExpressionNotIndexable(ty.clone()))
+        .with_span(from)?;
+    Ok((ty, size))
+}
+
+/// Check if the expression from the parse tree can be evaluated at compile time.
+///
+/// Constant expressions read neither witness data nor parameters,
+/// they call only jets that don't depend on the transaction environment,
+/// and they call only custom functions that are `const fn`.
+fn check_const(from: &parse::Expression, scope: &Scope) -> Result<(), RichError> {
+    use parse::SingleExpressionInner as S;
+
+    for node in parse::ExprTree::Expression(from).pre_order_iter() {
+        match node {
+            parse::ExprTree::Single(single) => {
+                if let S::Witness(..) | S::Parameter(..) = single.inner() {
+                    return Err(Error::ExpressionNotConstant).with_span(single);
+                }
+            }
+            parse::ExprTree::Call(call) => match call.name() {
+                parse::CallName::Jet(name)
+                    if ElementsExtension::from_str(name.as_inner()).is_ok()
+                        && Core::parse_name(name.as_inner()).is_none() =>
+                {
+                    return Err(Error::ExpressionNotConstant).with_span(call);
+                }
+                parse::CallName::Disconnect(..) => {
+                    return Err(Error::ExpressionNotConstant).with_span(call);
+                }
+                parse::CallName::Custom(name)
+                | parse::CallName::Fold(name, _)
+                | parse::CallName::ArrayFold(name, _)
+                | parse::CallName::ArrayMap(name, _)
+                | parse::CallName::ForWhile(name) => {
+                    if scope
+                        .get_function(name)
+                        .is_some_and(|function| !function.is_const())
+                    {
+                        return Err(Error::FunctionNotConst(name.clone())).with_span(call);
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Infer the type of an expression from the parse tree without analyzing the expression.
+///
+/// Types are usually checked from the outside in, but an indexed array must be typed
+/// from the inside out. Inference supports variables, parenthesized expressions,
+/// calls of jets and custom functions, SHA256 hashes, and indexing and slicing.
+///
+/// Return `None` if the type cannot be inferred.
+fn infer_type(from: &parse::Expression, scope: &Scope) -> Option<ResolvedType> {
+    use parse::SingleExpressionInner as S;
+
+    let single = match from.inner() {
+        parse::ExpressionInner::Single(single) => single,
+        parse::ExpressionInner::Block(..) => return None,
+    };
+    match single.inner() {
+        S::Variable(identifier) => scope.get_variable(identifier).cloned().or_else(|| {
+            scope
+                .get_constant(identifier)
+                .map(|value| value.ty().clone())
+        }),
+        S::Expression(inner) => infer_type(inner, scope),
+        S::Call(call) => match call.name() {
+            parse::CallName::Jet(name) => ElementsExtension::from_str(name.as_inner())
+                .ok()
+                .and_then(|jet| crate::jet::target_type(jet).resolve_builtin().ok()),
+            parse::CallName::Custom(name) => scope
+                .get_function(name)
+                .map(|function| function.body().ty().clone()),
+            parse::CallName::Sha256(..) => Some(ResolvedType::from(UIntType::U256)),
+            _ => None,
+        },
+        S::Index(array, _) => infer_type(array, scope)
+            .as_ref()
+            .and_then(ResolvedType::as_array)
+            .map(|(element_ty, _)| element_ty.clone()),
+        S::Slice(array, start, end) => infer_type(array, scope)
+            .as_ref()
+            .and_then(ResolvedType::as_array)
+            .map(|(element_ty, _)| {
+                ResolvedType::array(element_ty.clone(), end.saturating_sub(*start))
+            }),
+        _ => None,
+    }
+}
+
+impl Call {
+    /// Evaluate the call at compile time if it calls a `const fn` with constant arguments.
+    ///
+    /// Return `None` if the call cannot be evaluated at compile time.
+    fn eval_if_const(&self, ty: &ResolvedType) -> Result<Option<Value>, RichError> {
+        let CallName::Custom(function) = self.name() else {
+            return Ok(None);
+        };
+        if !function.is_const()
+            || self
+                .args()
+                .iter()
+                .any(|arg| Value::from_const_expr(arg).is_none())
+        {
+            return Ok(None);
+        }
+        let single = SingleExpression {
+            inner: SingleExpressionInner::Call(self.clone()),
+            ty: ty.clone(),
+            span: self.span,
+        };
+        let expression = Expression {
+            inner: ExpressionInner::Single(single),
+            ty: ty.clone(),
+            span: self.span,
+        };
+        expression.eval_const().map(Some)
+    }
+}
+
 impl AbstractSyntaxTree for Call {
     type From = parse::Call;
 
@@ -1116,6 +1667,41 @@ impl AbstractSyntaxTree for Call {
         }
 
         let name = CallName::analyze(from, ty, scope)?;
+        match (from.name(), &name) {
+            (
+                parse::CallName::Custom(function_name)
+                | parse::CallName::Disconnect(function_name, _),
+                CallName::Custom(function) | CallName::Disconnect(function, _),
+            ) => scope
+                .use_function_witnesses(function_name, function)
+                .with_span(from)?,
+            (
+                parse::CallName::Fold(function_name, _)
+                | parse::CallName::ArrayFold(function_name, _)
+                | parse::CallName::ForWhile(function_name)
+                | parse::CallName::ArrayMap(function_name, _),
+                CallName::Fold(function, _)
+                | CallName::ArrayFold(function, _)
+                | CallName::ForWhile(function, _)
+                | CallName::ArrayMap(function, _),
+            ) => {
+                // The loop body runs more than once, so it would use its witnesses more than once
+                if let Some(witness) = function.witnesses().first() {
+                    return Err(Error::WitnessInLoop(function_name.clone(), witness.clone()))
+                        .with_span(from);
+                }
+            }
+            _ => {}
+        }
+        if from.message().is_some()
+            && !matches!(
+                from.name(),
+                parse::CallName::Assert | parse::CallName::Panic
+            )
+        {
+            return Err(Error::MessageNotSupported(from.name().clone())).with_span(from);
+        }
+        let message = from.message().map(Arc::from);
         let args = match name.clone() {
             CallName::Jet(jet) => {
                 let args_tys = crate::jet::source_type(jet)
@@ -1167,14 +1753,14 @@ impl AbstractSyntaxTree for Call {
                 check_argument_types(from.args(), &args_tys).with_span(from)?;
                 let out_ty = ResolvedType::unit();
                 check_output_type(&out_ty, ty).with_span(from)?;
-                scope.track_call(from, TrackedCallName::Assert);
+                scope.track_call(from, TrackedCallName::Assert(message));
                 analyze_arguments(from.args(), &args_tys, scope)?
             }
             CallName::Panic => {
                 let args_tys = [];
                 check_argument_types(from.args(), &args_tys).with_span(from)?;
                 // panic! allows every output type because it will never return anything
-                scope.track_call(from, TrackedCallName::Panic);
+                scope.track_call(from, TrackedCallName::Panic(message));
                 analyze_arguments(from.args(), &args_tys, scope)?
             }
             CallName::Debug => {
@@ -1271,6 +1857,159 @@ impl AbstractSyntaxTree for Call {
                 check_output_type(out_ty, ty).with_span(from)?;
                 analyze_arguments(from.args(), &args_ty, scope)?
             }
+            CallName::Disconnect(function, delegate) => {
+                // A disconnect has the signature:
+                //   disconnect::<f, NAME>(input: A) -> (B, D)
+                // where
+                //   fn f(cmr: u256, input: A) -> (B, C)
+                //   NAME is a delegated program of type C → D
+                let input_ty = function
+                    .params()
+                    .get(1)
+                    .expect("disconnectable function")
+                    .ty()
+                    .clone();
+                let args_ty = [input_ty];
+
+                check_argument_types(from.args(), &args_ty).with_span(from)?;
+                let (function_out_ty, delegate_source_ty) = match function.body().ty().as_tuple() {
+                    Some([out_ty, source_ty]) => (out_ty, source_ty),
+                    _ => unreachable!("disconnectable function"),
+                };
+                let (out_ty, delegate_target_ty) = match ty.as_tuple() {
+                    Some([out_ty, target_ty]) => (out_ty, target_ty),
+                    _ => return Err(Error::ExpressionUnexpectedType(ty.clone())).with_span(from),
+                };
+                check_output_type(function_out_ty, out_ty).with_span(from)?;
+                scope
+                    .insert_delegate(
+                        delegate.clone(),
+                        delegate_source_ty.as_ref().clone(),
+                        delegate_target_ty.as_ref().clone(),
+                    )
+                    .with_span(from)?;
+                analyze_arguments(from.args(), &args_ty, scope)?
+            }
+            CallName::Sha256(size) => {
+                // sha256::<N>(bytes: [u8; N]) -> u256
+                let args_tys = [ResolvedType::array(UIntType::U8.into(), size.get())];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                let out_ty = ResolvedType::from(UIntType::U256);
+                check_output_type(&out_ty, ty).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::Sha256Add(size) => {
+                // sha256_add::<N>(hasher: Ctx8, bytes: [u8; N]) -> Ctx8
+                let args_tys = [
+                    BuiltinAlias::Ctx8.resolve(),
+                    ResolvedType::array(UIntType::U8.into(), size.get()),
+                ];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                let out_ty = BuiltinAlias::Ctx8.resolve();
+                check_output_type(&out_ty, ty).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ArrayIndex(..)
+            | CallName::ArrayIndexDynamic(..)
+            | CallName::ArraySlice(..) => {
+                unreachable!("Indexing is not a named call")
+            }
+            CallName::ArrayConcat(size_l, size_r) => {
+                // array_concat::<N, M>(left: [E; N], right: [E; M]) -> [E; N + M]
+                let element_ty = match ty.as_array() {
+                    Some((element_ty, size)) if size == size_l.get() + size_r.get() => element_ty,
+                    _ => return Err(Error::ExpressionUnexpectedType(ty.clone())).with_span(from),
+                };
+                let args_tys = [
+                    ResolvedType::array(element_ty.clone(), size_l.get()),
+                    ResolvedType::array(element_ty.clone(), size_r.get()),
+                ];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ArrayMap(function, size) => {
+                // An array map has the signature:
+                //   array_map::<f, N>(array: [A; N]) -> [B; N]
+                // where
+                //   fn f(element: A) -> B
+                let element_ty = function.params().first().expect("mappable function").ty();
+                let args_tys = [ResolvedType::array(element_ty.clone(), size.get())];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                let out_ty = ResolvedType::array(function.body().ty().clone(), size.get());
+                check_output_type(&out_ty, ty).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ArrayZip(size) => {
+                // array_zip(left: [A; N], right: [B; N]) -> [(A, B); N]
+                let (left_ty, right_ty) = match ty.as_array().and_then(|(el, _)| el.as_tuple()) {
+                    Some([left_ty, right_ty]) => (left_ty, right_ty),
+                    _ => return Err(Error::ExpressionUnexpectedType(ty.clone())).with_span(from),
+                };
+                let args_tys = [
+                    ResolvedType::array(left_ty.as_ref().clone(), size),
+                    ResolvedType::array(right_ty.as_ref().clone(), size),
+                ];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ArrayReverse(..) => {
+                // array_reverse(array: [E; N]) -> [E; N]
+                let args_tys = [ty.clone()];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ArrayContains(element_ty, size) => {
+                // array_contains::<E, N>(array: [E; N], value: E) -> bool
+                let args_tys = [
+                    ResolvedType::array(element_ty.into(), size.get()),
+                    element_ty.into(),
+                ];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                let out_ty = ResolvedType::boolean();
+                check_output_type(&out_ty, ty).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ArrayGet(index_ty, size) => {
+                // array_get::<I, N>(array: [E; N], index: I) -> Option<E>
+                let element_ty = ty
+                    .as_option()
+                    .ok_or(Error::ExpressionUnexpectedType(ty.clone()))
+                    .with_span(from)?;
+                let args_tys = [
+                    ResolvedType::array(element_ty.clone(), size.get()),
+                    index_ty.into(),
+                ];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ListLen(element_ty, bound) => {
+                // list_len::<E, N>(list: List<E, N>) -> u32
+                let args_tys = [ResolvedType::list(element_ty, bound)];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                let out_ty = ResolvedType::from(UIntType::U32);
+                check_output_type(&out_ty, ty).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ListPush(..) => {
+                // list_push(list: List<E, N>, element: E) -> List<E, N>
+                let element_ty = ty.as_list().expect("list type").0;
+                let args_tys = [ty.clone(), element_ty.clone()];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
+            CallName::ListGet(bound) => {
+                // list_get::<N>(list: List<E, N>, index: u32) -> Option<E>
+                let element_ty = ty
+                    .as_option()
+                    .ok_or(Error::ExpressionUnexpectedType(ty.clone()))
+                    .with_span(from)?;
+                let args_tys = [
+                    ResolvedType::list(element_ty.clone(), bound),
+                    UIntType::U32.into(),
+                ];
+                check_argument_types(from.args(), &args_tys).with_span(from)?;
+                analyze_arguments(from.args(), &args_tys, scope)?
+            }
         };
 
         Ok(Self {
@@ -1285,11 +2024,7 @@ impl AbstractSyntaxTree for CallName {
     // Take parse::Call, so we have access to the span for pretty errors
     type From = parse::Call;
 
-    fn analyze(
-        from: &Self::From,
-        _ty: &ResolvedType,
-        scope: &mut Scope,
-    ) -> Result<Self, RichError> {
+    fn analyze(from: &Self::From, ty: &ResolvedType, scope: &mut Scope) -> Result<Self, RichError> {
         match from.name() {
             parse::CallName::Jet(name) => match ElementsExtension::from_str(name.as_inner()) {
                 Ok(
@@ -1386,6 +2121,90 @@ impl AbstractSyntaxTree for CallName {
                     _ => Err(Error::FunctionNotLoopable(name.clone())).with_span(from),
                 }
             }
+            parse::CallName::Disconnect(name, delegate) => {
+                let function = scope
+                    .get_function(name)
+                    .cloned()
+                    .ok_or(Error::FunctionUndefined(name.clone()))
+                    .with_span(from)?;
+                // A function that is used in a disconnect has the signature:
+                //   fn f(cmr: u256, input: A) -> (B, C)
+                let takes_cmr = function.params().len() == 2
+                    && function.params()[0].ty().as_integer() == Some(UIntType::U256);
+                let returns_pair = matches!(function.body().ty().as_tuple(), Some([_, _]));
+                if takes_cmr && returns_pair {
+                    Ok(Self::Disconnect(function, delegate.clone()))
+                } else {
+                    Err(Error::FunctionNotDisconnectable(name.clone())).with_span(from)
+                }
+            }
+            parse::CallName::Sha256(size) => Ok(Self::Sha256(*size)),
+            parse::CallName::Sha256Add(size) => Ok(Self::Sha256Add(*size)),
+            parse::CallName::ArrayConcat(size_l, size_r) => Ok(Self::ArrayConcat(*size_l, *size_r)),
+            parse::CallName::ArrayMap(name, size) => {
+                let function = scope
+                    .get_function(name)
+                    .cloned()
+                    .ok_or(Error::FunctionUndefined(name.clone()))
+                    .with_span(from)?;
+                // A function that is used in an array map has the signature:
+                //   fn f(element: A) -> B
+                if function.params().len() != 1 {
+                    Err(Error::FunctionNotMappable(name.clone())).with_span(from)
+                } else {
+                    Ok(Self::ArrayMap(function, *size))
+                }
+            }
+            // The size of the array is the size of the output
+            parse::CallName::ArrayZip => ty
+                .as_array()
+                .map(|(_, size)| Self::ArrayZip(size))
+                .ok_or(Error::ExpressionUnexpectedType(ty.clone()))
+                .with_span(from),
+            parse::CallName::ArrayReverse => ty
+                .as_array()
+                .map(|(_, size)| Self::ArrayReverse(size))
+                .ok_or(Error::ExpressionUnexpectedType(ty.clone()))
+                .with_span(from),
+            parse::CallName::ArrayContains(element_ty, size) => {
+                let element_ty = scope.resolve(element_ty).with_span(from)?;
+                element_ty
+                    .as_integer()
+                    .map(|int_ty| Self::ArrayContains(int_ty, *size))
+                    .ok_or(Error::TypeNotComparable(element_ty))
+                    .with_span(from)
+            }
+            parse::CallName::ArrayGet(index_ty, size) => {
+                let index_ty = scope.resolve(index_ty).with_span(from)?;
+                index_ty
+                    .as_integer()
+                    .map(|int_ty| Self::ArrayGet(int_ty, *size))
+                    .ok_or(Error::ArrayIndexNotInteger(index_ty))
+                    .with_span(from)
+            }
+            parse::CallName::ListLen(element_ty, bound) => {
+                // The length of a list with bound N is less than N.
+                // It fits into a u32 if N is at most 2^32.
+                if (u32::MAX as usize) < bound.get() - 1 {
+                    return Err(Error::IntegerOutOfBounds(UIntType::U32)).with_span(from);
+                }
+                scope
+                    .resolve(element_ty)
+                    .map(|element_ty| Self::ListLen(element_ty, *bound))
+                    .with_span(from)
+            }
+            // The bound of the list is the bound of the output
+            parse::CallName::ListPush => ty
+                .as_list()
+                .map(|(_, bound)| Self::ListPush(bound))
+                .ok_or(Error::ExpressionUnexpectedType(ty.clone()))
+                .with_span(from),
+            parse::CallName::ListGet(bound) => {
+                if (u32::MAX as usize) < bound.get() - 1 {
+                    return Err(Error::IntegerOutOfBounds(UIntType::U32)).with_span(from);
+                }
+                Ok(Self::ListGet(*bound))
+            }
         }
     }
 }
@@ -1399,6 +2218,7 @@ impl AbstractSyntaxTree for Match {
         let scrutinee =
             Expression::analyze(from.scrutinee(), &scrutinee_ty, scope).map(Arc::new)?;
 
+        let start = scope.start_match_arms();
         scope.push_scope();
         if let Some((id_l, ty_l)) = from.left().pattern().as_typed_variable() {
             let ty_l = scope.resolve(ty_l).with_span(from)?;
@@ -1406,6 +2226,7 @@ impl AbstractSyntaxTree for Match {
         }
         let ast_l = Expression::analyze(from.left().expression(), ty, scope).map(Arc::new)?;
         scope.pop_scope();
+        let left_uses = scope.finish_left_arm(start);
         scope.push_scope();
         if let Some((id_r, ty_r)) = from.right().pattern().as_typed_variable() {
             let ty_r = scope.resolve(ty_r).with_span(from)?;
@@ -1413,6 +2234,7 @@ impl AbstractSyntaxTree for Match {
         }
         let ast_r = Expression::analyze(from.right().expression(), ty, scope).map(Arc::new)?;
         scope.pop_scope();
+        scope.finish_right_arm(left_uses);
 
         Ok(Self {
             scrutinee,
@@ -1,10 +1,15 @@
 use std::num::NonZeroUsize;
+use std::ops::Range;
 use std::sync::Arc;
 
+use simplicity::jet::Jet;
 use simplicity::node::CoreConstructible;
+use simplicity::{Cmr, FailEntropy};
 
 use super::ProgNode;
-use crate::named::CoreExt;
+use crate::array::BTreeSlice;
+use crate::named::{CoreExt, PairBuilder, SelectorBuilder};
+use crate::num::NonZeroPow2Usize;
 
 /// Fold an array of size `size` elements using function `f`.
 ///
@@ -15,36 +20,15 @@ use crate::named::CoreExt;
 /// The fold `(fold f)_n : E^n × A → A`
 /// takes the array of type `E^n` and an initial accumulator of type `A`,
 /// and it produces the final accumulator of type `A`.
-pub fn array_fold<'brand>(
+pub fn array_fold<'brand, J: Jet>(
     size: NonZeroUsize,
-    f: &ProgNode<'brand>,
-) -> Result<ProgNode<'brand>, simplicity::types::Error> {
-    /// Recursively fold the array using the precomputed folding functions.
-    fn tree_fold<'brand>(
-        n: usize,
-        f_powers_of_two: &[ProgNode<'brand>],
-    ) -> Result<ProgNode<'brand>, simplicity::types::Error> {
-        // Array is a left-balanced (right-associative) binary tree.
-        let max_pow2 = n.ilog2() as usize;
-        debug_assert!(max_pow2 < f_powers_of_two.len());
-        let f_right = &f_powers_of_two[max_pow2];
-
-        // If the tree is balanced, return precomputed solution.
-        let size_right = 1 << max_pow2;
-        if n == size_right {
-            return Ok(Arc::clone(f_right));
-        }
-        debug_assert!(size_right < n);
-
-        let f_left = tree_fold(n - size_right, f_powers_of_two)?;
-        f_array_fold(&f_left, f_right)
-    }
-
+    f: &ProgNode<'brand, J>,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
     /// Fold the two arrays applying the folding function sequentially left -> right.
-    fn f_array_fold<'brand>(
-        f_left: &ProgNode<'brand>,
-        f_right: &ProgNode<'brand>,
-    ) -> Result<ProgNode<'brand>, simplicity::types::Error> {
+    fn f_array_fold<'brand, J: Jet>(
+        f_left: &ProgNode<'brand, J>,
+        f_right: &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
         // The input is a tuple ((L, R), acc): ([E; n], A) where:
         // - L and R are arrays of varying size E^x and E^y respectively (x + y = n).
         // - acc is an accumulator of type A.
@@ -72,11 +56,685 @@ pub fn array_fold<'brand>(
         i *= 2;
     }
 
-    tree_fold(n, &f_powers_of_two)
+    tree_fold(n, &f_powers_of_two, f_array_fold)
+}
+
+/// Add a byte array of size `size` to a SHA256 hash context.
+///
+/// The jet `add_jets[i]: Ctx8 × u8^(2^i) → Ctx8`
+/// adds an array of `2^i` bytes to the hash context.
+///
+/// The function `(sha256_add)_n : Ctx8 × u8^n → Ctx8`
+/// takes the hash context and the array of type `u8^n`,
+/// and it produces the updated hash context.
+///
+/// The array is split into blocks whose sizes are the powers of two of the binary representation of `n`.
+/// Each block is added with a single jet, unless it is larger than the largest jet.
+pub fn sha256_add<'brand, J: Jet>(
+    size: NonZeroUsize,
+    add_jets: &[ProgNode<'brand, J>],
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    /// Add the two arrays to the hash context sequentially left -> right.
+    fn f_sha256_add<'brand, J: Jet>(
+        f_left: &ProgNode<'brand, J>,
+        f_right: &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+        // The input is a tuple (ctx, (L, R)): (Ctx8, [u8; n]) where:
+        // - ctx is a hash context.
+        // - L and R are byte arrays of varying size u8^x and u8^y respectively (x + y = n).
+        let ctx = f_left.inference_context();
+        let hash_ctx = ProgNode::o().h(ctx);
+        let left_arr = ProgNode::i().o().h(ctx);
+        let right_arr = ProgNode::i().i().h(ctx);
+        let left_res = hash_ctx.pair(left_arr).comp(f_left)?;
+        let right_res = left_res.pair(right_arr).comp(f_right)?;
+        Ok(right_res.build())
+    }
+
+    // Use the jets for blocks of size 2^i where i < n,
+    // and split blocks that are larger than the largest jet in half.
+    let n = size.get();
+    let max_pow2 = n.ilog2() as usize;
+    let mut f_powers_of_two: Vec<ProgNode> = Vec::with_capacity(1 + max_pow2);
+    f_powers_of_two.extend(add_jets.iter().take(1 + max_pow2).cloned());
+
+    while f_powers_of_two.len() <= max_pow2 {
+        let f_prev = f_powers_of_two.last().expect("at least one jet");
+        let f_next = f_sha256_add(f_prev, f_prev)?;
+        f_powers_of_two.push(f_next);
+    }
+
+    tree_fold(n, &f_powers_of_two, f_sha256_add)
+}
+
+/// Select the element at `index` of an array of size `size`.
+///
+/// The `selector` selects the array from the input.
+pub fn select_element<'brand, J: Jet>(
+    selector: SelectorBuilder<ProgNode<'brand, J>>,
+    size: usize,
+    index: usize,
+) -> SelectorBuilder<ProgNode<'brand, J>> {
+    // Array is a left-balanced (right-associative) binary tree.
+    debug_assert!(index < size);
+    if size == 1 {
+        return selector;
+    }
+    let size_left = size - size.next_power_of_two() / 2;
+    if index < size_left {
+        select_element(selector.o(), size_left, index)
+    } else {
+        select_element(selector.i(), size - size_left, index - size_left)
+    }
+}
+
+/// Select bit `i` of an unsigned integer of `bit_width` bits,
+/// where bit `i` has the value `2^i`.
+///
+/// The `selector` selects the integer from the input.
+pub fn select_bit<'brand, J: Jet>(
+    selector: SelectorBuilder<ProgNode<'brand, J>>,
+    bit_width: usize,
+    i: usize,
+) -> SelectorBuilder<ProgNode<'brand, J>> {
+    // An integer is an array of bits, starting with the most significant bit.
+    select_element(selector, bit_width, bit_width - 1 - i)
+}
+
+/// Select the subarray of `len` elements that starts at `start` of an array of size `size`,
+/// if the subarray is a node of the array tree.
+///
+/// The `selector` selects the array from the input.
+fn select_subarray<'brand, J: Jet>(
+    selector: SelectorBuilder<ProgNode<'brand, J>>,
+    size: usize,
+    start: usize,
+    len: usize,
+) -> Option<SelectorBuilder<ProgNode<'brand, J>>> {
+    // Array is a left-balanced (right-associative) binary tree.
+    if start == 0 && len == size {
+        return Some(selector);
+    }
+    if size <= 1 {
+        return None;
+    }
+    let size_left = size - size.next_power_of_two() / 2;
+    if start + len <= size_left {
+        select_subarray(selector.o(), size_left, start, len)
+    } else if size_left <= start {
+        select_subarray(selector.i(), size - size_left, start - size_left, len)
+    } else {
+        None
+    }
+}
+
+/// Build an array out of the elements that the `selectors` select from the input.
+fn select_array<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    selectors: impl IntoIterator<Item = SelectorBuilder<ProgNode<'brand, J>>>,
+) -> PairBuilder<ProgNode<'brand, J>> {
+    let elements: Vec<PairBuilder<ProgNode<'brand, J>>> = selectors
+        .into_iter()
+        .map(|selector| selector.h(ctx))
+        .collect();
+    BTreeSlice::from_slice(&elements)
+        .fold(PairBuilder::pair)
+        .unwrap_or_else(|| PairBuilder::unit(ctx))
+}
+
+/// Take the elements in the range `range` of an array of size `size`.
+///
+/// The function `(slice)_n : E^n → E^m`
+/// takes the array of type `E^n`
+/// and it produces the subarray of type `E^m` where `m` is the length of the range.
+pub fn array_slice<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    size: usize,
+    range: Range<usize>,
+) -> PairBuilder<ProgNode<'brand, J>> {
+    debug_assert!(range.end <= size);
+    let selectors = range.map(|index| select_element(SelectorBuilder::default(), size, index));
+    select_array(ctx, selectors)
+}
+
+/// Concatenate an array of size `size_left` with an array of size `size_right`.
+///
+/// The function `(concat)_(n,m) : E^n × E^m → E^(n + m)`
+/// takes the two arrays and it produces the array of both arrays' elements.
+pub fn array_concat<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    size_left: usize,
+    size_right: usize,
+) -> PairBuilder<ProgNode<'brand, J>> {
+    let left = (0..size_left).map(|index| select_element(ProgNode::o(), size_left, index));
+    let right = (0..size_right).map(|index| select_element(ProgNode::i(), size_right, index));
+    select_array(ctx, left.chain(right))
+}
+
+/// Reverse the order of the elements of an array of size `size`.
+///
+/// The function `(reverse)_n : E^n → E^n`
+/// takes the array and it produces the array of the same elements in reverse order.
+pub fn array_reverse<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    size: usize,
+) -> PairBuilder<ProgNode<'brand, J>> {
+    let selectors = (0..size)
+        .rev()
+        .map(|index| select_element(SelectorBuilder::default(), size, index));
+    select_array(ctx, selectors)
+}
+
+/// Map an array of size `size` using function `f`.
+///
+/// Function `f: A → B`
+/// takes an array element of type `A` and produces a new element of type `B`.
+///
+/// The map `(map f)_n : A^n → B^n`
+/// takes the array of type `A^n` and it produces the array of type `B^n`.
+pub fn array_map<'brand, J: Jet>(
+    size: NonZeroUsize,
+    f: &ProgNode<'brand, J>,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    /// Map the left and the right array separately.
+    fn f_array_map<'brand, J: Jet>(
+        f_left: &ProgNode<'brand, J>,
+        f_right: &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+        // The input is a tuple (L, R): [A; n] where:
+        // - L and R are arrays of varying size A^x and A^y respectively (x + y = n).
+        let ctx = f_left.inference_context();
+        let left_res = ProgNode::o().h(ctx).comp(f_left)?;
+        let right_res = ProgNode::i().h(ctx).comp(f_right)?;
+        Ok(left_res.pair(right_res).build())
+    }
+
+    let n = size.get();
+    let f_powers_of_two = powers_of_two(n, f, f_array_map)?;
+    tree_fold(n, &f_powers_of_two, f_array_map)
+}
+
+/// Zip two arrays of size `size` into an array of pairs.
+///
+/// The function `(zip)_n : A^n × B^n → (A × B)^n`
+/// takes two arrays and it produces the array of pairs of elements at the same index.
+pub fn array_zip<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    size: usize,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    /// Zip the two left arrays and the two right arrays separately.
+    fn f_array_zip<'brand, J: Jet>(
+        f_left: &ProgNode<'brand, J>,
+        f_right: &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+        // The input is a tuple ((L1, R1), (L2, R2)): ([A; n], [B; n]) where:
+        // - L1 and L2 are arrays of equal size A^x and B^x respectively.
+        // - R1 and R2 are arrays of equal size A^y and B^y respectively (x + y = n).
+        let ctx = f_left.inference_context();
+        let left_arrs = ProgNode::o().o().h(ctx).pair(ProgNode::i().o().h(ctx));
+        let right_arrs = ProgNode::o().i().h(ctx).pair(ProgNode::i().i().h(ctx));
+        let left_res = left_arrs.comp(f_left)?;
+        let right_res = right_arrs.comp(f_right)?;
+        Ok(left_res.pair(right_res).build())
+    }
+
+    let n = match size {
+        0 => return Ok(ProgNode::unit(ctx)),
+        n => n,
+    };
+    // Two arrays of size 1 are zipped into the pair of their elements.
+    let f_powers_of_two = powers_of_two(n, &ProgNode::iden(ctx), f_array_zip)?;
+    tree_fold(n, &f_powers_of_two, f_array_zip)
+}
+
+/// Check if an array of size `size` contains a value, using the equality function `eq`.
+///
+/// Function `eq: E × E → 2` returns true if its arguments are equal.
+///
+/// The function `(contains eq)_n : E^n × E → 2`
+/// takes the array of type `E^n` and the value of type `E`,
+/// and it returns true if any element is equal to the value.
+pub fn array_contains<'brand, J: Jet>(
+    size: NonZeroUsize,
+    eq: &ProgNode<'brand, J>,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    /// Check the left and the right array and return the disjunction.
+    fn f_array_contains<'brand, J: Jet>(
+        f_left: &ProgNode<'brand, J>,
+        f_right: &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+        // The input is a tuple ((L, R), x): ([E; n], E) where:
+        // - L and R are arrays of varying size E^x and E^y respectively (x + y = n).
+        // - x is the value to look for.
+        let ctx = f_left.inference_context();
+        let value = ProgNode::i().h(ctx);
+        let left_res = ProgNode::o().o().h(ctx).pair(value.clone()).comp(f_left)?;
+        let right_res = ProgNode::o().i().h(ctx).pair(value).comp(f_right)?;
+        let or = ProgNode::case(
+            &ProgNode::drop_(&ProgNode::iden(ctx)),
+            &ProgNode::bit_true(ctx),
+        )?;
+        left_res.pair(right_res).comp(&or).map(PairBuilder::build)
+    }
+
+    let n = size.get();
+    let f_powers_of_two = powers_of_two(n, eq, f_array_contains)?;
+    tree_fold(n, &f_powers_of_two, f_array_contains)
+}
+
+/// Compare two unsigned integers of `2n` bits for equality,
+/// using the equality function `eq_half` for integers of `n` bits.
+///
+/// The function `eq_2n : 2^(2n) × 2^(2n) → 2` returns true if its arguments are equal.
+pub fn eq_halves<'brand, J: Jet>(
+    eq_half: &ProgNode<'brand, J>,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    // The input is a tuple ((H1, L1), (H2, L2)) of the high and low halves of both integers.
+    let ctx = eq_half.inference_context();
+    let high = ProgNode::o()
+        .o()
+        .h(ctx)
+        .pair(ProgNode::i().o().h(ctx))
+        .comp(eq_half)?;
+    let low = ProgNode::o()
+        .i()
+        .h(ctx)
+        .pair(ProgNode::i().i().h(ctx))
+        .comp(eq_half)?;
+    let and = ProgNode::case(
+        &ProgNode::bit_false(ctx),
+        &ProgNode::drop_(&ProgNode::iden(ctx)),
+    )?;
+    high.pair(low).comp(&and).map(PairBuilder::build)
+}
+
+/// Get the element of an array of size `size` at a runtime index.
+///
+/// The function `(get)_n : E^n × 2^w → 1 + E`
+/// takes the array and the index of `bit_width` bits,
+/// and it returns the element at the index or nothing if the index is out of bounds.
+///
+/// The function is a balanced `case` tree over the bits of the index,
+/// starting with the most significant bit.
+/// Once the remaining bits select a subarray of `2^i` elements that is a node of the array tree,
+/// the function descends into the subarray using [`array_get_step`].
+/// The cost is logarithmic in the array size and linear in the bit width of the index.
+pub fn array_get<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    size: usize,
+    bit_width: usize,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    struct Getter<'a, 'brand, J: Jet> {
+        ctx: &'a simplicity::types::Context<'brand>,
+        size: usize,
+        bit_width: usize,
+        /// Functions `get_i :  E^(2^i) × 2^w → E` for arrays of size `2^i`.
+        get_powers_of_two: Vec<ProgNode<'brand, J>>,
+        none: ProgNode<'brand, J>,
+    }
+
+    impl<'brand, J: Jet> Getter<'_, 'brand, J> {
+        /// Get the element whose index is in the range `start..start + 2^remaining_bits`.
+        /// The bits of the index above the `remaining_bits` are known.
+        fn get(
+            &self,
+            start: usize,
+            remaining_bits: usize,
+        ) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+            if self.size <= start {
+                return Ok(self.none.clone());
+            }
+            let len = 1usize.checked_shl(remaining_bits as u32);
+            let subarray = len.and_then(|len| {
+                select_subarray(ProgNode::o(), self.size, start, len).map(|sel| (len, sel))
+            });
+            if let Some((len, subarray)) = subarray {
+                // (O sel ▵ IH); get_i; injr
+                let get_subarray = &self.get_powers_of_two[len.ilog2() as usize];
+                let element = subarray
+                    .h(self.ctx)
+                    .pair(ProgNode::i().h(self.ctx))
+                    .comp(get_subarray)?
+                    .injr();
+                return Ok(element.build());
+            }
+
+            // (I bit_(r - 1) ▵ iden); case (drop get_left) (drop get_right)
+            debug_assert!(0 < remaining_bits);
+            let i = remaining_bits - 1;
+            let get_left = self.get(start, i)?;
+            let get_right = match 1usize
+                .checked_shl(i as u32)
+                .and_then(|x| start.checked_add(x))
+            {
+                Some(start_right) => self.get(start_right, i)?,
+                None => self.none.clone(),
+            };
+            let bit = select_bit(ProgNode::i(), self.bit_width, i).h(self.ctx);
+            let case = ProgNode::case(&ProgNode::drop_(&get_left), &ProgNode::drop_(&get_right))?;
+            bit.pair(PairBuilder::iden(self.ctx))
+                .comp(&case)
+                .map(PairBuilder::build)
+        }
+    }
+
+    let mut get_powers_of_two = vec![ProgNode::o().h(ctx).build()];
+    if 0 < size {
+        for i in 1..=size.ilog2() as usize {
+            let get_prev = get_powers_of_two.last().expect("at least one function");
+            let get_next = array_get_step(get_prev, bit_width, i)?;
+            get_powers_of_two.push(get_next);
+        }
+    }
+    let getter = Getter {
+        ctx,
+        size,
+        bit_width,
+        get_powers_of_two,
+        none: PairBuilder::unit(ctx).injl().build(),
+    };
+    getter.get(0, bit_width)
+}
+
+/// Compute the length of a list of less than `bound` elements.
+///
+/// The function `(len)_n : E^(<2^n) → 2^32` returns the number of elements of the list.
+///
+/// The list consists of blocks of `2^i` elements that are either present or absent.
+/// Bit `i` of the length is set if the block of `2^i` elements is present.
+pub fn list_len<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    bound: NonZeroPow2Usize,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    let n = bound.log2().get() as usize;
+    debug_assert!(n <= 32);
+    // The length starts with the most significant bit.
+    let bits = (0..32)
+        .rev()
+        .map(|i| match i < n {
+            true => select_block(n, i)
+                .h(ctx)
+                .pair(PairBuilder::unit(ctx))
+                .comp(&ProgNode::case_false_true(ctx)),
+            false => Ok(ProgNode::bit(ctx, false)),
+        })
+        .collect::<Result<Vec<PairBuilder<ProgNode<J>>>, simplicity::types::Error>>()?;
+    let len = BTreeSlice::from_slice(&bits)
+        .fold(PairBuilder::pair)
+        .expect("non-empty integer");
+    Ok(len.build())
+}
+
+/// Get the element of a list of less than `bound` elements at a runtime index.
+///
+/// The function `(get)_n : E^(<2^n) × 2^32 → 1 + E`
+/// takes the list and the index,
+/// and it returns the element at the index or nothing if the index is out of bounds.
+///
+/// The cost is logarithmic in the list bound:
+/// The function looks at each bit of the index once and descends into the block that holds the index.
+pub fn list_get<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    bound: NonZeroPow2Usize,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    /* Let L_m be the list of less than 2^m elements.
+     * The input of get_m is a tuple (L_m, i) of the list and the index.
+     * The index is less than 2^m, so get_m looks only at bits 0 to m - 1 of the index.
+     *
+     * get_1 :  (1 + E) × 2^32 → 1 + E
+     * get_1 := (bit_0 ▵ iden); case (drop OH) (drop none)
+     */
+    let n = bound.log2().get() as usize;
+    debug_assert!(n <= 32);
+    let iden = PairBuilder::iden(ctx);
+    let none = PairBuilder::unit(ctx).injl();
+    let bit_0 = select_bit(ProgNode::i(), 32, 0).h(ctx);
+    let get_1 = ProgNode::case(
+        &ProgNode::drop_(ProgNode::o().h(ctx).as_ref()),
+        &ProgNode::drop_(none.as_ref()),
+    )?;
+    let mut get = bit_0.pair(iden.clone()).comp(&get_1)?.build();
+    let mut get_block = ProgNode::o().h(ctx).build();
+
+    for m in 2..=n {
+        /* L_m = (1 + E^(2^(m - 1))) × L_(m - 1)
+         *
+         * If the block of 2^(m - 1) elements is absent,
+         * then the list contains less than 2^(m - 1) elements.
+         * Bit m - 1 of the index must be unset.
+         *
+         * get_absent :  L_m × 2^32 → 1 + E
+         * get_absent := (bit_(m - 1) ▵ iden); case (drop (OIH ▵ IH; get_(m - 1))) (drop none)
+         *
+         * If the block is present, then the block contains the elements with bit m - 1 unset.
+         *
+         * get_present :  E^(2^(m - 1)) × (L_m × 2^32) → 1 + E
+         * get_present := (I bit_(m - 1) ▵ iden); case (drop (OH ▵ IIH; get_block; injr))
+         *                                              (drop (drop (OIH ▵ IH; get_(m - 1))))
+         *
+         * get_m :  L_m × 2^32 → 1 + E
+         * get_m := (OOH ▵ iden); case (drop get_absent) get_present
+         */
+        get_block = array_get_step(&get_block, 32, m - 1)?;
+        let bit = select_bit(ProgNode::i(), 32, m - 1).h(ctx);
+        let get_rest = ProgNode::o()
+            .i()
+            .h(ctx)
+            .pair(ProgNode::i().h(ctx))
+            .comp(&get)?;
+        let absent_case = ProgNode::case(
+            &ProgNode::drop_(get_rest.as_ref()),
+            &ProgNode::drop_(none.as_ref()),
+        )?;
+        let get_absent = bit.pair(iden.clone()).comp(&absent_case)?;
+
+        let bit = select_bit(ProgNode::i().i(), 32, m - 1).h(ctx);
+        let get_element = ProgNode::o()
+            .h(ctx)
+            .pair(ProgNode::i().i().h(ctx))
+            .comp(&get_block)?
+            .injr();
+        let present_case = ProgNode::case(
+            &ProgNode::drop_(get_element.as_ref()),
+            &ProgNode::drop_(&ProgNode::drop_(get_rest.as_ref())),
+        )?;
+        let get_present = bit.pair(iden.clone()).comp(&present_case)?;
+
+        let block_case =
+            ProgNode::case(&ProgNode::drop_(get_absent.as_ref()), get_present.as_ref())?;
+        get = ProgNode::o()
+            .o()
+            .h(ctx)
+            .pair(iden.clone())
+            .comp(&block_case)?
+            .build();
+    }
+
+    // Return nothing if any of the bits n to 31 of the index is set.
+    for i in n..32 {
+        let bit = select_bit(ProgNode::i(), 32, i).h(ctx);
+        let check = ProgNode::case(&ProgNode::drop_(&get), &ProgNode::drop_(none.as_ref()))?;
+        get = bit.pair(iden.clone()).comp(&check)?.build();
+    }
+
+    Ok(get)
+}
+
+/// Append an element to a list of less than `bound` elements.
+///
+/// The function `(push)_n : E^(<2^n) × E → E^(<2^n)`
+/// takes the list and the element, and it returns the list with the element at the end.
+///
+/// The function fails if the list is full.
+pub fn list_push<'brand, J: Jet>(
+    ctx: &simplicity::types::Context<'brand>,
+    bound: NonZeroPow2Usize,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    /* Let L_m be the list of less than 2^m elements.
+     *
+     * push_m :  L_m × E → L_m + E^(2^m)
+     *
+     * push_m returns the updated list if it had room for the element.
+     * Otherwise, push_m returns the full array of 2^m elements.
+     *
+     * push_1 :  (1 + E) × E → (1 + E) + E^2
+     * push_1 := case (injl (injr IH)) (injr iden)
+     */
+    let n = bound.log2().get() as usize;
+    let iden = ProgNode::iden(ctx);
+    let mut push = ProgNode::case(
+        &ProgNode::injl(&ProgNode::injr(&ProgNode::drop_(&iden))),
+        &ProgNode::injr(&iden),
+    )?;
+    let mut empty = PairBuilder::unit(ctx).injl();
+
+    for _ in 1..n {
+        /* L_(m + 1) = (1 + E^(2^m)) × L_m
+         *
+         * Push the element into the smaller list L_m.
+         * If L_m overflows, then its elements fill the block of 2^m elements,
+         * unless the block is already present, in which case L_(m + 1) overflows.
+         *
+         * push_(m + 1) := ((OIH ▵ IH); push_m) ▵ OOH;
+         *                 case (injl (IH ▵ OH))
+         *                      ((IH ▵ OH); case (injl (injr IH ▵ empty_m)) (injr iden))
+         */
+        let pushed = ProgNode::o()
+            .i()
+            .h(ctx)
+            .pair(ProgNode::i().h(ctx))
+            .comp(&push)?;
+        let swap = ProgNode::i().h(ctx).pair(ProgNode::o().h(ctx));
+        let no_overflow = swap.clone().injl();
+        let fill_block = ProgNode::i().h(ctx).injr().pair(empty.clone()).injl();
+        let overflow = PairBuilder::iden(ctx).injr();
+        let block_case = ProgNode::case(fill_block.as_ref(), overflow.as_ref())?;
+        let rest_overflow = swap.comp(&block_case)?;
+        let rest_case = ProgNode::case(no_overflow.as_ref(), rest_overflow.as_ref())?;
+        push = pushed
+            .pair(ProgNode::o().o().h(ctx))
+            .comp(&rest_case)?
+            .build();
+        empty = PairBuilder::unit(ctx).injl().pair(empty);
+    }
+
+    // Fail if the list overflows.
+    let unwrap_list = ProgNode::assertl_take(&iden, Cmr::fail(FailEntropy::ZERO));
+    PairBuilder::iden(ctx)
+        .comp(&push)?
+        .pair(PairBuilder::unit(ctx))
+        .comp(&unwrap_list)
+        .map(PairBuilder::build)
+}
+
+/// Select the block of `2^i` elements of a list of less than `2^n` elements.
+fn select_block<'brand, J: Jet>(n: usize, i: usize) -> SelectorBuilder<ProgNode<'brand, J>> {
+    // The list is a nested tuple of blocks, starting with the largest block:
+    // L_n = (1 + E^(2^(n - 1))) × L_(n - 1) and L_1 = 1 + E
+    debug_assert!(i < n);
+    let mut selector = SelectorBuilder::default();
+    for _ in i + 1..n {
+        selector = selector.i();
+    }
+    match i {
+        0 => selector,
+        _ => selector.o(),
+    }
+}
+
+/// Extend the function that gets the element of an array of size `2^(i - 1)` at a runtime index
+/// to arrays of size `2^i`.
+///
+/// The index is an unsigned integer of `bit_width` bits.
+/// Bit `i - 1` of the index decides if the element is in the left or in the right half.
+///
+/// ```text
+/// get_i :  E^(2^i) × 2^w → E
+/// get_i := (I bit_(i - 1) ▵ iden); case (drop (OOH ▵ IH; get_(i - 1)))
+///                                      (drop (OIH ▵ IH; get_(i - 1)))
+/// ```
+///
+/// The function for arrays of size `2^0` is `OH`.
+pub fn array_get_step<'brand, J: Jet>(
+    get: &ProgNode<'brand, J>,
+    bit_width: usize,
+    i: usize,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    let ctx = get.inference_context();
+    let bit = select_bit(ProgNode::i(), bit_width, i - 1).h(ctx);
+    let index = ProgNode::i().h(ctx);
+    let get_left = ProgNode::o().o().h(ctx).pair(index.clone()).comp(get)?;
+    let get_right = ProgNode::o().i().h(ctx).pair(index).comp(get)?;
+    let case = ProgNode::case(
+        &ProgNode::drop_(get_left.as_ref()),
+        &ProgNode::drop_(get_right.as_ref()),
+    )?;
+    bit.pair(PairBuilder::iden(ctx))
+        .comp(&case)
+        .map(PairBuilder::build)
+}
+
+/// Precompute the functions for the arrays of size `2^i` where `2^i <= n`.
+///
+/// Function `f` processes arrays of size 1.
+/// Function `combine` takes the functions `f_left` and `f_right`
+/// for the left and right subarray, and it produces the function for the entire array.
+fn powers_of_two<'brand, J: Jet>(
+    n: usize,
+    f: &ProgNode<'brand, J>,
+    combine: fn(
+        &ProgNode<'brand, J>,
+        &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error>,
+) -> Result<Vec<ProgNode<'brand, J>>, simplicity::types::Error> {
+    let max_pow2 = n.ilog2() as usize;
+    let mut f_powers_of_two: Vec<ProgNode> = Vec::with_capacity(1 + max_pow2);
+    f_powers_of_two.push(f.clone());
+
+    while f_powers_of_two.len() <= max_pow2 {
+        let f_prev = f_powers_of_two.last().expect("at least one function");
+        let f_next = combine(f_prev, f_prev)?;
+        f_powers_of_two.push(f_next);
+    }
+
+    Ok(f_powers_of_two)
+}
+
+/// Recursively process an array of size `n` using the precomputed functions
+/// for the arrays of size `2^i`.
+///
+/// Function `combine` takes the functions `f_left` and `f_right`
+/// for the left and right subarray, and it produces the function for the entire array.
+fn tree_fold<'brand, J: Jet>(
+    n: usize,
+    f_powers_of_two: &[ProgNode<'brand, J>],
+    combine: fn(
+        &ProgNode<'brand, J>,
+        &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error>,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    // Array is a left-balanced (right-associative) binary tree.
+    let max_pow2 = n.ilog2() as usize;
+    debug_assert!(max_pow2 < f_powers_of_two.len());
+    let f_right = &f_powers_of_two[max_pow2];
+
+    // If the tree is balanced, return precomputed solution.
+    let size_right = 1 << max_pow2;
+    if n == size_right {
+        return Ok(Arc::clone(f_right));
+    }
+    debug_assert!(size_right < n);
+
+    let f_left = tree_fold(n - size_right, f_powers_of_two, combine)?;
+    combine(&f_left, f_right)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
+    use simplicity::hashes::{sha256, Hash};
+
     use crate::{tests::TestCase, WitnessValues};
 
     #[test]
@@ -85,4 +743,198 @@ mod tests {
             .with_witness_values(WitnessValues::default())
             .assert_run_success();
     }
+
+    #[test]
+    fn sha256_byte_string() {
+        let prog_text = r#"fn main() {
+    let hash: u256 = sha256::<3>(b"abc");
+    assert!(jet::eq_256(hash, 0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn sha256_byte_string_escapes() {
+        let prog_text = r#"fn main() {
+    let bytes: [u8; 6] = b"a\"\\\n\t\x00";
+    assert!(jet::eq_256(sha256::<6>(bytes), sha256::<6>(0x61225c0a0900)));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn sha256_sizes() {
+        for size in [1, 2, 3, 7, 32, 33, 64, 100, 512, 513, 1000, 1024, 1500] {
+            let bytes: Vec<u8> = (0..size).map(|i| i as u8).collect();
+            let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+            let hash = sha256::Hash::hash(&bytes);
+            let prog_text = format!(
+                r#"fn main() {{
+    let bytes: [u8; {size}] = 0x{hex};
+    assert!(jet::eq_256(sha256::<{size}>(bytes), 0x{hash}));
+}}"#
+            );
+            TestCase::program_text(Cow::Owned(prog_text))
+                .with_witness_values(WitnessValues::default())
+                .assert_run_success();
+        }
+    }
+
+    #[test]
+    fn sha256_add() {
+        let prog_text = r#"fn sha256_add_hello(hasher: Ctx8) -> Ctx8 {
+    sha256_add::<5>(hasher, b"hello")
+}
+
+fn main() {
+    let hasher: Ctx8 = sha256_add_hello(jet::sha_256_ctx_8_init());
+    let hasher: Ctx8 = sha256_add::<6>(hasher, b" world");
+    let hash: u256 = jet::sha_256_ctx_8_finalize(hasher);
+    assert!(jet::eq_256(hash, sha256::<11>(b"hello world")));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn array_index_slice() {
+        let prog_text = r#"fn main() {
+    let arr: [u8; 7] = [0, 1, 2, 3, 4, 5, 6];
+    assert!(jet::eq_8(arr[0], 0));
+    assert!(jet::eq_8(arr[3], 3));
+    assert!(jet::eq_8(arr[6], 6));
+    let slice: [u8; 3] = arr[2..5];
+    assert!(jet::eq_8(slice[0], 2));
+    assert!(jet::eq_8(slice[2], 4));
+    let _: [u8; 0] = arr[4..4];
+    assert!(jet::eq_8(arr[1..6][4], 5));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn array_index_dynamic() {
+        for size in [1, 2, 3, 4, 5, 7, 8, 9, 16] {
+            let elements: Vec<String> = (0..size).map(|i| i.to_string()).collect();
+            let elements = elements.join(", ");
+            for index in 0..size {
+                let prog_text = format!(
+                    r#"fn main() {{
+    let arr: [u8; {size}] = [{elements}];
+    let i: u8 = {index};
+    assert!(jet::eq_8(arr[i], {index}));
+    assert!(jet::eq_8(unwrap(array_get::<u8, {size}>(arr, i)), {index}));
+    let j: u16 = {index};
+    assert!(jet::eq_8(arr[j], {index}));
+}}"#
+                );
+                TestCase::program_text(Cow::Owned(prog_text))
+                    .with_witness_values(WitnessValues::default())
+                    .assert_run_success();
+            }
+            for index in [size, size + 1, 255] {
+                let prog_text = format!(
+                    r#"fn main() {{
+    let arr: [u8; {size}] = [{elements}];
+    let i: u8 = {index};
+    assert!(is_none::<u8>(array_get::<u8, {size}>(arr, i)));
+}}"#
+                );
+                TestCase::program_text(Cow::Owned(prog_text))
+                    .with_witness_values(WitnessValues::default())
+                    .assert_run_success();
+            }
+        }
+    }
+
+    #[test]
+    fn array_index_dynamic_out_of_bounds() {
+        let prog_text = r#"fn main() {
+    let arr: [u8; 3] = [1, 2, 3];
+    let i: u8 = 3;
+    let x: u8 = arr[i];
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_failure();
+    }
+
+    #[test]
+    fn array_methods() {
+        let prog_text = r#"fn double(x: u8) -> u8 {
+    let (_, y): (bool, u8) = jet::add_8(x, x);
+    y
+}
+
+fn main() {
+    let arr: [u8; 5] = array_concat::<2, 3>([1, 2], [3, 4, 5]);
+    assert!(jet::eq_8(arr[1], 2));
+    assert!(jet::eq_8(arr[2], 3));
+    let doubled: [u8; 5] = array_map::<double, 5>(arr);
+    assert!(jet::eq_8(doubled[4], 10));
+    let reversed: [u8; 5] = array_reverse(doubled);
+    assert!(jet::eq_8(reversed[0], 10));
+    assert!(jet::eq_8(reversed[4], 2));
+    let pairs: [(u8, bool); 2] = array_zip([1, 2], [true, false]);
+    let (x, b): (u8, bool) = pairs[1];
+    assert!(jet::eq_8(x, 2));
+    assert!(jet::eq_1(<bool>::into(b), 0));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn array_contains() {
+        let prog_text = r#"fn main() {
+    let bytes: [u8; 5] = [1, 2, 3, 4, 5];
+    assert!(array_contains::<u8, 5>(bytes, 4));
+    assert!(jet::eq_1(<bool>::into(array_contains::<u8, 5>(bytes, 6)), 0));
+    let nibbles: [u4; 3] = [1, 7, 15];
+    assert!(array_contains::<u4, 3>(nibbles, 15));
+    assert!(jet::eq_1(<bool>::into(array_contains::<u4, 3>(nibbles, 2)), 0));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn list_methods() {
+        let prog_text = r#"fn main() {
+    let xs: List<u8, 8> = list![1, 2, 3, 4, 5];
+    assert!(jet::eq_32(list_len::<u8, 8>(xs), 5));
+    assert!(jet::eq_8(unwrap(list_get::<8>(xs, 0)), 1));
+    assert!(jet::eq_8(unwrap(list_get::<8>(xs, 4)), 5));
+    assert!(is_none::<u8>(list_get::<8>(xs, 5)));
+    assert!(is_none::<u8>(list_get::<8>(xs, 4294967295)));
+    let xs: List<u8, 8> = list_push(xs, 6);
+    assert!(jet::eq_32(list_len::<u8, 8>(xs), 6));
+    assert!(jet::eq_8(unwrap(list_get::<8>(xs, 5)), 6));
+    let ys: List<u8, 2> = list_push(list![], 7);
+    assert!(jet::eq_32(list_len::<u8, 2>(ys), 1));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn list_push_full() {
+        let prog_text = r#"fn main() {
+    let xs: List<u8, 4> = list![1, 2, 3];
+    let xs: List<u8, 4> = list_push(xs, 4);
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_failure();
+    }
 }
@@ -2,33 +2,41 @@
 
 mod builtins;
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
 
-use simplicity_unchained::jets::unchained::ElementsExtension;
-
 use either::Either;
-use simplicity::jet::Elements;
-use simplicity::node::{CoreConstructible as _, JetConstructible as _};
-use simplicity::{types, Cmr, FailEntropy};
+use simplicity::jet::{Core, Elements, Jet};
+use simplicity::node::{
+    CoreConstructible as _, DisconnectConstructible as _, JetConstructible as _,
+};
+use simplicity::{types, BitMachine, Cmr, FailEntropy};
+use simplicity_unchained::jets::unchained::ElementsExtension;
 
-use self::builtins::array_fold;
+use self::builtins::{
+    array_concat, array_contains, array_fold, array_get, array_map, array_reverse, array_slice,
+    array_zip, eq_halves, list_get, list_len, list_push, sha256_add,
+};
 use crate::array::{BTreeSlice, Partition};
 use crate::ast::{
-    Call, CallName, Expression, ExpressionInner, Match, Program, SingleExpression,
+    Call, CallName, CustomFunction, Expression, ExpressionInner, Match, Program, SingleExpression,
     SingleExpressionInner, Statement,
 };
-use crate::debug::CallTracker;
+use crate::debug::{CallTracker, DebugSymbols, TrackedFunction, TrackedMatch};
 use crate::error::{Error, RichError, Span, WithSpan};
+use crate::jet::JetSet;
 use crate::named::{self, CoreExt, PairBuilder};
 use crate::num::{NonZeroPow2Usize, Pow2Usize};
 use crate::pattern::{BasePattern, Pattern};
-use crate::str::WitnessName;
-use crate::types::{StructuralType, TypeDeconstructible};
+use crate::str::{JetName, WitnessName};
+use crate::types::{StructuralType, TypeDeconstructible, UIntType};
 use crate::value::StructuralValue;
-use crate::witness::Arguments;
+use crate::witness::{Arguments, WitnessValues};
 use crate::Value;
 
-type ProgNode<'brand> = Arc<named::ConstructNode<'brand, ElementsExtension>>;
+type ProgNode<'brand, J> = Arc<named::ConstructNode<'brand, J>>;
 
 /// Each SimplicityHL expression expects an _input value_.
 /// A SimplicityHL expression is translated into a Simplicity expression
@@ -71,6 +79,10 @@ struct Scope<'brand> {
     ctx: simplicity::types::Context<'brand>,
     /// Tracker of function calls.
     call_tracker: Arc<CallTracker>,
+    /// Match expressions and functions that have been compiled so far.
+    ///
+    /// The debug symbols are shared with the child scopes of called functions.
+    debug_symbols: Rc<RefCell<DebugSymbols>>,
     /// Values for parameters inside the SimplicityHL program.
     arguments: Arguments,
     include_debug_symbols: bool,
@@ -95,6 +107,7 @@ impl<'brand> Scope<'brand> {
             variables: vec![vec![Pattern::Ignore]],
             ctx,
             call_tracker,
+            debug_symbols: Rc::default(),
             arguments,
             include_debug_symbols,
         }
@@ -106,6 +119,7 @@ impl<'brand> Scope<'brand> {
             variables: vec![vec![input]],
             ctx: self.ctx.shallow_clone(),
             call_tracker: Arc::clone(&self.call_tracker),
+            debug_symbols: Rc::clone(&self.debug_symbols),
             arguments: self.arguments.clone(),
             include_debug_symbols: self.include_debug_symbols,
         }
@@ -186,7 +200,7 @@ impl<'brand> Scope<'brand> {
     /// ```
     ///
     /// The expression `drop (IOH & OH)` returns the seeked value.
-    pub fn get(&self, target: &BasePattern) -> Option<PairBuilder<ProgNode<'brand>>> {
+    pub fn get<J: Jet>(&self, target: &BasePattern) -> Option<PairBuilder<ProgNode<'brand, J>>> {
         BasePattern::from(&self.get_input_pattern()).translate(&self.ctx, target)
     }
 
@@ -201,12 +215,12 @@ impl<'brand> Scope<'brand> {
     ///
     /// The debug symbol is attached in such a way that a Simplicity runtime without support
     /// for debug symbols will simply ignore it. The semantics of the program remain unchanged.
-    pub fn with_debug_symbol<S: AsRef<Span>>(
+    pub fn with_debug_symbol<J: Jet, S: AsRef<Span>>(
         &mut self,
-        args: PairBuilder<ProgNode<'brand>>,
-        body: &ProgNode<'brand>,
+        args: PairBuilder<ProgNode<'brand, J>>,
+        body: &ProgNode<'brand, J>,
         span: &S,
-    ) -> Result<PairBuilder<ProgNode<'brand>>, RichError> {
+    ) -> Result<PairBuilder<ProgNode<'brand, J>>, RichError> {
         match self.call_tracker.get_cmr(span.as_ref()) {
             Some(cmr) if self.include_debug_symbols => {
                 let false_and_args = ProgNode::bit(self.ctx(), false).pair(args);
@@ -217,6 +231,35 @@ impl<'brand> Scope<'brand> {
         }
     }
 
+    /// Remember that the given match expression compiles to a `case` node with the given CMR.
+    pub fn track_match(&mut self, cmr: Cmr, tracked_match: TrackedMatch) {
+        self.debug_symbols
+            .borrow_mut()
+            .insert_match(cmr, tracked_match);
+    }
+
+    /// Compile the body of the given custom function in a child scope.
+    ///
+    /// Remember that the function compiles to a body with the resulting CMR.
+    pub fn compile_function<J: JetSet>(
+        &mut self,
+        function: &CustomFunction,
+    ) -> Result<PairBuilder<ProgNode<'brand, J>>, RichError> {
+        let mut function_scope = self.child(function.params_pattern());
+        let body = function.body().compile_body(&mut function_scope)?;
+        let tracked_function = TrackedFunction::new(function.name().clone(), *function.span());
+        self.debug_symbols
+            .borrow_mut()
+            .insert_function(body.as_ref().cmr(), tracked_function);
+        Ok(body)
+    }
+
+    /// Add the match expressions and functions that have been compiled so far
+    /// to the given debug symbols.
+    pub fn insert_debug_symbols(&self, debug_symbols: &mut DebugSymbols) {
+        debug_symbols.extend(self.debug_symbols.borrow().clone());
+    }
+
     pub fn get_argument(&self, name: &WitnessName) -> &Value {
         self.arguments
             .get(name)
@@ -224,32 +267,202 @@ impl<'brand> Scope<'brand> {
     }
 }
 
-fn compile_blk<'brand>(
+/// Control flow of a compiled expression.
+///
+/// Expressions that contain `return` or `?` may return early from the enclosing function.
+/// Let `R` be the output type of the enclosing function,
+/// let `A` be the input type of the expression
+/// and let `T` be the output type of the expression.
+#[derive(Debug, Clone)]
+enum Flow<P> {
+    /// The expression always produces its value.
+    ///
+    /// ```text
+    /// A → T
+    /// ```
+    Value(P),
+    /// The expression either returns early (left) or produces its value (right).
+    ///
+    /// ```text
+    /// A → R + T
+    /// ```
+    Exit(P),
+}
+
+type FlowBuilder<'brand, J> = Flow<PairBuilder<ProgNode<'brand, J>>>;
+
+impl<'brand, J: Jet> FlowBuilder<'brand, J> {
+    /// Check if the expression may return early.
+    fn is_exit(&self) -> bool {
+        matches!(self, Self::Exit(..))
+    }
+
+    /// Convert the expression into an expression that may return early.
+    ///
+    /// ```text
+    /// A → T
+    /// -------------------
+    /// injr : A → R + T
+    /// ```
+    fn into_exit(self) -> PairBuilder<ProgNode<'brand, J>> {
+        match self {
+            Self::Value(expr) => expr.injr(),
+            Self::Exit(expr) => expr,
+        }
+    }
+
+    /// Run `then` on the value of the expression and on the input of the expression,
+    /// unless the expression returns early.
+    ///
+    /// ```text
+    /// self : A → R + T
+    /// then : T × A → R + U
+    /// ---------------------------------------------
+    /// (self ▵ iden); case (injl OH) then : A → R + U
+    /// ```
+    fn bind(
+        self,
+        then: &ProgNode<'brand, J>,
+        ctx: &simplicity::types::Context<'brand>,
+    ) -> Result<PairBuilder<ProgNode<'brand, J>>, simplicity::types::Error> {
+        match self {
+            Self::Value(expr) => expr.pair(PairBuilder::iden(ctx)).comp(then),
+            Self::Exit(expr) => {
+                let early = ProgNode::o().h(ctx).injl();
+                let case = ProgNode::case(early.as_ref(), then)?;
+                expr.pair(PairBuilder::iden(ctx)).comp(&case)
+            }
+        }
+    }
+
+    /// Pair the values of two expressions.
+    /// The right expression is not run if the left expression returns early.
+    fn pair(
+        self,
+        right: Self,
+        ctx: &simplicity::types::Context<'brand>,
+    ) -> Result<Self, simplicity::types::Error> {
+        if let (Self::Value(left), Self::Value(right)) = (&self, &right) {
+            return Ok(Self::Value(left.clone().pair(right.clone())));
+        }
+        // (IH; right) ▵ OH : T × A → (R + U) × T
+        // case (injl OH) (injr (IH ▵ OH)) : (R + U) × T → R + T × U
+        let right = ProgNode::i().h(ctx).comp(&right.into_exit())?;
+        let early = ProgNode::o().h(ctx).injl();
+        let both = ProgNode::i().h(ctx).pair(ProgNode::o().h(ctx)).injr();
+        let case = ProgNode::case(early.as_ref(), both.as_ref())?;
+        let then = right.pair(ProgNode::o().h(ctx)).comp(&case)?;
+        self.bind(then.as_ref(), ctx).map(Self::Exit)
+    }
+
+    /// Pair the values of a sequence of expressions into a right-nested tuple.
+    ///
+    /// ```text
+    /// e_0 × (e_1 × (… × (e_(n-1) × 1)))
+    /// ```
+    ///
+    /// The expressions are run from left to right.
+    /// Subsequent expressions are not run if an expression returns early.
+    fn sequence(
+        flows: Vec<Self>,
+        ctx: &simplicity::types::Context<'brand>,
+    ) -> Result<Self, simplicity::types::Error> {
+        flows
+            .into_iter()
+            .rev()
+            .try_fold(Self::Value(PairBuilder::unit(ctx)), |acc, flow| {
+                flow.pair(acc, ctx)
+            })
+    }
+
+    /// Apply `f` to the value of the expression, unless the expression returns early.
+    ///
+    /// Function `f` takes an expression that produces the value.
+    fn map<F>(
+        self,
+        ctx: &simplicity::types::Context<'brand>,
+        f: F,
+    ) -> Result<Self, simplicity::types::Error>
+    where
+        F: FnOnce(
+            PairBuilder<ProgNode<'brand, J>>,
+        ) -> Result<PairBuilder<ProgNode<'brand, J>>, simplicity::types::Error>,
+    {
+        match self {
+            Self::Value(expr) => f(expr).map(Self::Value),
+            Self::Exit(expr) => {
+                let then = f(ProgNode::o().h(ctx))?.injr();
+                Self::Exit(expr).bind(then.as_ref(), ctx).map(Self::Exit)
+            }
+        }
+    }
+
+    /// Return the value of a function body whose expression may return early.
+    ///
+    /// ```text
+    /// self : A → R + R
+    /// ------------------------------------
+    /// (self ▵ unit); case OH OH : A → R
+    /// ```
+    fn merge(
+        self,
+        ctx: &simplicity::types::Context<'brand>,
+    ) -> Result<PairBuilder<ProgNode<'brand, J>>, simplicity::types::Error> {
+        match self {
+            Self::Value(expr) => Ok(expr),
+            Self::Exit(expr) => {
+                let value = ProgNode::o().h(ctx);
+                let case = ProgNode::case(value.as_ref(), value.as_ref())?;
+                expr.pair(PairBuilder::unit(ctx)).comp(&case)
+            }
+        }
+    }
+}
+
+fn compile_blk<'brand, J: JetSet>(
     stmts: &[Statement],
     scope: &mut Scope<'brand>,
     index: usize,
     last_expr: Option<&Expression>,
-) -> Result<PairBuilder<ProgNode<'brand>>, RichError> {
+) -> Result<FlowBuilder<'brand, J>, RichError> {
     if index >= stmts.len() {
         return match last_expr {
             Some(expr) => expr.compile(scope),
-            None => Ok(PairBuilder::unit(scope.ctx())),
+            None => Ok(Flow::Value(PairBuilder::unit(scope.ctx()))),
         };
     }
     match &stmts[index] {
         Statement::Assignment(assignment) => {
             let expr = assignment.expression().compile(scope)?;
             scope.insert(assignment.pattern().clone());
-            let left = expr.pair(PairBuilder::iden(scope.ctx()));
             let right = compile_blk(stmts, scope, index + 1, last_expr)?;
-            left.comp(&right).with_span(assignment)
+            match (expr, right) {
+                (Flow::Value(expr), Flow::Value(right)) => {
+                    let left = expr.pair(PairBuilder::iden(scope.ctx()));
+                    left.comp(&right).with_span(assignment).map(Flow::Value)
+                }
+                (expr, right) => expr
+                    .bind(right.into_exit().as_ref(), scope.ctx())
+                    .with_span(assignment)
+                    .map(Flow::Exit),
+            }
         }
         Statement::Expression(expression) => {
             let left = expression.compile(scope)?;
             let right = compile_blk(stmts, scope, index + 1, last_expr)?;
-            let pair = left.pair(right);
-            let drop_iden = ProgNode::drop_(&ProgNode::iden(scope.ctx()));
-            pair.comp(&drop_iden).with_span(expression)
+            match (left, right) {
+                (Flow::Value(left), Flow::Value(right)) => {
+                    let pair = left.pair(right);
+                    let drop_iden = ProgNode::drop_(&ProgNode::iden(scope.ctx()));
+                    pair.comp(&drop_iden).with_span(expression).map(Flow::Value)
+                }
+                (left, right) => {
+                    let then = ProgNode::drop_(right.into_exit().as_ref());
+                    left.bind(&then, scope.ctx())
+                        .with_span(expression)
+                        .map(Flow::Exit)
+                }
+            }
         }
     }
 }
@@ -261,11 +474,12 @@ impl Program {
     ///
     /// The supplied `arguments` are consistent with the program's parameters.
     /// Call [`Arguments::is_consistent`] before calling this method!
-    pub fn compile(
+    pub fn compile<J: JetSet>(
         &self,
         arguments: Arguments,
         include_debug_symbols: bool,
-    ) -> Result<Arc<named::CommitNode<ElementsExtension>>, RichError> {
+        debug_symbols: &mut DebugSymbols,
+    ) -> Result<Arc<named::CommitNode<J>>, crate::Error> {
         types::Context::with_context(|ctx| {
             let mut scope = Scope::new(
                 ctx,
@@ -275,19 +489,63 @@ impl Program {
             );
 
             let main = self.main();
-            let construct = main.compile(&mut scope).map(PairBuilder::build)?;
-            // SimplicityHL types should be correct by construction. If not, assign the
-            // whole main function as the span for them, which is as sensible as anything.
-            named::finalize_types(&construct).with_span(main)
+            let construct = main.compile_body(&mut scope).map(PairBuilder::build)?;
+            scope.insert_debug_symbols(debug_symbols);
+            // SimplicityHL types should be correct by construction.
+            // If not, report the type error of Simplicity as is.
+            named::finalize_types(&construct).map_err(crate::Error::Types)
         })
     }
 }
 
 impl Expression {
-    fn compile<'brand>(
+    /// Compile the expression as the body of a function.
+    ///
+    /// Early returns from the function produce the output of the function.
+    fn compile_body<'brand, J: JetSet>(
+        &self,
+        scope: &mut Scope<'brand>,
+    ) -> Result<PairBuilder<ProgNode<'brand, J>>, RichError> {
+        let body = self.compile(scope)?;
+        body.merge(scope.ctx()).with_span(self)
+    }
+
+    /// Evaluate the expression at compile time.
+    ///
+    /// The expression is compiled to Simplicity target code with [`Core`] jets,
+    /// which is run on the Bit Machine.
+    ///
+    /// ## Errors
+    ///
+    /// - The expression cannot be compiled with [`Core`] jets.
+    /// - The expression fails when it is run.
+    pub(crate) fn eval_const(&self) -> Result<Value, RichError> {
+        let commit = types::Context::with_context(|ctx| {
+            let mut scope = Scope::new(ctx, Arc::default(), Arguments::default(), false);
+            let construct = self
+                .compile_body::<Core>(&mut scope)
+                .map(PairBuilder::build)?;
+            named::finalize_types(&construct).with_span(self)
+        })?;
+        let redeem = named::populate_witnesses(&commit, WitnessValues::default(), &HashMap::new())
+            .map_err(|error| Error::ConstEvaluationFailed(error.to_string()))
+            .with_span(self)?;
+        let mut mac = BitMachine::for_program(&redeem)
+            .map_err(|error| Error::ConstEvaluationFailed(error.to_string()))
+            .with_span(self)?;
+        let output = mac
+            .exec(&redeem, &())
+            .map_err(|error| Error::ConstEvaluationFailed(error.to_string()))
+            .with_span(self)?;
+        Value::reconstruct(&StructuralValue::from(output), self.ty())
+            .ok_or(Error::ExpressionUnexpectedType(self.ty().clone()))
+            .with_span(self)
+    }
+
+    fn compile<'brand, J: JetSet>(
         &self,
         scope: &mut Scope<'brand>,
-    ) -> Result<PairBuilder<ProgNode<'brand>>, RichError> {
+    ) -> Result<FlowBuilder<'brand, J>, RichError> {
         match self.inner() {
             ExpressionInner::Block(stmts, expr) => {
                 scope.push_scope();
@@ -301,22 +559,25 @@ impl Expression {
 }
 
 impl SingleExpression {
-    fn compile<'brand>(
+    fn compile<'brand, J: JetSet>(
         &self,
         scope: &mut Scope<'brand>,
-    ) -> Result<PairBuilder<ProgNode<'brand>>, RichError> {
-        let expr = match self.inner() {
+    ) -> Result<FlowBuilder<'brand, J>, RichError> {
+        let expr: FlowBuilder<J> = match self.inner() {
             SingleExpressionInner::Constant(value) => {
                 let value = StructuralValue::from(value);
-                PairBuilder::unit_scribe(scope.ctx(), value.as_ref())
+                Flow::Value(PairBuilder::unit_scribe(scope.ctx(), value.as_ref()))
+            }
+            SingleExpressionInner::Witness(name) => {
+                Flow::Value(PairBuilder::witness(scope.ctx(), name.clone()))
             }
-            SingleExpressionInner::Witness(name) => PairBuilder::witness(scope.ctx(), name.clone()),
             SingleExpressionInner::Parameter(name) => {
                 let value = StructuralValue::from(scope.get_argument(name));
-                PairBuilder::unit_scribe(scope.ctx(), value.as_ref())
+                Flow::Value(PairBuilder::unit_scribe(scope.ctx(), value.as_ref()))
             }
             SingleExpressionInner::Variable(identifier) => scope
                 .get(&BasePattern::Identifier(identifier.clone()))
+                .map(Flow::Value)
                 .ok_or(Error::UndefinedVariable(identifier.clone()))
                 .with_span(self)?,
             SingleExpressionInner::Expression(expr) => expr.compile(scope)?,
@@ -324,64 +585,164 @@ impl SingleExpression {
                 let compiled = elements
                     .iter()
                     .map(|e| e.compile(scope))
-                    .collect::<Result<Vec<PairBuilder<ProgNode>>, RichError>>()?;
-                let tree = BTreeSlice::from_slice(&compiled);
-                tree.fold(PairBuilder::pair)
-                    .unwrap_or_else(|| PairBuilder::unit(scope.ctx()))
+                    .collect::<Result<Vec<FlowBuilder<J>>, RichError>>()?;
+                compile_elements(compiled, scope.ctx(), |compiled| {
+                    let tree = BTreeSlice::from_slice(compiled);
+                    tree.fold(PairBuilder::pair)
+                        .unwrap_or_else(|| PairBuilder::unit(scope.ctx()))
+                })
+                .with_span(self)?
             }
             SingleExpressionInner::List(elements) => {
                 let compiled = elements
                     .iter()
                     .map(|e| e.compile(scope))
-                    .collect::<Result<Vec<PairBuilder<ProgNode>>, RichError>>()?;
+                    .collect::<Result<Vec<FlowBuilder<J>>, RichError>>()?;
                 let bound = self.ty().as_list().unwrap().1;
-                let partition = Partition::from_slice(&compiled, bound);
-                partition.fold(
-                    |block, _size: usize| {
-                        let tree = BTreeSlice::from_slice(block);
-                        match tree.fold(PairBuilder::pair) {
-                            None => PairBuilder::unit(scope.ctx()).injl(),
-                            Some(pair) => pair.injr(),
-                        }
-                    },
-                    PairBuilder::pair,
-                )
+                compile_elements(compiled, scope.ctx(), |compiled| {
+                    let partition = Partition::from_slice(compiled, bound);
+                    partition.fold(
+                        |block, _size: usize| {
+                            let tree = BTreeSlice::from_slice(block);
+                            match tree.fold(PairBuilder::pair) {
+                                None => PairBuilder::unit(scope.ctx()).injl(),
+                                Some(pair) => pair.injr(),
+                            }
+                        },
+                        PairBuilder::pair,
+                    )
+                })
+                .with_span(self)?
             }
-            SingleExpressionInner::Option(None) => PairBuilder::unit(scope.ctx()).injl(),
-            SingleExpressionInner::Either(Either::Left(inner)) => {
-                inner.compile(scope).map(PairBuilder::injl)?
+            SingleExpressionInner::Option(None) => {
+                Flow::Value(PairBuilder::unit(scope.ctx()).injl())
             }
+            SingleExpressionInner::Either(Either::Left(inner)) => inner
+                .compile(scope)?
+                .map(scope.ctx(), |inner| Ok(inner.injl()))
+                .with_span(self)?,
             SingleExpressionInner::Either(Either::Right(inner))
-            | SingleExpressionInner::Option(Some(inner)) => {
-                inner.compile(scope).map(PairBuilder::injr)?
-            }
+            | SingleExpressionInner::Option(Some(inner)) => inner
+                .compile(scope)?
+                .map(scope.ctx(), |inner| Ok(inner.injr()))
+                .with_span(self)?,
             SingleExpressionInner::Call(call) => call.compile(scope)?,
             SingleExpressionInner::Match(match_) => match_.compile(scope)?,
+            SingleExpressionInner::Return(None) => {
+                Flow::Exit(PairBuilder::unit(scope.ctx()).injl())
+            }
+            SingleExpressionInner::Return(Some(inner)) => match inner.compile(scope)? {
+                Flow::Value(inner) => Flow::Exit(inner.injl()),
+                Flow::Exit(inner) => {
+                    // Both the early return and the value of the inner expression
+                    // are returned from the function.
+                    let early = ProgNode::o().h(scope.ctx()).injl();
+                    let case = ProgNode::case(early.as_ref(), early.as_ref()).with_span(self)?;
+                    inner
+                        .pair(PairBuilder::unit(scope.ctx()))
+                        .comp(&case)
+                        .map(Flow::Exit)
+                        .with_span(self)?
+                }
+            },
+            SingleExpressionInner::Try(inner) => {
+                // Option<T> = 1 + T and Either<L, T> = L + T.
+                // The left value is returned from the function inside the same kind of sum.
+                let early = ProgNode::o().h(scope.ctx()).injl().injl();
+                let value = ProgNode::o().h(scope.ctx()).injr();
+                let case = ProgNode::case(early.as_ref(), value.as_ref()).with_span(self)?;
+                inner
+                    .compile(scope)?
+                    .bind(&case, scope.ctx())
+                    .map(Flow::Exit)
+                    .with_span(self)?
+            }
         };
 
-        scope
-            .ctx()
-            .unify(
-                &expr.as_ref().cached_data().arrow().target,
-                &StructuralType::from(self.ty()).to_unfinalized(scope.ctx()),
-                "",
-            )
-            .with_span(self)?;
+        if let Flow::Value(value) = &expr {
+            scope
+                .ctx()
+                .unify(
+                    &value.as_ref().cached_data().arrow().target,
+                    &StructuralType::from(self.ty()).to_unfinalized(scope.ctx()),
+                    "",
+                )
+                .with_span(self)?;
+        }
         Ok(expr)
     }
 }
 
+/// Compile a compound value from the compiled expressions of its elements.
+///
+/// Function `build` combines the expressions of the elements into the compound value.
+/// If an element may return early, then the elements are run in sequence,
+/// and `build` combines selectors of the sequenced values.
+fn compile_elements<'brand, J: Jet, F>(
+    compiled: Vec<FlowBuilder<'brand, J>>,
+    ctx: &simplicity::types::Context<'brand>,
+    build: F,
+) -> Result<FlowBuilder<'brand, J>, simplicity::types::Error>
+where
+    F: FnOnce(&[PairBuilder<ProgNode<'brand, J>>]) -> PairBuilder<ProgNode<'brand, J>>,
+{
+    if !compiled.iter().any(Flow::is_exit) {
+        let compiled: Vec<_> = compiled
+            .into_iter()
+            .map(|flow| match flow {
+                Flow::Value(expr) => expr,
+                Flow::Exit(..) => unreachable!("no early returns"),
+            })
+            .collect();
+        return Ok(Flow::Value(build(&compiled)));
+    }
+
+    let len = compiled.len();
+    let sequence = Flow::sequence(compiled, ctx)?;
+    // The sequenced values e_0 × (e_1 × (… × 1)) are the left component of the input of `then`.
+    let selectors: Vec<_> = (0..len)
+        .map(|index| {
+            let selector = (0..index).fold(ProgNode::o(), |selector, _| selector.i());
+            selector.o().h(ctx)
+        })
+        .collect();
+    let then = build(&selectors).injr();
+    sequence.bind(then.as_ref(), ctx).map(Flow::Exit)
+}
+
 impl Call {
-    fn compile<'brand>(
+    fn compile<'brand, J: JetSet>(
         &self,
         scope: &mut Scope<'brand>,
-    ) -> Result<PairBuilder<ProgNode<'brand>>, RichError> {
+    ) -> Result<FlowBuilder<'brand, J>, RichError> {
         let args_ast = SingleExpression::tuple(self.args().clone(), *self.as_ref());
-        let args = args_ast.compile(scope)?;
+        match args_ast.compile(scope)? {
+            Flow::Value(args) => self.compile_with_args(args, scope).map(Flow::Value),
+            Flow::Exit(args) => {
+                // Call the function on the arguments, unless they return early.
+                let call = self.compile_with_args(ProgNode::o().h(scope.ctx()), scope)?;
+                Flow::Exit(args)
+                    .bind(call.injr().as_ref(), scope.ctx())
+                    .map(Flow::Exit)
+                    .with_span(self)
+            }
+        }
+    }
 
+    fn compile_with_args<'brand, J: JetSet>(
+        &self,
+        args: PairBuilder<ProgNode<'brand, J>>,
+        scope: &mut Scope<'brand>,
+    ) -> Result<PairBuilder<ProgNode<'brand, J>>, RichError> {
         match self.name() {
             CallName::Jet(name) => {
-                let jet = ProgNode::jet(scope.ctx(), *name);
+                let jet = J::from_extension(*name)
+                    .ok_or_else(|| {
+                        let name = JetName::from_str_unchecked(&name.to_string());
+                        Error::JetNotSupported(name, J::NAME)
+                    })
+                    .with_span(self)?;
+                let jet = ProgNode::jet(scope.ctx(), jet);
                 scope.with_debug_symbol(args, &jet, self)
             }
             CallName::UnwrapLeft(..) => {
@@ -412,11 +773,12 @@ impl Call {
                 args.comp(&body).with_span(self)
             }
             CallName::Assert => {
-                let jet = ProgNode::jet(scope.ctx(), ElementsExtension::Elements(Elements::Verify));
+                let jet = ProgNode::jet(scope.ctx(), J::verify());
                 scope.with_debug_symbol(args, &jet, self)
             }
             CallName::Panic => {
                 // panic! ignores its arguments
+                // The entropy is fixed, so the CMR is deterministic
                 let fail = ProgNode::fail(scope.ctx(), FailEntropy::ZERO);
                 scope.with_debug_symbol(args, &fail, self)
             }
@@ -433,30 +795,172 @@ impl Call {
                 Ok(args)
             }
             CallName::Custom(function) => {
-                let mut function_scope = scope.child(function.params_pattern());
-                let body = function.body().compile(&mut function_scope)?;
+                let body = scope.compile_function(function)?;
                 args.comp(&body).with_span(self)
             }
             CallName::Fold(function, bound) => {
-                let mut function_scope = scope.child(function.params_pattern());
-                let body = function.body().compile(&mut function_scope)?;
+                let body = scope.compile_function(function)?;
                 let fold_body = list_fold(*bound, body.as_ref()).with_span(self)?;
                 args.comp(&fold_body).with_span(self)
             }
             CallName::ArrayFold(function, size) => {
-                let mut function_scope = scope.child(function.params_pattern());
-                let body = function.body().compile(&mut function_scope)?;
+                let body = scope.compile_function(function)?;
                 let fold_body = array_fold(*size, body.as_ref()).with_span(self)?;
                 args.comp(&fold_body).with_span(self)
             }
             CallName::ForWhile(function, bit_width) => {
-                let mut function_scope = scope.child(function.params_pattern());
-                let body = function.body().compile(&mut function_scope)?;
+                let body = scope.compile_function(function)?;
                 let fold_body = for_while(*bit_width, body).with_span(self)?;
                 args.comp(&fold_body).with_span(self)
             }
+            CallName::Disconnect(function, delegate) => {
+                // The function takes the CMR of the delegated program and the call argument.
+                // The delegated program is filled in when the program is satisfied.
+                let body = scope.compile_function(function)?;
+                let disconnect = ProgNode::disconnect(body.as_ref(), delegate).with_span(self)?;
+                args.comp(&disconnect).with_span(self)
+            }
+            CallName::Sha256(size) => {
+                let init = ProgNode::jet(scope.ctx(), self.jet(Elements::Sha256Ctx8Init)?);
+                let add = sha256_add(*size, &self.sha256_add_jets(scope)?).with_span(self)?;
+                let finalize = ProgNode::jet(scope.ctx(), self.jet(Elements::Sha256Ctx8Finalize)?);
+                let body = PairBuilder::unit(scope.ctx())
+                    .comp(&init)
+                    .with_span(self)?
+                    .pair(PairBuilder::iden(scope.ctx()))
+                    .comp(&add)
+                    .and_then(|hasher| hasher.comp(&finalize))
+                    .with_span(self)?;
+                args.comp(&body).with_span(self)
+            }
+            CallName::Sha256Add(size) => {
+                let add = sha256_add(*size, &self.sha256_add_jets(scope)?).with_span(self)?;
+                args.comp(&add).with_span(self)
+            }
+            CallName::ArrayIndex(size, index) => {
+                let element = array_slice(scope.ctx(), *size, *index..*index + 1);
+                args.comp(&element).with_span(self)
+            }
+            CallName::ArrayIndexDynamic(size, ty) => {
+                let get = array_get(scope.ctx(), *size, ty.bit_width().get()).with_span(self)?;
+                let extract_inner = ProgNode::assertr_take(
+                    Cmr::fail(FailEntropy::ZERO),
+                    &ProgNode::iden(scope.ctx()),
+                );
+                let body = PairBuilder::iden(scope.ctx())
+                    .comp(&get)
+                    .and_then(|option| {
+                        option
+                            .pair(PairBuilder::unit(scope.ctx()))
+                            .comp(&extract_inner)
+                    })
+                    .with_span(self)?;
+                scope.with_debug_symbol(args, body.as_ref(), self)
+            }
+            CallName::ArraySlice(size, start, end) => {
+                let slice = array_slice(scope.ctx(), *size, *start..*end);
+                args.comp(&slice).with_span(self)
+            }
+            CallName::ArrayConcat(size_l, size_r) => {
+                let concat = array_concat(scope.ctx(), size_l.get(), size_r.get());
+                args.comp(&concat).with_span(self)
+            }
+            CallName::ArrayMap(function, size) => {
+                let body = scope.compile_function(function)?;
+                let map_body = array_map(*size, body.as_ref()).with_span(self)?;
+                args.comp(&map_body).with_span(self)
+            }
+            CallName::ArrayZip(size) => {
+                let zip = array_zip(scope.ctx(), *size).with_span(self)?;
+                args.comp(&zip).with_span(self)
+            }
+            CallName::ArrayReverse(size) => {
+                let reverse = array_reverse(scope.ctx(), *size);
+                args.comp(&reverse).with_span(self)
+            }
+            CallName::ArrayContains(ty, size) => {
+                let eq = self.eq_function(*ty, scope)?;
+                let contains = array_contains(*size, &eq).with_span(self)?;
+                args.comp(&contains).with_span(self)
+            }
+            CallName::ArrayGet(ty, size) => {
+                let get =
+                    array_get(scope.ctx(), size.get(), ty.bit_width().get()).with_span(self)?;
+                args.comp(&get).with_span(self)
+            }
+            CallName::ListLen(_, bound) => {
+                let len = list_len(scope.ctx(), *bound).with_span(self)?;
+                args.comp(&len).with_span(self)
+            }
+            CallName::ListPush(bound) => {
+                let push = list_push(scope.ctx(), *bound).with_span(self)?;
+                args.comp(&push).with_span(self)
+            }
+            CallName::ListGet(bound) => {
+                let get = list_get(scope.ctx(), *bound).with_span(self)?;
+                args.comp(&get).with_span(self)
+            }
         }
     }
+
+    /// Return the function that compares two integers of the given type for equality.
+    fn eq_function<'brand, J: JetSet>(
+        &self,
+        ty: UIntType,
+        scope: &Scope<'brand>,
+    ) -> Result<ProgNode<'brand, J>, RichError> {
+        let jet = match ty {
+            UIntType::U1 => Elements::Eq1,
+            UIntType::U8 => Elements::Eq8,
+            UIntType::U16 => Elements::Eq16,
+            UIntType::U32 => Elements::Eq32,
+            UIntType::U64 => Elements::Eq64,
+            UIntType::U256 => Elements::Eq256,
+            UIntType::U2 | UIntType::U4 | UIntType::U128 => {
+                // There are no jets for these types, so compare their halves.
+                let half_width = ty.bit_width().checked_div2().expect("at least two bits");
+                let half_ty = UIntType::from_bit_width(half_width).expect("half of integer type");
+                let eq_half = self.eq_function(half_ty, scope)?;
+                return eq_halves(&eq_half).with_span(self);
+            }
+        };
+        self.jet(jet).map(|jet| ProgNode::jet(scope.ctx(), jet))
+    }
+
+    /// Convert the Elements jet into the jet set `J`.
+    ///
+    /// Fail if the jet set does not contain the jet.
+    fn jet<J: JetSet>(&self, jet: Elements) -> Result<J, RichError> {
+        let jet = ElementsExtension::Elements(jet);
+        J::from_extension(jet)
+            .ok_or_else(|| {
+                let name = JetName::from_str_unchecked(&jet.to_string());
+                Error::JetNotSupported(name, J::NAME)
+            })
+            .with_span(self)
+    }
+
+    /// Return the jets that add `2^i` bytes to a SHA256 hash context, in order of `i`.
+    fn sha256_add_jets<'brand, J: JetSet>(
+        &self,
+        scope: &Scope<'brand>,
+    ) -> Result<Vec<ProgNode<'brand, J>>, RichError> {
+        [
+            Elements::Sha256Ctx8Add1,
+            Elements::Sha256Ctx8Add2,
+            Elements::Sha256Ctx8Add4,
+            Elements::Sha256Ctx8Add8,
+            Elements::Sha256Ctx8Add16,
+            Elements::Sha256Ctx8Add32,
+            Elements::Sha256Ctx8Add64,
+            Elements::Sha256Ctx8Add128,
+            Elements::Sha256Ctx8Add256,
+            Elements::Sha256Ctx8Add512,
+        ]
+        .into_iter()
+        .map(|jet| self.jet(jet).map(|jet| ProgNode::jet(scope.ctx(), jet)))
+        .collect()
+    }
 }
 
 /// Fold a list of less than `2^n` elements using function `f`.
@@ -468,13 +972,13 @@ impl Call {
 /// The fold `(fold f)_n : E^(<2^n) × A → A`
 /// takes the list of type `E^(<2^n)` and an initial accumulator of type `A`,
 /// and it produces the final accumulator of type `A`.
-fn list_fold<'brand>(
+fn list_fold<'brand, J: Jet>(
     bound: NonZeroPow2Usize,
-    f: &ProgNode<'brand>,
-) -> Result<ProgNode<'brand>, simplicity::types::Error> {
-    fn next_f_array<'brand>(
-        f_array: &ProgNode<'brand>,
-    ) -> Result<ProgNode<'brand>, simplicity::types::Error> {
+    f: &ProgNode<'brand, J>,
+) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
+    fn next_f_array<'brand, J: Jet>(
+        f_array: &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
         /* f_(n + 1) :  E^(2^(n + 1)) × A → A
          * f_(n + 1) := OIH ▵ (OOH ▵ IH; f_n); f_n
          */
@@ -484,10 +988,10 @@ fn list_fold<'brand>(
         let half2_acc = ProgNode::o().i().h(ctx).pair(updated_acc);
         half2_acc.comp(f_array).map(PairBuilder::build)
     }
-    fn next_f_fold<'brand>(
-        f_array: &ProgNode<'brand>,
-        f_fold: &ProgNode<'brand>,
-    ) -> Result<ProgNode<'brand>, simplicity::types::Error> {
+    fn next_f_fold<'brand, J: Jet>(
+        f_array: &ProgNode<'brand, J>,
+        f_fold: &ProgNode<'brand, J>,
+    ) -> Result<ProgNode<'brand, J>, simplicity::types::Error> {
         /* (fold f)_(n + 1) :  E<2^(n + 1) × A → A
          * (fold f)_(n + 1) := OOH ▵ (OIH ▵ IH);
          *                     case (drop (fold f)_n)
@@ -545,18 +1049,18 @@ fn list_fold<'brand>(
 /// In this case, the loop continues without returning anything.
 /// The loop returns the final iterator after the final iteration
 /// if `f` never returned a successful output.
-fn for_while<'brand>(
+fn for_while<'brand, J: Jet>(
     bit_width: Pow2Usize,
-    f: PairBuilder<ProgNode<'brand>>,
-) -> Result<PairBuilder<ProgNode<'brand>>, simplicity::types::Error> {
+    f: PairBuilder<ProgNode<'brand, J>>,
+) -> Result<PairBuilder<ProgNode<'brand, J>>, simplicity::types::Error> {
     /* for_while_0 f :  E × A → A
      * for_while_0 f := (OH ▵ (IH ▵ false); f) ▵ IH;
      *                  case (injl OH)
      *                       (OH ▵ (IH ▵ true); f)
      */
-    fn for_while_0<'brand>(
-        f: &ProgNode<'brand>,
-    ) -> Result<PairBuilder<ProgNode<'brand>>, simplicity::types::Error> {
+    fn for_while_0<'brand, J: Jet>(
+        f: &ProgNode<'brand, J>,
+    ) -> Result<PairBuilder<ProgNode<'brand, J>>, simplicity::types::Error> {
         let ctx = f.inference_context();
         let f_output = ProgNode::o()
             .h(ctx)
@@ -579,9 +1083,9 @@ fn for_while<'brand>(
      * where
      *       f :  A × (C × 2^(2^(n + 1))) → B + A
      */
-    fn adapt_f<'brand>(
-        f: &ProgNode<'brand>,
-    ) -> Result<PairBuilder<ProgNode<'brand>>, simplicity::types::Error> {
+    fn adapt_f<'brand, J: Jet>(
+        f: &ProgNode<'brand, J>,
+    ) -> Result<PairBuilder<ProgNode<'brand, J>>, simplicity::types::Error> {
         let ctx = f.inference_context();
         let f_input = ProgNode::o().h(ctx).pair(
             ProgNode::i()
@@ -650,10 +1154,10 @@ fn for_while<'brand>(
 }
 
 impl Match {
-    fn compile<'brand>(
+    fn compile<'brand, J: JetSet>(
         &self,
         scope: &mut Scope<'brand>,
-    ) -> Result<PairBuilder<ProgNode<'brand>>, RichError> {
+    ) -> Result<FlowBuilder<'brand, J>, RichError> {
         scope.push_scope();
         scope.insert(
             self.left()
@@ -677,8 +1181,27 @@ impl Match {
         scope.pop_scope();
 
         let scrutinee = self.scrutinee().compile(scope)?;
-        let input = scrutinee.pair(PairBuilder::iden(scope.ctx()));
-        let output = ProgNode::case(left.as_ref(), right.as_ref()).with_span(self)?;
-        input.comp(&output).with_span(self)
+        let tracked_match = TrackedMatch::new(
+            *self.as_ref(),
+            *self.left().expression().as_ref(),
+            *self.right().expression().as_ref(),
+        );
+        match (scrutinee, left, right) {
+            (Flow::Value(scrutinee), Flow::Value(left), Flow::Value(right)) => {
+                let input = scrutinee.pair(PairBuilder::iden(scope.ctx()));
+                let output = ProgNode::case(left.as_ref(), right.as_ref()).with_span(self)?;
+                scope.track_match(output.cmr(), tracked_match);
+                input.comp(&output).map(Flow::Value).with_span(self)
+            }
+            (scrutinee, left, right) => {
+                let (left, right) = (left.into_exit(), right.into_exit());
+                let output = ProgNode::case(left.as_ref(), right.as_ref()).with_span(self)?;
+                scope.track_match(output.cmr(), tracked_match);
+                scrutinee
+                    .bind(&output, scope.ctx())
+                    .map(Flow::Exit)
+                    .with_span(self)
+            }
+        }
     }
 }
@@ -0,0 +1,532 @@
+//! Branch and function coverage of SimplicityHL programs.
+//!
+//! Simplicity programs are pruned to the branches that they execute.
+//! Coverage records which arms of match expressions and which functions were executed,
+//! aggregated over multiple runs of the same program, such as the cases of a test suite.
+//! Match expressions and functions are mapped back to the source file via [`DebugSymbols`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Write};
+
+use simplicity::bit_machine::PruneTracker;
+use simplicity::dag::{Dag, DagLike};
+use simplicity::node::Inner;
+use simplicity::RedeemNode;
+
+use crate::debug::DebugSymbols;
+use crate::error::Span;
+use crate::jet::JetSet;
+use crate::str::FunctionName;
+
+/// Format of a coverage report.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CoverageFormat {
+    /// [LCOV tracefile](https://github.com/linux-test-project/lcov).
+    Lcov,
+    /// [Cobertura XML](https://cobertura.github.io/cobertura/).
+    Cobertura,
+}
+
+impl std::str::FromStr for CoverageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lcov" => Ok(Self::Lcov),
+            "cobertura" => Ok(Self::Cobertura),
+            _ => Err(format!("Unknown coverage format `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for CoverageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lcov => f.write_str("lcov"),
+            Self::Cobertura => f.write_str("cobertura"),
+        }
+    }
+}
+
+/// Coverage of a match expression.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+struct MatchCoverage {
+    left: Span,
+    right: Span,
+    left_hits: usize,
+    right_hits: usize,
+}
+
+/// Coverage of a function.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+struct FunctionCoverage {
+    span: Span,
+    hits: usize,
+}
+
+/// Branch and function coverage of a SimplicityHL program over multiple runs.
+///
+/// Each hit count is the number of runs that executed the match arm or function.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct Coverage {
+    runs: usize,
+    matches: HashMap<Span, MatchCoverage>,
+    functions: HashMap<FunctionName, FunctionCoverage>,
+}
+
+impl Coverage {
+    /// Create an empty coverage of the program with the given debug symbols.
+    ///
+    /// Each match expression and each function of the program starts without hits.
+    /// Functions that are never called are not part of the compiled program
+    /// and are therefore not part of the coverage.
+    pub fn new(debug_symbols: &DebugSymbols) -> Self {
+        let matches = debug_symbols
+            .matches()
+            .map(|tracked| {
+                let coverage = MatchCoverage {
+                    left: *tracked.left(),
+                    right: *tracked.right(),
+                    left_hits: 0,
+                    right_hits: 0,
+                };
+                (*tracked.span(), coverage)
+            })
+            .collect();
+        let functions = debug_symbols
+            .functions()
+            .map(|tracked| {
+                let coverage = FunctionCoverage {
+                    span: *tracked.span(),
+                    hits: 0,
+                };
+                (tracked.name().clone(), coverage)
+            })
+            .collect();
+
+        Self {
+            runs: 0,
+            matches,
+            functions,
+        }
+    }
+
+    /// Record a run of the given `program`.
+    ///
+    /// The `tracker` must have been used to run the program,
+    /// so it knows which branches of the program were executed.
+    ///
+    /// Match arms are covered if their branch was executed.
+    /// Functions are covered if they can be reached via the executed branches.
+    /// If the run failed, then this includes functions after the point of failure.
+    pub fn record<J: JetSet, T: PruneTracker<J>>(
+        &mut self,
+        program: &RedeemNode<J>,
+        debug_symbols: &DebugSymbols,
+        tracker: &T,
+    ) {
+        self.runs += 1;
+
+        let mut covered_arms = HashSet::new();
+        let mut covered_functions = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![program];
+
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node.ihr()) {
+                continue;
+            }
+            if let Some(tracked) = debug_symbols.get_function(&node.cmr()) {
+                covered_functions.insert(tracked.name().clone());
+            }
+
+            match node.inner() {
+                Inner::Case(left, right) => {
+                    let executed_left = tracker.contains_left(node.ihr());
+                    let executed_right = tracker.contains_right(node.ihr());
+                    if let Some(tracked) = debug_symbols.get_match(&node.cmr()) {
+                        if executed_left {
+                            covered_arms.insert((*tracked.span(), true));
+                        }
+                        if executed_right {
+                            covered_arms.insert((*tracked.span(), false));
+                        }
+                    }
+                    if executed_left {
+                        stack.push(left);
+                    }
+                    if executed_right {
+                        stack.push(right);
+                    }
+                }
+                _ => match node.as_dag_node() {
+                    Dag::Nullary => {}
+                    Dag::Unary(child) => stack.push(child),
+                    Dag::Binary(left, right) => {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                },
+            }
+        }
+
+        for (span, is_left) in covered_arms {
+            if let Some(coverage) = self.matches.get_mut(&span) {
+                match is_left {
+                    true => coverage.left_hits += 1,
+                    false => coverage.right_hits += 1,
+                }
+            }
+        }
+        for name in covered_functions {
+            if let Some(coverage) = self.functions.get_mut(&name) {
+                coverage.hits += 1;
+            }
+        }
+    }
+
+    /// Access the number of recorded runs.
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// Return the number of match arms that were executed at least once,
+    /// and the total number of match arms.
+    pub fn arms(&self) -> (usize, usize) {
+        let covered = self
+            .matches
+            .values()
+            .map(|coverage| {
+                usize::from(coverage.left_hits > 0) + usize::from(coverage.right_hits > 0)
+            })
+            .sum();
+        (covered, 2 * self.matches.len())
+    }
+
+    /// Return the number of functions that were executed at least once,
+    /// and the total number of functions.
+    pub fn functions(&self) -> (usize, usize) {
+        let covered = self
+            .functions
+            .values()
+            .filter(|coverage| coverage.hits > 0)
+            .count();
+        (covered, self.functions.len())
+    }
+
+    /// Return the match expressions, ordered by their position in the source file.
+    fn sorted_matches(&self) -> Vec<(&Span, &MatchCoverage)> {
+        let mut matches: Vec<_> = self.matches.iter().collect();
+        matches.sort_by_key(|(span, _)| position_key(span));
+        matches
+    }
+
+    /// Return the functions, ordered by their position in the source file.
+    fn sorted_functions(&self) -> Vec<(&FunctionName, &FunctionCoverage)> {
+        let mut functions: Vec<_> = self.functions.iter().collect();
+        functions.sort_by_key(|(_, coverage)| position_key(&coverage.span));
+        functions
+    }
+
+    /// Return the hit count of each line that starts a match arm or a function.
+    ///
+    /// If a line starts multiple arms or functions, then the highest hit count is used.
+    fn line_hits(&self) -> BTreeMap<usize, usize> {
+        let mut lines = BTreeMap::new();
+        let mut insert = |span: &Span, hits: usize| {
+            let entry = lines.entry(span.start.line.get()).or_insert(0);
+            *entry = hits.max(*entry);
+        };
+        for coverage in self.matches.values() {
+            insert(&coverage.left, coverage.left_hits);
+            insert(&coverage.right, coverage.right_hits);
+        }
+        for coverage in self.functions.values() {
+            insert(&coverage.span, coverage.hits);
+        }
+        lines
+    }
+
+    /// Write the coverage report of the given source file in the given format.
+    pub fn report(&self, format: CoverageFormat, source_file: &str) -> String {
+        match format {
+            CoverageFormat::Lcov => self.to_lcov(source_file),
+            CoverageFormat::Cobertura => self.to_cobertura(source_file),
+        }
+    }
+
+    /// Write the coverage as an LCOV tracefile for the given source file.
+    pub fn to_lcov(&self, source_file: &str) -> String {
+        let mut s = String::new();
+        // Writing to a string cannot fail
+        let _ = self.write_lcov(&mut s, source_file);
+        s
+    }
+
+    fn write_lcov<W: Write>(&self, w: &mut W, source_file: &str) -> fmt::Result {
+        writeln!(w, "TN:")?;
+        writeln!(w, "SF:{source_file}")?;
+
+        let functions = self.sorted_functions();
+        for (name, coverage) in &functions {
+            writeln!(w, "FN:{},{name}", coverage.span.start.line)?;
+        }
+        for (name, coverage) in &functions {
+            writeln!(w, "FNDA:{},{name}", coverage.hits)?;
+        }
+        let (covered, total) = self.functions();
+        writeln!(w, "FNF:{total}")?;
+        writeln!(w, "FNH:{covered}")?;
+
+        for (block, (span, coverage)) in self.sorted_matches().into_iter().enumerate() {
+            for (branch, hits) in [coverage.left_hits, coverage.right_hits]
+                .into_iter()
+                .enumerate()
+            {
+                let taken = match self.runs {
+                    0 => "-".to_string(),
+                    _ => hits.to_string(),
+                };
+                writeln!(w, "BRDA:{},{block},{branch},{taken}", span.start.line)?;
+            }
+        }
+        let (covered, total) = self.arms();
+        writeln!(w, "BRF:{total}")?;
+        writeln!(w, "BRH:{covered}")?;
+
+        let lines = self.line_hits();
+        for (line, hits) in &lines {
+            writeln!(w, "DA:{line},{hits}")?;
+        }
+        writeln!(w, "LF:{}", lines.len())?;
+        writeln!(w, "LH:{}", lines.values().filter(|hits| **hits > 0).count())?;
+        writeln!(w, "end_of_record")
+    }
+
+    /// Write the coverage as a Cobertura XML report for the given source file.
+    pub fn to_cobertura(&self, source_file: &str) -> String {
+        let mut s = String::new();
+        // Writing to a string cannot fail
+        let _ = self.write_cobertura(&mut s, source_file);
+        s
+    }
+
+    fn write_cobertura<W: Write>(&self, w: &mut W, source_file: &str) -> fmt::Result {
+        let lines = self.line_hits();
+        let lines_covered = lines.values().filter(|hits| **hits > 0).count();
+        let line_rate = rate(lines_covered, lines.len());
+        let (branches_covered, branches_valid) = self.arms();
+        let branch_rate = rate(branches_covered, branches_valid);
+        let file = xml_escape(source_file);
+
+        // Branches of each line that starts a match expression
+        let mut line_branches: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+        for (span, coverage) in self.sorted_matches() {
+            let entry = line_branches.entry(span.start.line.get()).or_insert((0, 0));
+            entry.0 += usize::from(coverage.left_hits > 0) + usize::from(coverage.right_hits > 0);
+            entry.1 += 2;
+        }
+
+        writeln!(w, r#"<?xml version="1.0" ?>"#)?;
+        writeln!(
+            w,
+            r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+        )?;
+        writeln!(
+            w,
+            r#"<coverage line-rate="{line_rate}" branch-rate="{branch_rate}" lines-covered="{lines_covered}" lines-valid="{}" branches-covered="{branches_covered}" branches-valid="{branches_valid}" complexity="0" version="0" timestamp="0">"#,
+            lines.len()
+        )?;
+        writeln!(w, "  <sources>")?;
+        writeln!(w, "    <source>.</source>")?;
+        writeln!(w, "  </sources>")?;
+        writeln!(w, "  <packages>")?;
+        writeln!(
+            w,
+            r#"    <package name="" line-rate="{line_rate}" branch-rate="{branch_rate}" complexity="0">"#
+        )?;
+        writeln!(w, "      <classes>")?;
+        writeln!(
+            w,
+            r#"        <class name="{file}" filename="{file}" line-rate="{line_rate}" branch-rate="{branch_rate}" complexity="0">"#
+        )?;
+        writeln!(w, "          <methods>")?;
+        for (name, coverage) in self.sorted_functions() {
+            let function_rate = rate(usize::from(coverage.hits > 0), 1);
+            writeln!(
+                w,
+                r#"            <method name="{}" signature="" line-rate="{function_rate}" branch-rate="{function_rate}" complexity="0">"#,
+                xml_escape(name.as_inner())
+            )?;
+            writeln!(w, "              <lines>")?;
+            writeln!(
+                w,
+                r#"                <line number="{}" hits="{}"/>"#,
+                coverage.span.start.line, coverage.hits
+            )?;
+            writeln!(w, "              </lines>")?;
+            writeln!(w, "            </method>")?;
+        }
+        writeln!(w, "          </methods>")?;
+        writeln!(w, "          <lines>")?;
+        for (line, hits) in &lines {
+            match line_branches.get(line) {
+                Some((covered, total)) => writeln!(
+                    w,
+                    r#"            <line number="{line}" hits="{hits}" branch="true" condition-coverage="{}% ({covered}/{total})"/>"#,
+                    covered * 100 / total
+                )?,
+                None => writeln!(
+                    w,
+                    r#"            <line number="{line}" hits="{hits}" branch="false"/>"#
+                )?,
+            }
+        }
+        writeln!(w, "          </lines>")?;
+        writeln!(w, "        </class>")?;
+        writeln!(w, "      </classes>")?;
+        writeln!(w, "    </package>")?;
+        writeln!(w, "  </packages>")?;
+        writeln!(w, "</coverage>")
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (covered_arms, total_arms) = self.arms();
+        let (covered_functions, total_functions) = self.functions();
+        write!(
+            f,
+            "{covered_arms}/{total_arms} match arms and {covered_functions}/{total_functions} functions covered by {} runs",
+            self.runs
+        )
+    }
+}
+
+/// Sort key for spans by their start position.
+fn position_key(span: &Span) -> (usize, usize) {
+    (span.start.line.get(), span.start.col.get())
+}
+
+/// Ratio of covered items with four decimal places, formatted as in Cobertura reports.
+fn rate(covered: usize, total: usize) -> String {
+    match total {
+        0 => "1".to_string(),
+        _ => {
+            let permyriad = covered * 10_000 / total;
+            format!("{}.{:04}", permyriad / 10_000, permyriad % 10_000)
+        }
+    }
+}
+
+/// Escape the special characters of XML attribute values.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::str::WitnessName;
+    use crate::tracker::DefaultTracker;
+    use crate::{dummy_env, Arguments, CompiledProgram, Value, WitnessValues};
+
+    const PROGRAM: &str = r#"fn double(x: u32) -> u32 {
+    let (_, y): (bool, u32) = jet::add_32(x, x);
+    y
+}
+
+fn main() {
+    let amount: u32 = match witness::IS_BIG {
+        false => 1,
+        true => double(1000),
+    };
+    assert!(jet::lt_32(amount, 5000));
+}"#;
+
+    fn run(program: &CompiledProgram, coverage: &mut Coverage, is_big: bool) {
+        let witness_values = WitnessValues::from(HashMap::from([(
+            WitnessName::from_str_unchecked("IS_BIG"),
+            Value::from(is_big),
+        )]));
+        let satisfied = program.satisfy(witness_values).unwrap();
+        let mut tracker = DefaultTracker::new(satisfied.debug_symbols());
+        satisfied
+            .redeem()
+            .prune_with_tracker(&dummy_env::dummy(), &mut tracker)
+            .unwrap();
+        coverage.record(
+            satisfied.redeem().as_ref(),
+            satisfied.debug_symbols(),
+            &tracker,
+        );
+    }
+
+    #[test]
+    fn aggregate_runs() {
+        let program = CompiledProgram::new(PROGRAM, Arguments::default(), false).unwrap();
+        let mut coverage = Coverage::new(program.debug_symbols());
+        assert_eq!((0, 2), coverage.arms());
+        assert_eq!((0, 1), coverage.functions());
+
+        run(&program, &mut coverage, false);
+        assert_eq!((1, 2), coverage.arms());
+        assert_eq!((0, 1), coverage.functions());
+
+        run(&program, &mut coverage, true);
+        assert_eq!(2, coverage.runs());
+        assert_eq!((2, 2), coverage.arms());
+        assert_eq!((1, 1), coverage.functions());
+    }
+
+    #[test]
+    fn lcov_report() {
+        let program = CompiledProgram::new(PROGRAM, Arguments::default(), false).unwrap();
+        let mut coverage = Coverage::new(program.debug_symbols());
+        run(&program, &mut coverage, false);
+
+        let expected = "TN:
+SF:test.simf
+FN:1,double
+FNDA:0,double
+FNF:1
+FNH:0
+BRDA:7,0,0,1
+BRDA:7,0,1,0
+BRF:2
+BRH:1
+DA:1,0
+DA:8,1
+DA:9,0
+LF:3
+LH:1
+end_of_record
+";
+        assert_eq!(expected, coverage.to_lcov("test.simf"));
+    }
+
+    #[test]
+    fn cobertura_report() {
+        let program = CompiledProgram::new(PROGRAM, Arguments::default(), false).unwrap();
+        let mut coverage = Coverage::new(program.debug_symbols());
+        run(&program, &mut coverage, true);
+
+        let report = coverage.to_cobertura("test.simf");
+        assert!(report.contains(r#"<method name="double" signature="" line-rate="1.0000""#));
+        assert!(report.contains(r#"<line number="9" hits="1" branch="false"/>"#));
+        assert!(report.contains(r#"branches-covered="1" branches-valid="2""#));
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 
 use either::Either;
@@ -6,14 +7,27 @@ use hashes::{sha256, Hash, HashEngine};
 use simplicity::{hashes, Cmr};
 
 use crate::error::Span;
+use crate::str::FunctionName;
 use crate::types::ResolvedType;
-use crate::value::{StructuralValue, Value};
+use crate::value::{StructuralValue, Value, ValueInner};
 
-/// Tracker of SimplicityHL call expressions inside Simplicity target code.
+/// Tracker of SimplicityHL call expressions, match expressions and functions
+/// inside Simplicity target code.
 ///
-/// Tracking happens via CMRs that are inserted into the Simplicity target code.
+/// Call expressions are tracked via CMRs that are inserted into the Simplicity target code.
+/// Match expressions are tracked via the CMRs of the `case` nodes that they compile to.
+/// Functions are tracked via the CMRs of their compiled bodies.
+///
+/// With the `serde` feature, debug symbols can be serialized as a versioned sidecar file
+/// (`.simdbg`), so the Simplicity target code can be debugged without its source file.
+/// The sidecar records the CMR of the compiled program, so it cannot be used with a different program.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct DebugSymbols(HashMap<Cmr, TrackedCall>);
+pub struct DebugSymbols {
+    program: Option<Cmr>,
+    calls: HashMap<Cmr, TrackedCall>,
+    matches: HashMap<Cmr, TrackedMatch>,
+    functions: HashMap<Cmr, TrackedFunction>,
+}
 
 /// Intermediate representation of tracked SimplicityHL call expressions
 /// that is mutable and that lacks information about the source file.
@@ -29,18 +43,40 @@ pub(crate) struct CallTracker {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TrackedCall {
     text: Arc<str>,
+    span: Span,
     name: TrackedCallName,
 }
 
+/// Match expression that is compiled to a `case` node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TrackedMatch {
+    span: Span,
+    left: Span,
+    right: Span,
+}
+
+/// Custom function whose body is compiled to Simplicity target code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TrackedFunction {
+    name: FunctionName,
+    span: Span,
+}
+
 /// Name of a call expression with a debug symbol.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TrackedCallName {
-    Assert,
-    Panic,
+    /// `assert!` with its optional failure message.
+    Assert(Option<Arc<str>>),
+    /// `panic!` with its optional failure message.
+    Panic(Option<Arc<str>>),
     Jet,
     UnwrapLeft(ResolvedType),
     UnwrapRight(ResolvedType),
     Unwrap,
+    /// Runtime index into an array.
+    ///
+    /// The type is the type of the call arguments: a tuple of the array and the index.
+    ArrayIndex(ResolvedType),
     Debug(ResolvedType),
 }
 
@@ -48,18 +84,23 @@ pub enum TrackedCallName {
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct FallibleCall {
     text: Arc<str>,
+    span: Span,
     name: FallibleCallName,
 }
 
 /// Name of a fallible call expression with runtime input value.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum FallibleCallName {
-    Assert,
-    Panic,
+    /// `assert!` with its optional failure message.
+    Assert(Option<Arc<str>>),
+    /// `panic!` with its optional failure message.
+    Panic(Option<Arc<str>>),
     Jet,
     UnwrapLeft(Value),
     UnwrapRight(Value),
     Unwrap,
+    /// Runtime index into an array, with the index that is out of bounds.
+    ArrayIndex(Value),
 }
 
 /// Debug expression with runtime input value.
@@ -79,23 +120,99 @@ impl DebugSymbols {
             .and_then(|s| s.strip_suffix(")"))
             .unwrap_or(&text);
 
-        self.0.insert(
+        self.insert_call(
             cmr,
             TrackedCall {
                 text: Arc::from(text),
+                span,
                 name,
             },
         );
     }
 
+    /// Insert a tracked call expression whose text is already known.
+    pub(crate) fn insert_call(&mut self, cmr: Cmr, tracked_call: TrackedCall) {
+        self.calls.insert(cmr, tracked_call);
+    }
+
+    /// Insert a tracked match expression whose `case` node has the given CMR.
+    pub(crate) fn insert_match(&mut self, cmr: Cmr, tracked_match: TrackedMatch) {
+        self.matches.insert(cmr, tracked_match);
+    }
+
+    /// Insert a tracked function whose compiled body has the given CMR.
+    pub(crate) fn insert_function(&mut self, cmr: Cmr, tracked_function: TrackedFunction) {
+        self.functions.insert(cmr, tracked_function);
+    }
+
+    /// Insert all tracked expressions of the `other` debug symbols.
+    pub(crate) fn extend(&mut self, other: Self) {
+        self.calls.extend(other.calls);
+        self.matches.extend(other.matches);
+        self.functions.extend(other.functions);
+    }
+
+    /// Set the CMR of the compiled program that the debug symbols belong to.
+    pub(crate) fn set_program_cmr(&mut self, cmr: Cmr) {
+        self.program = Some(cmr);
+    }
+
+    /// Get the CMR of the compiled program that the debug symbols belong to.
+    ///
+    /// Empty debug symbols belong to no program in particular.
+    pub fn program_cmr(&self) -> Option<Cmr> {
+        self.program
+    }
+
     /// Check if the given CMR tracks any call expressions.
     pub fn contains_key(&self, cmr: &Cmr) -> bool {
-        self.0.contains_key(cmr)
+        self.calls.contains_key(cmr)
     }
 
     /// Get the call expression that is tracked by the given CMR.
     pub fn get(&self, cmr: &Cmr) -> Option<&TrackedCall> {
-        self.0.get(cmr)
+        self.calls.get(cmr)
+    }
+
+    /// Get the match expression that compiles to the `case` node with the given CMR.
+    ///
+    /// Identical match expressions compile to the same CMR.
+    /// In this case, one of them is returned.
+    pub fn get_match(&self, cmr: &Cmr) -> Option<&TrackedMatch> {
+        self.matches.get(cmr)
+    }
+
+    /// Get the function whose body compiles to the given CMR.
+    ///
+    /// Functions with identical bodies compile to the same CMR.
+    /// In this case, one of them is returned.
+    pub fn get_function(&self, cmr: &Cmr) -> Option<&TrackedFunction> {
+        self.functions.get(cmr)
+    }
+
+    /// Iterate over the tracked call expressions and their CMRs.
+    pub fn calls(&self) -> impl Iterator<Item = (&Cmr, &TrackedCall)> {
+        self.calls.iter()
+    }
+
+    /// Iterate over the tracked match expressions.
+    pub fn matches(&self) -> impl Iterator<Item = &TrackedMatch> {
+        self.matches.values()
+    }
+
+    /// Iterate over the tracked match expressions and the CMRs of their `case` nodes.
+    pub(crate) fn matches_with_cmrs(&self) -> impl Iterator<Item = (&Cmr, &TrackedMatch)> {
+        self.matches.iter()
+    }
+
+    /// Iterate over the tracked functions.
+    pub fn functions(&self) -> impl Iterator<Item = &TrackedFunction> {
+        self.functions.values()
+    }
+
+    /// Iterate over the tracked functions and the CMRs of their bodies.
+    pub(crate) fn functions_with_cmrs(&self) -> impl Iterator<Item = (&Cmr, &TrackedFunction)> {
+        self.functions.iter()
     }
 }
 
@@ -152,11 +269,21 @@ impl CallTracker {
 }
 
 impl TrackedCall {
+    /// Create a tracked call expression from its text, span and name.
+    pub(crate) fn new(text: Arc<str>, span: Span, name: TrackedCallName) -> Self {
+        Self { text, span, name }
+    }
+
     /// Access the text of the SimplicityHL call expression.
     pub fn text(&self) -> &str {
         &self.text
     }
 
+    /// Access the span of the SimplicityHL call expression.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
     /// Access the name of the call.
     pub fn name(&self) -> &TrackedCallName {
         &self.name
@@ -170,8 +297,8 @@ impl TrackedCall {
     /// according to the debug symbol.
     pub fn map_value(&self, value: &StructuralValue) -> Option<Either<FallibleCall, DebugValue>> {
         let name = match self.name() {
-            TrackedCallName::Assert => FallibleCallName::Assert,
-            TrackedCallName::Panic => FallibleCallName::Panic,
+            TrackedCallName::Assert(message) => FallibleCallName::Assert(message.clone()),
+            TrackedCallName::Panic(message) => FallibleCallName::Panic(message.clone()),
             TrackedCallName::Jet => FallibleCallName::Jet,
             TrackedCallName::UnwrapLeft(ty) => {
                 Value::reconstruct(value, ty).map(FallibleCallName::UnwrapLeft)?
@@ -180,6 +307,13 @@ impl TrackedCall {
                 Value::reconstruct(value, ty).map(FallibleCallName::UnwrapRight)?
             }
             TrackedCallName::Unwrap => FallibleCallName::Unwrap,
+            TrackedCallName::ArrayIndex(ty) => {
+                let args = Value::reconstruct(value, ty)?;
+                let ValueInner::Tuple(args) = args.inner() else {
+                    return None;
+                };
+                args.get(1).cloned().map(FallibleCallName::ArrayIndex)?
+            }
             TrackedCallName::Debug(ty) => {
                 return Value::reconstruct(value, ty)
                     .map(|value| DebugValue {
@@ -191,21 +325,97 @@ impl TrackedCall {
         };
         Some(Either::Left(FallibleCall {
             text: Arc::clone(&self.text),
+            span: self.span,
             name,
         }))
     }
 }
 
+impl TrackedMatch {
+    /// Create a tracked match expression from the spans of the expression and of its two arms.
+    pub(crate) fn new(span: Span, left: Span, right: Span) -> Self {
+        Self { span, left, right }
+    }
+
+    /// Access the span of the SimplicityHL match expression.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Access the span of the left arm.
+    pub fn left(&self) -> &Span {
+        &self.left
+    }
+
+    /// Access the span of the right arm.
+    pub fn right(&self) -> &Span {
+        &self.right
+    }
+}
+
+impl TrackedFunction {
+    /// Create a tracked function from its name and the span of its definition.
+    pub(crate) fn new(name: FunctionName, span: Span) -> Self {
+        Self { name, span }
+    }
+
+    /// Access the name of the function.
+    pub fn name(&self) -> &FunctionName {
+        &self.name
+    }
+
+    /// Access the span of the function definition.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
 impl FallibleCall {
     /// Access the SimplicityHL text of the call expression.
     pub fn text(&self) -> &str {
         &self.text
     }
 
+    /// Access the span of the SimplicityHL call expression.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
     /// Access the name of the call.
     pub fn name(&self) -> &FallibleCallName {
         &self.name
     }
+
+    /// Access the failure message of the call, if there is one.
+    pub fn message(&self) -> Option<&str> {
+        match self.name() {
+            FallibleCallName::Assert(message) | FallibleCallName::Panic(message) => {
+                message.as_deref()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FallibleCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            FallibleCallName::UnwrapLeft(value) | FallibleCallName::UnwrapRight(value) => {
+                write!(f, "`{}` failed on input `{value}`", self.text())
+            }
+            FallibleCallName::ArrayIndex(index) => {
+                write!(
+                    f,
+                    "`{}` failed on out-of-bounds index `{index}`",
+                    self.text()
+                )
+            }
+            _ => match self.message() {
+                Some(message) => write!(f, "`{}` failed: {message}", self.text()),
+                None => write!(f, "`{}` failed", self.text()),
+            },
+        }
+    }
 }
 
 impl DebugValue {
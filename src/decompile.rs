@@ -0,0 +1,258 @@
+//! Readable rendering of Simplicity target code.
+//!
+//! Decoded Simplicity programs are rendered as a tree of combinators,
+//! where each node is annotated with its inferred type.
+//! Subexpressions that are used more than once are rendered once and named by their CMR.
+//! If [`DebugSymbols`] are available, then nodes are annotated with the SimplicityHL source
+//! of the functions, match expressions and calls that they compile from.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write};
+
+use simplicity::dag::{Dag, DagLike};
+use simplicity::jet::Jet;
+use simplicity::node::{Inner, Marker, Node};
+use simplicity::types::arrow::FinalArrow;
+use simplicity::{CommitNode, RedeemNode};
+
+use crate::debug::DebugSymbols;
+
+/// Number of spaces that each level of the tree is indented by.
+const INDENT: usize = 2;
+
+/// Render a program without witness data.
+pub fn render_commit<J: Jet>(program: &CommitNode<J>, debug_symbols: &DebugSymbols) -> String {
+    Renderer::new(program, CommitNode::arrow, |_| None, debug_symbols).render()
+}
+
+/// Render a program with witness data.
+///
+/// Witness nodes are rendered together with their values.
+pub fn render_redeem<J: Jet>(program: &RedeemNode<J>, debug_symbols: &DebugSymbols) -> String {
+    Renderer::new(
+        program,
+        RedeemNode::arrow,
+        |value| Some(value.to_string()),
+        debug_symbols,
+    )
+    .render()
+}
+
+/// Identifies a node of a program by its address.
+fn node_key<N: Marker>(node: &Node<N>) -> usize {
+    std::ptr::from_ref(node) as usize
+}
+
+struct Renderer<'a, N: Marker, A, W> {
+    root: &'a Node<N>,
+    arrow: A,
+    witness: W,
+    debug_symbols: &'a DebugSymbols,
+    /// Names of the shared nodes.
+    names: HashMap<usize, String>,
+    /// Shared nodes in post order, so each definition comes before its uses.
+    shared: Vec<&'a Node<N>>,
+}
+
+impl<'a, N, A, W> Renderer<'a, N, A, W>
+where
+    N: Marker,
+    N::Jet: fmt::Display,
+    A: Fn(&Node<N>) -> &FinalArrow,
+    W: Fn(&N::Witness) -> Option<String>,
+{
+    fn new(root: &'a Node<N>, arrow: A, witness: W, debug_symbols: &'a DebugSymbols) -> Self {
+        // Count the parents of each node
+        let mut parents = HashMap::<usize, usize>::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node_key(node)) {
+                continue;
+            }
+            for child in children(node) {
+                *parents.entry(node_key(child)).or_default() += 1;
+                stack.push(child);
+            }
+        }
+
+        let mut renderer = Self {
+            root,
+            arrow,
+            witness,
+            debug_symbols,
+            names: HashMap::new(),
+            shared: Vec::new(),
+        };
+        let mut visited = HashSet::new();
+        renderer.collect_shared(root, &parents, &mut visited);
+        renderer
+    }
+
+    fn collect_shared(
+        &mut self,
+        node: &'a Node<N>,
+        parents: &HashMap<usize, usize>,
+        visited: &mut HashSet<usize>,
+    ) {
+        if !visited.insert(node_key(node)) {
+            return;
+        }
+        let children = children(node);
+        for child in &children {
+            self.collect_shared(child, parents, visited);
+        }
+        // Leaves are short enough to be repeated
+        let is_shared = parents.get(&node_key(node)).copied().unwrap_or(0) > 1;
+        if is_shared && !children.is_empty() {
+            let cmr = node.cmr().to_string();
+            let mut name = format!("#{}", &cmr[..8]);
+            let mut suffix = 2;
+            while self.names.values().any(|existing| existing == &name) {
+                name = format!("#{}_{suffix}", &cmr[..8]);
+                suffix += 1;
+            }
+            self.names.insert(node_key(node), name);
+            self.shared.push(node);
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut output = String::new();
+        let root_arrow = (self.arrow)(self.root);
+        writeln!(output, "// CMR: {}", self.root.cmr()).unwrap();
+        writeln!(
+            output,
+            "// type: {} → {}",
+            root_arrow.source, root_arrow.target
+        )
+        .unwrap();
+
+        for node in &self.shared {
+            writeln!(output, "{} :=", self.names[&node_key(node)]).unwrap();
+            self.render_node(node, INDENT, true, &mut output);
+        }
+        writeln!(output, "main :=").unwrap();
+        self.render_node(self.root, INDENT, true, &mut output);
+        output
+    }
+
+    fn render_node(&self, node: &Node<N>, depth: usize, is_definition: bool, output: &mut String) {
+        let arrow = (self.arrow)(node);
+        write!(output, "{:depth$}", "").unwrap();
+
+        if let (Some(name), false) = (self.names.get(&node_key(node)), is_definition) {
+            writeln!(output, "{name} : {} → {}", arrow.source, arrow.target).unwrap();
+            return;
+        }
+
+        let (label, hidden) = match node.inner() {
+            Inner::Iden => ("iden".to_string(), None),
+            Inner::Unit => ("unit".to_string(), None),
+            Inner::InjL(_) => ("injl".to_string(), None),
+            Inner::InjR(_) => ("injr".to_string(), None),
+            Inner::Take(_) => ("take".to_string(), None),
+            Inner::Drop(_) => ("drop".to_string(), None),
+            Inner::Comp(..) => ("comp".to_string(), None),
+            Inner::Case(..) => ("case".to_string(), None),
+            Inner::AssertL(_, cmr) => ("assertl".to_string(), Some(*cmr)),
+            Inner::AssertR(cmr, _) => ("assertr".to_string(), Some(*cmr)),
+            Inner::Pair(..) => ("pair".to_string(), None),
+            Inner::Disconnect(..) => ("disconnect".to_string(), None),
+            Inner::Witness(value) => match (self.witness)(value) {
+                Some(value) => (format!("witness {value}"), None),
+                None => ("witness".to_string(), None),
+            },
+            Inner::Fail(_) => ("fail".to_string(), None),
+            Inner::Jet(jet) => (format!("jet::{jet}"), None),
+            Inner::Word(word) => (format!("const {}", word.as_value()), None),
+        };
+        write!(output, "{label} : {} → {}", arrow.source, arrow.target).unwrap();
+        if let Some(annotation) = self.annotation(node) {
+            write!(output, "  // {annotation}").unwrap();
+        }
+        output.push('\n');
+
+        for child in children(node) {
+            self.render_node(child, depth + INDENT, false, output);
+        }
+        if let Some(cmr) = hidden {
+            writeln!(output, "{:width$}hidden {cmr}", "", width = depth + INDENT).unwrap();
+        }
+    }
+
+    fn annotation(&self, node: &Node<N>) -> Option<String> {
+        let cmr = node.cmr();
+        if let Some(function) = self.debug_symbols.get_function(&cmr) {
+            return Some(format!(
+                "fn {} (line {})",
+                function.name(),
+                function.span().start.line
+            ));
+        }
+        match node.inner() {
+            Inner::Case(..) => self
+                .debug_symbols
+                .get_match(&cmr)
+                .map(|tracked| format!("match (line {})", tracked.span().start.line)),
+            Inner::AssertL(_, cmr) => self
+                .debug_symbols
+                .get(cmr)
+                .map(|call| format!("{} (line {})", call.text(), call.span().start.line)),
+            _ => None,
+        }
+    }
+}
+
+fn children<N: Marker>(node: &Node<N>) -> Vec<&Node<N>> {
+    match node.as_dag_node() {
+        Dag::Nullary => vec![],
+        Dag::Unary(child) => vec![child],
+        Dag::Binary(left, right) => vec![left, right],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simplicity::BitIter;
+    use simplicity_unchained::jets::unchained::ElementsExtension;
+
+    use super::*;
+    use crate::{Arguments, TemplateProgram};
+
+    #[test]
+    fn render_decoded_program() {
+        let prog_text = r#"fn double(x: u32) -> u32 {
+    let (_, y): (bool, u32) = jet::add_32(x, x);
+    y
+}
+
+fn main() {
+    let a: u32 = double(2);
+    let b: u32 = double(3);
+    assert!(jet::eq_32(jet::add_32_unchecked(a, b), 10));
+}
+"#;
+        let program = TemplateProgram::new(prog_text)
+            .unwrap()
+            .instantiate(Arguments::default(), true)
+            .unwrap();
+        let bytes = program.commit().to_vec_without_witness();
+        let decoded = CommitNode::<ElementsExtension>::decode(BitIter::from(bytes.as_slice()))
+            .expect("compiled program decodes");
+
+        let rendered = render_commit(&decoded, program.debug_symbols());
+        assert!(rendered.starts_with(&format!("// CMR: {}\n", decoded.cmr())));
+        assert!(rendered.contains("// type: 1 → 1\n"));
+        assert!(rendered.contains("\nmain :=\n"));
+        assert!(rendered.contains("jet::add_32 : "));
+        assert!(rendered.contains("  // fn double (line 1)"));
+        assert!(rendered.contains("  // jet::add_32(x, x) (line 2)"));
+        // Both calls of `double` share the same body
+        assert!(rendered.lines().any(|line| line.starts_with('#')));
+
+        let without_symbols = render_commit(&decoded, &DebugSymbols::default());
+        assert!(!without_symbols.contains("fn double"));
+        assert!(!without_symbols.contains("(line "));
+    }
+}
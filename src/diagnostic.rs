@@ -0,0 +1,493 @@
+//! Structured, machine-readable compiler diagnostics.
+//!
+//! [`RichError`] renders a snippet for humans.
+//! [`Diagnostic`] carries the same information in a form that tools can consume:
+//! a stable code, a severity, the primary span, secondary labels, notes and suggested fixes.
+
+use std::fmt;
+
+use crate::ast;
+use crate::error::{Error, RichError, Span, WithFile};
+use crate::lint::{self, LintConfig};
+use crate::stdlib;
+use crate::{Arguments, CompiledProgram, TemplateProgram, WitnessValues};
+
+/// Severity of a diagnostic.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    /// Additional information.
+    Note,
+    /// The program compiles, but something looks wrong.
+    Warning,
+    /// The program does not compile.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Note => f.write_str("note"),
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// Secondary span of a diagnostic with an explanation.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Label {
+    /// Area of the file that the label points to.
+    pub span: Span,
+    /// Explanation of the label.
+    pub message: String,
+}
+
+/// Suggested change of the source file that resolves a diagnostic.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fix {
+    /// Description of the change.
+    pub message: String,
+    /// Area of the file that is replaced.
+    pub span: Span,
+    /// Text that replaces the area.
+    pub replacement: String,
+}
+
+/// Machine-readable compiler diagnostic.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Diagnostic {
+    /// Stable code of the diagnostic, such as `E0002`.
+    pub code: Option<&'static str>,
+    /// Severity of the diagnostic.
+    pub severity: Severity,
+    /// Main message.
+    pub message: String,
+    /// Primary area of the file that the diagnostic refers to.
+    ///
+    /// Some diagnostics concern the entire program and have no span.
+    pub span: Option<Span>,
+    /// Secondary areas of the file.
+    pub labels: Vec<Label>,
+    /// Additional explanations.
+    pub notes: Vec<String>,
+    /// Suggested changes of the file.
+    pub fixes: Vec<Fix>,
+}
+
+impl Diagnostic {
+    /// Create a new diagnostic of the given severity.
+    pub fn new<S: Into<String>>(severity: Severity, message: S) -> Self {
+        Self {
+            code: None,
+            severity,
+            message: message.into(),
+            span: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            fixes: Vec::new(),
+        }
+    }
+
+    /// Create a new error diagnostic.
+    pub fn error<S: Into<String>>(message: S) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// Create a new warning diagnostic.
+    pub fn warning<S: Into<String>>(message: S) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    /// Set the code of the diagnostic.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Set the primary span of the diagnostic.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Add a secondary label to the diagnostic.
+    pub fn with_label<S: Into<String>>(mut self, span: Span, message: S) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Add a note to the diagnostic.
+    pub fn with_note<S: Into<String>>(mut self, note: S) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Add a suggested fix to the diagnostic.
+    pub fn with_fix<S: Into<String>, R: Into<String>>(
+        mut self,
+        message: S,
+        span: Span,
+        replacement: R,
+    ) -> Self {
+        self.fixes.push(Fix {
+            message: message.into(),
+            span,
+            replacement: replacement.into(),
+        });
+        self
+    }
+
+    /// Check if the diagnostic prevents compilation.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.severity)?;
+        if let Some(code) = self.code {
+            write!(f, "[{code}]")?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(span) = self.span {
+            write!(f, " at {}:{}", span.start.line, span.start.col)?;
+        }
+        for label in &self.labels {
+            write!(
+                f,
+                "\n  --> {}:{}: {}",
+                label.span.start.line, label.span.start.col, label.message
+            )?;
+        }
+        for note in &self.notes {
+            write!(f, "\n  = note: {note}")?;
+        }
+        for fix in &self.fixes {
+            write!(f, "\n  = help: {}: `{}`", fix.message, fix.replacement)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&RichError> for Diagnostic {
+    fn from(error: &RichError) -> Self {
+        let span = *error.span();
+        let mut diagnostic = Diagnostic::error(error.error().to_string())
+            .with_code(error.error().code())
+            .with_span(span);
+
+        match error.error() {
+            Error::ListBoundPow2(bound) => {
+                let suggestion = bound.next_power_of_two().max(2);
+                diagnostic = diagnostic
+                    .with_note("List bounds must be powers of two because lists are compiled to a binary tree of arrays.")
+                    .with_fix(
+                        format!("use the list bound {suggestion}"),
+                        span,
+                        suggestion.to_string(),
+                    );
+            }
+            Error::BitStringPow2(len) => {
+                diagnostic = diagnostic
+                    .with_note("Bit strings must have the width of an unsigned integer type.");
+                let literal = error.file().and_then(|file| span.to_slice(file));
+                if let Some(digits) = literal.and_then(|s| s.strip_prefix("0b")) {
+                    let width = len.next_power_of_two();
+                    if width <= 256 && digits.chars().all(|c| c == '0' || c == '1') {
+                        let padded = format!("0b{digits:0>width$}");
+                        diagnostic =
+                            diagnostic.with_fix("pad the bit string with zeros", span, padded);
+                    }
+                }
+            }
+            Error::ForWhileWidthPow2(..) => {
+                diagnostic = diagnostic.with_note(
+                    "The counter of a for-while loop is an unsigned integer of width 1, 2, 4, 8 or 16.",
+                );
+            }
+            Error::IncompatibleMatchArms(..) => {
+                diagnostic = diagnostic.with_note(
+                    "Both arms of a match expression must match the same sum type: `Left`/`Right`, `None`/`Some` or `false`/`true`.",
+                );
+            }
+            Error::WitnessReused(..) => {
+                diagnostic = diagnostic
+                    .with_note(
+                        "Each witness may be used at most once on each path through the program.",
+                    )
+                    .with_note("Assign the witness to a variable and use the variable instead.");
+            }
+            Error::WitnessOutsideMain => {
+                diagnostic = diagnostic.with_note(
+                    "Pass the witness value as an argument to the function that needs it.",
+                );
+            }
+            Error::DisconnectOutsideMain => {
+                diagnostic = diagnostic
+                    .with_note("Call `disconnect` in the main function and pass its output on.");
+            }
+            Error::WitnessFunctionCalledTwice(..) | Error::WitnessInLoop(..) => {
+                diagnostic = diagnostic
+                    .with_note(
+                        "Each witness may be used at most once on each path through the program.",
+                    )
+                    .with_note(
+                        "Read the witness in the caller and pass it to the function as an argument.",
+                    );
+            }
+            Error::MainNoInputs | Error::MainNoOutput => {
+                diagnostic = diagnostic.with_note(
+                    "The signature of the main function is `fn main()`. Inputs come from witness values and parameters.",
+                );
+            }
+            _ => {}
+        }
+
+        diagnostic
+    }
+}
+
+impl From<&crate::Error> for Diagnostic {
+    fn from(error: &crate::Error) -> Self {
+        match error {
+            crate::Error::Program(error) => Diagnostic::from(error),
+            crate::Error::Arguments(inner)
+            | crate::Error::Witness(inner)
+            | crate::Error::Delegates(inner) => {
+                Diagnostic::error(error.to_string()).with_code(inner.code())
+            }
+            error => Diagnostic::error(error.to_string()),
+        }
+    }
+}
+
+/// Parse, analyze and lint the given program, and return the diagnostics that were found.
+///
+/// Lints are checked at their default levels.
+/// The diagnostics are empty if the program is well-formed and no lint fires.
+pub fn check_program(file: &str) -> Vec<Diagnostic> {
+    check_program_with(file, &LintConfig::default())
+}
+
+/// Parse, analyze and lint the given program, and return the diagnostics that were found.
+///
+/// Lints are checked at the levels of the given `config`.
+/// Lints are skipped if the program is not well-formed.
+pub fn check_program_with(file: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let result = stdlib::parse_and_link(file).and_then(|(linked, program)| {
+        ast::Program::analyze(&program)
+            .with_file(linked)
+            .map(|_| program)
+    });
+
+    match result {
+        Ok(program) => lint::lint_program(&program, config),
+        Err(error) => vec![Diagnostic::from(&error)],
+    }
+}
+
+/// Parse, analyze, lint and compile the given program.
+///
+/// The program is analyzed only once for both linting and compilation.
+/// If `witness_values` are given, then they are linted against the same program.
+/// Return the result of compilation together with the lint diagnostics.
+/// Lints are checked at the levels of the given `config`.
+/// Lints are skipped if the program is not well-formed.
+pub fn compile_program_with(
+    file: &str,
+    arguments: Arguments,
+    witness_values: Option<&WitnessValues>,
+    include_debug_symbols: bool,
+    config: &LintConfig,
+) -> (Result<CompiledProgram, crate::Error>, Vec<Diagnostic>) {
+    let (linked, program) = match stdlib::parse_and_link(file) {
+        Ok(parsed) => parsed,
+        Err(error) => return (Err(error.into()), Vec::new()),
+    };
+    let template = match TemplateProgram::from_parsed(linked, &program) {
+        Ok(template) => template,
+        Err(error) => return (Err(error.into()), Vec::new()),
+    };
+    let mut diagnostics = lint::lint_program(&program, config);
+    if let Some(values) = witness_values {
+        diagnostics.extend(lint::lint_witness_values(&program, values, config));
+    }
+    (
+        template.instantiate(arguments, include_debug_symbols),
+        diagnostics,
+    )
+}
+
+/// Convert the given diagnostics into a [SARIF](https://sarifweb.azurewebsites.net) log.
+///
+/// `uri` identifies the source file to which the diagnostics belong.
+#[cfg(feature = "serde")]
+pub fn to_sarif(diagnostics: &[Diagnostic], uri: &str) -> serde_json::Value {
+    use serde_json::json;
+
+    let region = |span: &Span| {
+        json!({
+            "startLine": span.start.line,
+            "startColumn": span.start.col,
+            "endLine": span.end.line,
+            "endColumn": span.end.col,
+        })
+    };
+    let location = |span: &Span| {
+        json!({
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri },
+                "region": region(span),
+            }
+        })
+    };
+
+    let mut rules: Vec<&'static str> = diagnostics.iter().filter_map(|d| d.code).collect();
+    rules.sort_unstable();
+    rules.dedup();
+
+    let results: Vec<serde_json::Value> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut message = diagnostic.message.clone();
+            for note in &diagnostic.notes {
+                message.push_str("\nnote: ");
+                message.push_str(note);
+            }
+            let mut result = json!({
+                "level": diagnostic.severity.to_string(),
+                "message": { "text": message },
+                "locations": diagnostic.span.iter().map(location).collect::<Vec<_>>(),
+                "relatedLocations": diagnostic.labels.iter().map(|label| {
+                    let mut related = location(&label.span);
+                    related["message"] = json!({ "text": label.message });
+                    related
+                }).collect::<Vec<_>>(),
+                "fixes": diagnostic.fixes.iter().map(|fix| json!({
+                    "description": { "text": fix.message },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": uri },
+                        "replacements": [{
+                            "deletedRegion": region(&fix.span),
+                            "insertedContent": { "text": fix.replacement },
+                        }],
+                    }],
+                })).collect::<Vec<_>>(),
+            });
+            // SARIF forbids a null rule id, so diagnostics without a code have none
+            if let Some(code) = diagnostic.code {
+                result["ruleId"] = json!(code);
+            }
+            result
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "simc",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/BlockstreamResearch/SimplicityHL",
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_bound_fix() {
+        let diagnostics = check_program("fn main() { let x: List<u8, 5> = list![]; }");
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code, Some("E0002"));
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.fixes.len(), 1);
+        assert_eq!(diagnostic.fixes[0].replacement, "8");
+    }
+
+    #[test]
+    fn bit_string_fix() {
+        let diagnostics = check_program("fn main() { let x: u4 = 0b101; }");
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code, Some("E0003"));
+        assert_eq!(diagnostic.fixes[0].replacement, "0b0101");
+    }
+
+    #[test]
+    fn well_formed_program() {
+        assert!(check_program("fn main() {}").is_empty());
+    }
+
+    #[test]
+    fn lint_warning() {
+        let diagnostics = check_program("fn main() { let x: u8 = dbg!(1); }");
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+        assert!(!diagnostics.iter().any(Diagnostic::is_error));
+    }
+
+    #[test]
+    fn compile_and_lint() {
+        let (compiled, diagnostics) = compile_program_with(
+            "fn main() { let x: u8 = dbg!(1); }",
+            Arguments::default(),
+            None,
+            false,
+            &LintConfig::default(),
+        );
+        assert!(compiled.is_ok());
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+
+        let (compiled, diagnostics) = compile_program_with(
+            "fn main() { let x: u32 = y; }",
+            Arguments::default(),
+            None,
+            false,
+            &LintConfig::default(),
+        );
+        let error = compiled.unwrap_err();
+        assert!(diagnostics.is_empty());
+        assert_eq!(Diagnostic::from(&error).code, Some("E0024"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn sarif_output() {
+        let diagnostics = check_program("fn main() { let x: u32 = y; }");
+        let sarif = to_sarif(&diagnostics, "main.simf");
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "E0024");
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "main.simf"
+        );
+
+        let sarif = to_sarif(&[Diagnostic::error("missing witness")], "main.simf");
+        let result = &sarif["runs"][0]["results"][0];
+        assert!(result.get("ruleId").is_none());
+    }
+}
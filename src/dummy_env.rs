@@ -75,16 +75,32 @@ pub fn dummy_with_tx(tx: elements::Transaction) -> ElementsEnv<Arc<elements::Tra
     )
 }
 
+/// The default companion Bitcoin script of the dummy environment.
+///
+/// The script is a 2-of-2 multisig: `OP_2 <pubkey> <pubkey> OP_2 OP_CHECKMULTISIG`.
+pub fn default_script() -> Script {
+    Script::from_hex(
+        "5221033523982d58e94be3b735731593f8225043880d53727235b566c515d24a0f7baf21025eb4655feae15a304653e27441ca8e8ced2bef89c22ab6b20424b4c07b3d14cc52ae"
+    ).unwrap()
+}
+
 /// Returns a dummy Elements environment with the given locktime and sequence.
 pub fn dummy_with(
     lock_time: elements::LockTime,
     sequence: elements::Sequence,
     include_fee_output: bool,
 ) -> UnchainedEnv {
-    let script = Script::from_hex(
-        "5221033523982d58e94be3b735731593f8225043880d53727235b566c515d24a0f7baf21025eb4655feae15a304653e27441ca8e8ced2bef89c22ab6b20424b4c07b3d14cc52ae"
-    ).unwrap();
+    dummy_with_script(lock_time, sequence, include_fee_output, default_script())
+}
 
+/// Returns a dummy Elements environment with the given locktime, sequence
+/// and companion Bitcoin script.
+pub fn dummy_with_script(
+    lock_time: elements::LockTime,
+    sequence: elements::Sequence,
+    include_fee_output: bool,
+    script: Script,
+) -> UnchainedEnv {
     let default_tx = create_default_transaction(lock_time, sequence, include_fee_output);
     let elements_tx = dummy_with_tx(default_tx);
 
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use simplicity::hashes::{sha256, Hash, HashEngine};
 use simplicity::{elements, Cmr};
 
-use crate::parse::{MatchPattern, Rule};
+use crate::parse::{CallName, MatchPattern, Rule};
 use crate::str::{AliasName, FunctionName, Identifier, JetName, ModuleName, WitnessName};
 use crate::types::{ResolvedType, UIntType};
 
@@ -13,6 +13,7 @@ use crate::types::{ResolvedType, UIntType};
 ///
 /// [`pest::Position<'i>`] forces us to track lifetimes, so we introduce our own struct.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     /// Line where the object is located.
     ///
@@ -52,6 +53,7 @@ impl Position {
 ///
 /// [`pest::Span<'i>`] forces us to track lifetimes, so we introduce our own struct.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     /// Position where the object starts, inclusively.
     pub start: Position,
@@ -229,6 +231,11 @@ impl RichError {
     pub fn span(&self) -> &Span {
         &self.span
     }
+
+    /// Access the source file where the error occurred, if it is known.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
 }
 
 impl fmt::Display for RichError {
@@ -338,6 +345,26 @@ pub enum Error {
     ModuleRedefined(ModuleName),
     ArgumentMissing(WitnessName),
     ArgumentTypeMismatch(WitnessName, ResolvedType, ResolvedType),
+    FunctionNotDisconnectable(FunctionName),
+    DelegateTypeMismatch(WitnessName, String, String),
+    JetNotSupported(JetName, &'static str),
+    ModuleUndefined(Identifier),
+    ExpressionTypeUnknown,
+    ExpressionNotIndexable(ResolvedType),
+    ArrayIndexOutOfBounds(usize, usize),
+    ArraySliceOutOfBounds(usize, usize, usize),
+    FunctionNotMappable(FunctionName),
+    TypeNotComparable(ResolvedType),
+    ArrayIndexNotInteger(ResolvedType),
+    ReturnOutsideFunction,
+    TryUnsupportedReturnType(ResolvedType),
+    ConstantRedefined(Identifier),
+    FunctionNotConst(FunctionName),
+    ConstEvaluationFailed(String),
+    MessageNotSupported(CallName),
+    WitnessFunctionCalledTwice(FunctionName, WitnessName),
+    WitnessInLoop(FunctionName, WitnessName),
+    DisconnectOutsideMain,
 }
 
 #[rustfmt::skip]
@@ -450,7 +477,7 @@ impl fmt::Display for Error {
             ),
             Error::WitnessReused(name) => write!(
                 f,
-                "Witness `{name}` has been used before somewhere in the program"
+                "Witness `{name}` has already been used on this path through the program"
             ),
             Error::WitnessTypeMismatch(name, declared, assigned) => write!(
                 f,
@@ -476,6 +503,86 @@ impl fmt::Display for Error {
                 f,
                 "Parameter `{name}` was declared with type `{declared}` but its assigned argument is of type `{assigned}`"
             ),
+            Error::FunctionNotDisconnectable(name) => write!(
+                f,
+                "Expected a signature like `fn {name}(cmr: u256, input: A) -> (B, C)` for a disconnect"
+            ),
+            Error::DelegateTypeMismatch(name, declared, assigned) => write!(
+                f,
+                "Delegate `{name}` was declared with type `{declared}` but its assigned program is of type `{assigned}`"
+            ),
+            Error::JetNotSupported(name, jet_set) => write!(
+                f,
+                "Jet `{name}` is not supported by the target jet set {jet_set}"
+            ),
+            Error::ModuleUndefined(name) => write!(
+                f,
+                "Module `std::{name}` does not exist"
+            ),
+            Error::ExpressionTypeUnknown => write!(
+                f,
+                "Cannot infer the type of the expression; assign it to a variable with a type annotation"
+            ),
+            Error::ExpressionNotIndexable(ty) => write!(
+                f,
+                "Expected an array; found expression of type `{ty}`"
+            ),
+            Error::ArrayIndexOutOfBounds(index, size) => write!(
+                f,
+                "Index {index} is out of bounds for an array of size {size}"
+            ),
+            Error::ArraySliceOutOfBounds(start, end, size) => write!(
+                f,
+                "Range {start}..{end} is out of bounds for an array of size {size}"
+            ),
+            Error::FunctionNotMappable(name) => write!(
+                f,
+                "Expected a signature like `fn {name}(element: A) -> B` for a map"
+            ),
+            Error::TypeNotComparable(ty) => write!(
+                f,
+                "Values of type `{ty}` cannot be compared for equality"
+            ),
+            Error::ArrayIndexNotInteger(ty) => write!(
+                f,
+                "Expected an unsigned integer as array index; found expression of type `{ty}`"
+            ),
+            Error::ReturnOutsideFunction => write!(
+                f,
+                "Cannot return outside of a function body"
+            ),
+            Error::TryUnsupportedReturnType(ty) => write!(
+                f,
+                "The `?` operator can only be used in a function that returns `Option` or `Either`; found return type `{ty}`"
+            ),
+            Error::ConstantRedefined(name) => write!(
+                f,
+                "Constant `{name}` was defined multiple times"
+            ),
+            Error::FunctionNotConst(name) => write!(
+                f,
+                "Function `{name}` cannot be called at compile time because it is not a `const fn`"
+            ),
+            Error::ConstEvaluationFailed(description) => write!(
+                f,
+                "Evaluation at compile time failed: {description}"
+            ),
+            Error::MessageNotSupported(name) => write!(
+                f,
+                "Call `{name}` does not take a message, only `assert!` and `panic!` do"
+            ),
+            Error::WitnessFunctionCalledTwice(name, witness) => write!(
+                f,
+                "Function `{name}` uses witness `{witness}`, which has already been used by an earlier call"
+            ),
+            Error::WitnessInLoop(name, witness) => write!(
+                f,
+                "Function `{name}` cannot be called in a loop because it uses witness `{witness}`"
+            ),
+            Error::DisconnectOutsideMain => write!(
+                f,
+                "Delegated programs are not allowed outside the `main` function"
+            ),
         }
     }
 }
@@ -487,6 +594,154 @@ impl Error {
     pub fn with_span(self, span: Span) -> RichError {
         RichError::new(self, span)
     }
+
+    /// Return the stable code of the error.
+    ///
+    /// Codes are never reused, even if the error is removed.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Error::ArraySizeNonZero(..) => "E0001",
+            Error::ListBoundPow2(..) => "E0002",
+            Error::BitStringPow2(..) => "E0003",
+            Error::HexStringLen(..) => "E0004",
+            Error::ForWhileWidthPow2(..) => "E0005",
+            Error::CannotParse(..) => "E0006",
+            Error::Grammar(..) => "E0007",
+            Error::IncompatibleMatchArms(..) => "E0008",
+            Error::CannotCompile(..) => "E0009",
+            Error::JetDoesNotExist(..) => "E0010",
+            Error::InvalidCast(..) => "E0011",
+            Error::MainNoInputs => "E0012",
+            Error::MainNoOutput => "E0013",
+            Error::MainRequired => "E0014",
+            Error::FunctionRedefined(..) => "E0015",
+            Error::FunctionUndefined(..) => "E0016",
+            Error::InvalidNumberOfArguments(..) => "E0017",
+            Error::FunctionNotFoldable(..) => "E0018",
+            Error::FunctionNotLoopable(..) => "E0019",
+            Error::ExpressionUnexpectedType(..) => "E0020",
+            Error::ExpressionTypeMismatch(..) => "E0021",
+            Error::ExpressionNotConstant => "E0022",
+            Error::IntegerOutOfBounds(..) => "E0023",
+            Error::UndefinedVariable(..) => "E0024",
+            Error::UndefinedAlias(..) => "E0025",
+            Error::VariableReuseInPattern(..) => "E0026",
+            Error::WitnessReused(..) => "E0027",
+            Error::WitnessTypeMismatch(..) => "E0028",
+            Error::WitnessReassigned(..) => "E0029",
+            Error::WitnessOutsideMain => "E0030",
+            Error::ModuleRedefined(..) => "E0031",
+            Error::ArgumentMissing(..) => "E0032",
+            Error::ArgumentTypeMismatch(..) => "E0033",
+            Error::FunctionNotDisconnectable(..) => "E0034",
+            Error::DelegateTypeMismatch(..) => "E0035",
+            Error::JetNotSupported(..) => "E0036",
+            Error::ModuleUndefined(..) => "E0037",
+            Error::ExpressionTypeUnknown => "E0038",
+            Error::ExpressionNotIndexable(..) => "E0039",
+            Error::ArrayIndexOutOfBounds(..) => "E0040",
+            Error::ArraySliceOutOfBounds(..) => "E0041",
+            Error::FunctionNotMappable(..) => "E0042",
+            Error::TypeNotComparable(..) => "E0043",
+            Error::ArrayIndexNotInteger(..) => "E0044",
+            Error::ReturnOutsideFunction => "E0045",
+            Error::TryUnsupportedReturnType(..) => "E0046",
+            Error::ConstantRedefined(..) => "E0047",
+            Error::FunctionNotConst(..) => "E0048",
+            Error::ConstEvaluationFailed(..) => "E0049",
+            Error::MessageNotSupported(..) => "E0050",
+            Error::WitnessFunctionCalledTwice(..) => "E0051",
+            Error::WitnessInLoop(..) => "E0052",
+            Error::DisconnectOutsideMain => "E0053",
+        }
+    }
+
+    /// Return the long-form explanation of the error, in Markdown.
+    pub fn explanation(&self) -> &'static str {
+        explain(self.code()).expect("every error code has an explanation")
+    }
+}
+
+/// Long-form explanations of the error codes.
+///
+/// The explanations are the pages of the error index in the SimplicityHL book.
+const EXPLANATIONS: [(&str, &str); 53] = [
+    ("E0001", include_str!("../book/src/error_codes/E0001.md")),
+    ("E0002", include_str!("../book/src/error_codes/E0002.md")),
+    ("E0003", include_str!("../book/src/error_codes/E0003.md")),
+    ("E0004", include_str!("../book/src/error_codes/E0004.md")),
+    ("E0005", include_str!("../book/src/error_codes/E0005.md")),
+    ("E0006", include_str!("../book/src/error_codes/E0006.md")),
+    ("E0007", include_str!("../book/src/error_codes/E0007.md")),
+    ("E0008", include_str!("../book/src/error_codes/E0008.md")),
+    ("E0009", include_str!("../book/src/error_codes/E0009.md")),
+    ("E0010", include_str!("../book/src/error_codes/E0010.md")),
+    ("E0011", include_str!("../book/src/error_codes/E0011.md")),
+    ("E0012", include_str!("../book/src/error_codes/E0012.md")),
+    ("E0013", include_str!("../book/src/error_codes/E0013.md")),
+    ("E0014", include_str!("../book/src/error_codes/E0014.md")),
+    ("E0015", include_str!("../book/src/error_codes/E0015.md")),
+    ("E0016", include_str!("../book/src/error_codes/E0016.md")),
+    ("E0017", include_str!("../book/src/error_codes/E0017.md")),
+    ("E0018", include_str!("../book/src/error_codes/E0018.md")),
+    ("E0019", include_str!("../book/src/error_codes/E0019.md")),
+    ("E0020", include_str!("../book/src/error_codes/E0020.md")),
+    ("E0021", include_str!("../book/src/error_codes/E0021.md")),
+    ("E0022", include_str!("../book/src/error_codes/E0022.md")),
+    ("E0023", include_str!("../book/src/error_codes/E0023.md")),
+    ("E0024", include_str!("../book/src/error_codes/E0024.md")),
+    ("E0025", include_str!("../book/src/error_codes/E0025.md")),
+    ("E0026", include_str!("../book/src/error_codes/E0026.md")),
+    ("E0027", include_str!("../book/src/error_codes/E0027.md")),
+    ("E0028", include_str!("../book/src/error_codes/E0028.md")),
+    ("E0029", include_str!("../book/src/error_codes/E0029.md")),
+    ("E0030", include_str!("../book/src/error_codes/E0030.md")),
+    ("E0031", include_str!("../book/src/error_codes/E0031.md")),
+    ("E0032", include_str!("../book/src/error_codes/E0032.md")),
+    ("E0033", include_str!("../book/src/error_codes/E0033.md")),
+    ("E0034", include_str!("../book/src/error_codes/E0034.md")),
+    ("E0035", include_str!("../book/src/error_codes/E0035.md")),
+    ("E0036", include_str!("../book/src/error_codes/E0036.md")),
+    ("E0037", include_str!("../book/src/error_codes/E0037.md")),
+    ("E0038", include_str!("../book/src/error_codes/E0038.md")),
+    ("E0039", include_str!("../book/src/error_codes/E0039.md")),
+    ("E0040", include_str!("../book/src/error_codes/E0040.md")),
+    ("E0041", include_str!("../book/src/error_codes/E0041.md")),
+    ("E0042", include_str!("../book/src/error_codes/E0042.md")),
+    ("E0043", include_str!("../book/src/error_codes/E0043.md")),
+    ("E0044", include_str!("../book/src/error_codes/E0044.md")),
+    ("E0045", include_str!("../book/src/error_codes/E0045.md")),
+    ("E0046", include_str!("../book/src/error_codes/E0046.md")),
+    ("E0047", include_str!("../book/src/error_codes/E0047.md")),
+    ("E0048", include_str!("../book/src/error_codes/E0048.md")),
+    ("E0049", include_str!("../book/src/error_codes/E0049.md")),
+    ("E0050", include_str!("../book/src/error_codes/E0050.md")),
+    ("E0051", include_str!("../book/src/error_codes/E0051.md")),
+    ("E0052", include_str!("../book/src/error_codes/E0052.md")),
+    ("E0053", include_str!("../book/src/error_codes/E0053.md")),
+];
+
+/// Return the long-form explanation of the error with the given `code`, in Markdown.
+///
+/// Codes are case-insensitive. Return `None` if there is no error with the given code.
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, explanation)| *explanation)
+}
+
+/// Return the URL of the online explanation of the error with the given `code`.
+///
+/// Return `None` if there is no error with the given code.
+pub fn explanation_url(code: &str) -> Option<String> {
+    let (code, _) = EXPLANATIONS
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))?;
+    Some(format!(
+        "{}/blob/master/book/src/error_codes/{code}.md",
+        env!("CARGO_PKG_REPOSITORY")
+    ))
 }
 
 impl From<elements::hex::Error> for Error {
@@ -523,6 +778,19 @@ let x: u32 = Left(
 );"#;
     const EMPTY_FILE: &str = "";
 
+    #[test]
+    fn every_code_is_explained() {
+        for (n, (code, explanation)) in EXPLANATIONS.iter().enumerate() {
+            assert_eq!(*code, format!("E{:04}", n + 1));
+            assert!(explanation.starts_with(&format!("# {code}: ")));
+        }
+        assert_eq!(Error::MainNoInputs.explanation(), explain("e0012").unwrap());
+        assert!(explain("E9999").is_none());
+        assert!(explanation_url("E0002")
+            .unwrap()
+            .ends_with("/book/src/error_codes/E0002.md"));
+    }
+
     #[test]
     fn display_single_line() {
         let error = Error::ListBoundPow2(5)
@@ -5,47 +5,70 @@ pub extern crate simplicity_unchained;
 pub mod array;
 pub mod ast;
 pub mod compile;
+pub mod coverage;
 pub mod debug;
+pub mod decompile;
+pub mod diagnostic;
 pub mod dummy_env;
 pub mod error;
 pub mod jet;
+pub mod lint;
 pub mod named;
 pub mod num;
 pub mod parse;
 pub mod pattern;
 #[cfg(feature = "serde")]
 mod serde;
+pub mod stdlib;
 pub mod str;
 pub mod tracker;
 pub mod types;
 pub mod value;
 mod witness;
 
+use std::fmt;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
-use simplicity_unchained::jets::environments::UnchainedEnv;
 use simplicity_unchained::jets::unchained::ElementsExtension;
 
-use simplicity::{CommitNode, RedeemNode};
+use simplicity::bit_machine::{ExecTracker, ExecutionError};
+use simplicity::dag::{DagLike, InternalSharing};
+use simplicity::node::Inner;
+use simplicity::{BitIter, BitMachine, Cmr, CommitNode, Cost, DecodeError, RedeemNode};
 
 pub extern crate either;
 pub extern crate simplicity;
 pub use simplicity::elements;
 
-use crate::debug::DebugSymbols;
-use crate::error::WithFile;
-use crate::parse::ParseFromStr;
+use crate::debug::{DebugSymbols, FallibleCall};
+use crate::error::{RichError, Span, WithFile};
+use crate::jet::JetSet;
+use crate::str::WitnessName;
+use crate::tracker::RunTracker;
 pub use crate::types::ResolvedType;
 pub use crate::value::Value;
-pub use crate::witness::{Arguments, Parameters, WitnessTypes, WitnessValues};
+pub use crate::witness::{
+    Arguments, DelegateTypes, Delegates, Parameters, WitnessTypes, WitnessValues,
+};
+
+/// Version of the SimplicityHL compiler.
+///
+/// The compiler output is deterministic: the same source file, arguments and compiler version
+/// always produce the same Simplicity target code and the same CMR, on every platform.
+pub const COMPILER_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// The template of a SimplicityHL program.
 ///
 /// A template has parameterized values that need to be supplied with arguments.
+///
+/// The program is compiled for the jet set `J`.
+/// By default, this is the set of Elements jets with the script extension.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct TemplateProgram {
+pub struct TemplateProgram<J: JetSet = ElementsExtension> {
     simfony: ast::Program,
     file: Arc<str>,
+    jets: PhantomData<J>,
 }
 
 impl TemplateProgram {
@@ -54,13 +77,38 @@ impl TemplateProgram {
     /// ## Errors
     ///
     /// The string is not a valid SimplicityHL program.
-    pub fn new<Str: Into<Arc<str>>>(s: Str) -> Result<Self, String> {
-        let file = s.into();
-        let parse_program = parse::Program::parse_from_str(&file)?;
-        let ast_program = ast::Program::analyze(&parse_program).with_file(Arc::clone(&file))?;
+    pub fn new<Str: Into<Arc<str>>>(s: Str) -> Result<Self, Error> {
+        Self::new_with_jets(s)
+    }
+}
+
+impl<J: JetSet> TemplateProgram<J> {
+    /// Parse the template of a SimplicityHL program for the jet set `J`.
+    ///
+    /// ## Errors
+    ///
+    /// - The string is not a valid SimplicityHL program.
+    /// - The program imports a module that does not exist.
+    /// - The program calls a jet that is not part of the jet set.
+    pub fn new_with_jets<Str: Into<Arc<str>>>(s: Str) -> Result<Self, Error> {
+        let file: Arc<str> = s.into();
+        let (file, parse_program) = stdlib::parse_and_link(&file)?;
+        Ok(Self::from_parsed(file, &parse_program)?)
+    }
+
+    /// Analyze a program that was already parsed and linked to the standard library.
+    ///
+    /// `file` is the linked source file.
+    pub(crate) fn from_parsed(
+        file: Arc<str>,
+        parse_program: &parse::Program,
+    ) -> Result<Self, RichError> {
+        let ast_program =
+            ast::Program::analyze_for::<J>(parse_program).with_file(Arc::clone(&file))?;
         Ok(Self {
             simfony: ast_program,
             file,
+            jets: PhantomData,
         })
     }
 
@@ -73,35 +121,75 @@ impl TemplateProgram {
     ///
     /// ## Errors
     ///
-    /// The arguments are not consistent with the parameters of the program.
-    /// Use [`TemplateProgram::parameters`] to see which parameters the program has.
+    /// - The arguments are not consistent with the parameters of the program.
+    ///   Use [`TemplateProgram::parameters`] to see which parameters the program has.
+    /// - The types of the compiled program cannot be finalized.
     pub fn instantiate(
         &self,
         arguments: Arguments,
         include_debug_symbols: bool,
-    ) -> Result<CompiledProgram, String> {
+    ) -> Result<CompiledProgram<J>, Error> {
         arguments
             .is_consistent(self.simfony.parameters())
-            .map_err(|error| error.to_string())?;
+            .map_err(Error::Arguments)?;
 
+        let mut debug_symbols = self.simfony.debug_symbols(self.file.as_ref());
         let commit = self
             .simfony
-            .compile(arguments, include_debug_symbols)
-            .with_file(Arc::clone(&self.file))?;
+            .compile(arguments, include_debug_symbols, &mut debug_symbols)
+            .map_err(|error| match error {
+                Error::Program(error) => Error::Program(error.with_file(Arc::clone(&self.file))),
+                error => error,
+            })?;
+        debug_symbols.set_program_cmr(commit.cmr());
 
         Ok(CompiledProgram {
-            debug_symbols: self.simfony.debug_symbols(self.file.as_ref()),
+            debug_symbols,
             simplicity: commit,
             witness_types: self.simfony.witness_types().shallow_clone(),
+            delegate_types: self.simfony.delegate_types().shallow_clone(),
         })
     }
+
+    /// Check that the template compiles to the `expected` CMR
+    /// when it is instantiated with the given `arguments`.
+    ///
+    /// This verifies that a published source file and its arguments produce
+    /// the CMR that is committed to on chain, such as in a Taproot address.
+    /// Debug symbols change the CMR, so `include_debug_symbols` must match
+    /// how the deployed program was compiled.
+    ///
+    /// ## Errors
+    ///
+    /// - The template cannot be instantiated with the arguments.
+    /// - The compiled program has a different CMR.
+    pub fn verify_cmr(
+        &self,
+        arguments: Arguments,
+        expected: Cmr,
+        include_debug_symbols: bool,
+    ) -> Result<(), Error> {
+        let actual = self
+            .instantiate(arguments, include_debug_symbols)?
+            .commit()
+            .cmr();
+        if actual != expected {
+            return Err(Error::CmrMismatch {
+                expected,
+                actual,
+                compiler_version: COMPILER_VERSION,
+            });
+        }
+        Ok(())
+    }
 }
 
 /// A SimplicityHL program, compiled to Simplicity.
 #[derive(Clone, Debug)]
-pub struct CompiledProgram {
-    simplicity: Arc<named::CommitNode<ElementsExtension>>,
+pub struct CompiledProgram<J: JetSet = ElementsExtension> {
+    simplicity: Arc<named::CommitNode<J>>,
     witness_types: WitnessTypes,
+    delegate_types: DelegateTypes,
     debug_symbols: DebugSymbols,
 }
 
@@ -116,18 +204,27 @@ impl CompiledProgram {
         s: Str,
         arguments: Arguments,
         include_debug_symbols: bool,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, Error> {
         TemplateProgram::new(s)
             .and_then(|template| template.instantiate(arguments, include_debug_symbols))
     }
+}
 
+impl<J: JetSet> CompiledProgram<J> {
     /// Access the debug symbols for the Simplicity target code.
     pub fn debug_symbols(&self) -> &DebugSymbols {
         &self.debug_symbols
     }
 
+    /// Access the types of the delegated programs.
+    ///
+    /// Delegated programs are supplied via [`CompiledProgram::satisfy_with_delegates`].
+    pub fn delegate_types(&self) -> &DelegateTypes {
+        &self.delegate_types
+    }
+
     /// Access the Simplicity target code, without witness data.
-    pub fn commit(&self) -> Arc<CommitNode<ElementsExtension>> {
+    pub fn commit(&self) -> Arc<CommitNode<J>> {
         named::forget_names(&self.simplicity)
     }
 
@@ -137,7 +234,7 @@ impl CompiledProgram {
     ///
     /// - Witness values have a different type than declared in the SimplicityHL program.
     /// - There are missing witness values.
-    pub fn satisfy(&self, witness_values: WitnessValues) -> Result<SatisfiedProgram, String> {
+    pub fn satisfy(&self, witness_values: WitnessValues) -> Result<SatisfiedProgram<J>, Error> {
         self.satisfy_with_env(witness_values, None)
     }
 
@@ -148,18 +245,43 @@ impl CompiledProgram {
     ///
     /// - Witness values have a different type than declared in the SimplicityHL program.
     /// - There are missing witness values.
+    /// - The program fails when it is pruned with the given environment.
     pub fn satisfy_with_env(
         &self,
         witness_values: WitnessValues,
-        env: Option<&UnchainedEnv>,
-    ) -> Result<SatisfiedProgram, String> {
+        env: Option<&J::Environment>,
+    ) -> Result<SatisfiedProgram<J>, Error> {
+        self.satisfy_with_delegates(witness_values, Delegates::default(), env)
+    }
+
+    /// Satisfy the SimplicityHL program with the given `witness_values` and delegated programs.
+    /// If `env` is `None`, the program is not pruned, otherwise it is pruned with the given environment.
+    ///
+    /// Each `disconnect::<f, NAME>` in the program is satisfied with the delegated program `NAME`.
+    ///
+    /// ## Errors
+    ///
+    /// - Witness values have a different type than declared in the SimplicityHL program.
+    /// - Delegated programs have a different type than declared in the SimplicityHL program.
+    /// - There are missing witness values or delegated programs.
+    /// - The program fails when it is pruned with the given environment.
+    pub fn satisfy_with_delegates(
+        &self,
+        witness_values: WitnessValues,
+        delegates: Delegates<J>,
+        env: Option<&J::Environment>,
+    ) -> Result<SatisfiedProgram<J>, Error> {
         witness_values
             .is_consistent(&self.witness_types)
-            .map_err(|e| e.to_string())?;
+            .map_err(Error::Witness)?;
+        delegates
+            .is_consistent(&self.delegate_types)
+            .map_err(Error::Delegates)?;
 
-        let mut simplicity_redeem = named::populate_witnesses(&self.simplicity, witness_values)?;
+        let mut simplicity_redeem =
+            named::populate_witnesses(&self.simplicity, witness_values, delegates.as_inner())?;
         if let Some(env) = env {
-            simplicity_redeem = simplicity_redeem.prune(env).map_err(|e| e.to_string())?;
+            simplicity_redeem = simplicity_redeem.prune(env)?;
         }
         Ok(SatisfiedProgram {
             simplicity: simplicity_redeem,
@@ -170,8 +292,8 @@ impl CompiledProgram {
 
 /// A SimplicityHL program, compiled to Simplicity and satisfied with witness data.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct SatisfiedProgram {
-    simplicity: Arc<RedeemNode<ElementsExtension>>,
+pub struct SatisfiedProgram<J: JetSet = ElementsExtension> {
+    simplicity: Arc<RedeemNode<J>>,
     debug_symbols: DebugSymbols,
 }
 
@@ -188,13 +310,44 @@ impl SatisfiedProgram {
         arguments: Arguments,
         witness_values: WitnessValues,
         include_debug_symbols: bool,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, Error> {
         let compiled = CompiledProgram::new(s, arguments, include_debug_symbols)?;
         compiled.satisfy(witness_values)
     }
+}
+
+impl<J: JetSet> SatisfiedProgram<J> {
+    /// Decode a satisfied program from the serialized Simplicity target code and witness data.
+    ///
+    /// The `debug_symbols` are usually loaded from the sidecar file (`.simdbg`)
+    /// that was written when the program was compiled.
+    /// They enable the tracking of calls without the SimplicityHL source file.
+    /// Pass [`DebugSymbols::default()`] if the program has no debug symbols.
+    ///
+    /// ## Errors
+    ///
+    /// - The program or the witness data cannot be decoded.
+    /// - The debug symbols belong to a program with a different CMR.
+    pub fn decode(
+        program_bytes: &[u8],
+        witness_bytes: &[u8],
+        debug_symbols: DebugSymbols,
+    ) -> Result<Self, Error> {
+        let simplicity =
+            RedeemNode::decode(BitIter::from(program_bytes), BitIter::from(witness_bytes))?;
+        if let Some(expected) = debug_symbols.program_cmr() {
+            if expected != simplicity.cmr() {
+                return Err(Error::DebugSymbolsMismatch(expected, simplicity.cmr()));
+            }
+        }
+        Ok(Self {
+            simplicity,
+            debug_symbols,
+        })
+    }
 
     /// Access the Simplicity target code, including witness data.
-    pub fn redeem(&self) -> &Arc<RedeemNode<ElementsExtension>> {
+    pub fn redeem(&self) -> &Arc<RedeemNode<J>> {
         &self.simplicity
     }
 
@@ -202,6 +355,265 @@ impl SatisfiedProgram {
     pub fn debug_symbols(&self) -> &DebugSymbols {
         &self.debug_symbols
     }
+
+    /// Run the program on the Bit Machine in the given environment.
+    ///
+    /// The given `tracker` visits each executed node,
+    /// for instance to print debug output via [`tracker::DefaultTracker`].
+    ///
+    /// Return the cost of the nodes that were executed.
+    /// The cost is at most the cost bound of the program, which consensus charges
+    /// (see [`PrunedProgram::cost_bound`]). The run is cheaper if it skips expensive branches.
+    ///
+    /// ## Errors
+    ///
+    /// The program fails. If the program was compiled with debug symbols,
+    /// then the failure names the fallible call that failed.
+    pub fn run<T: ExecTracker<J>>(
+        &self,
+        env: &J::Environment,
+        tracker: &mut T,
+    ) -> Result<Cost, ExecutionFailure> {
+        let mut mac = BitMachine::for_program(&self.simplicity)
+            .map_err(|error| ExecutionFailure::new(ExecutionError::from(error), None))?;
+        let mut tracker = RunTracker::new(&self.debug_symbols, tracker);
+        match mac.exec_with_tracker(&self.simplicity, env, &mut tracker) {
+            Ok(_) => Ok(tracker.cost()),
+            Err(error) => Err(ExecutionFailure::new(error, tracker.failed_call().cloned())),
+        }
+    }
+
+    /// Prune the program in the given environment.
+    ///
+    /// The program is run on the Bit Machine and the branches that are not executed are removed.
+    /// The given `tracker` visits each executed node, like in [`SatisfiedProgram::run`].
+    ///
+    /// ## Errors
+    ///
+    /// The program fails. If the program was compiled with debug symbols,
+    /// then the failure names the fallible call that failed.
+    pub fn prune<T: ExecTracker<J>>(
+        &self,
+        env: &J::Environment,
+        tracker: &mut T,
+    ) -> Result<PrunedProgram<J>, ExecutionFailure> {
+        let mut tracker = RunTracker::new(&self.debug_symbols, tracker);
+        let pruned = match self.simplicity.prune_with_tracker(env, &mut tracker) {
+            Ok(pruned) => pruned,
+            Err(error) => return Err(ExecutionFailure::new(error, tracker.failed_call().cloned())),
+        };
+
+        // A match arm is pruned if its match expression was executed, but the arm was not.
+        let mut pruned_arms = Vec::new();
+        for node in self.simplicity.as_ref().pre_order_iter::<InternalSharing>() {
+            if !matches!(node.inner(), Inner::Case(..)) {
+                continue;
+            }
+            let Some(tracked_match) = self.debug_symbols.get_match(&node.cmr()) else {
+                continue;
+            };
+            let arm = match (
+                tracker.executed_left::<J>(node.ihr()),
+                tracker.executed_right::<J>(node.ihr()),
+            ) {
+                (false, true) => tracked_match.left(),
+                (true, false) => tracked_match.right(),
+                _ => continue,
+            };
+            if !pruned_arms.contains(arm) {
+                pruned_arms.push(*arm);
+            }
+        }
+
+        Ok(PrunedProgram {
+            cost_bound: pruned.bounds().cost,
+            program: SatisfiedProgram {
+                simplicity: pruned,
+                debug_symbols: self.debug_symbols.clone(),
+            },
+            pruned_arms,
+        })
+    }
+}
+
+/// A SimplicityHL program that was pruned to the branches that are executed in a given environment.
+#[derive(Clone, Debug)]
+pub struct PrunedProgram<J: JetSet = ElementsExtension> {
+    program: SatisfiedProgram<J>,
+    pruned_arms: Vec<Span>,
+    cost_bound: Cost,
+}
+
+impl<J: JetSet> PrunedProgram<J> {
+    /// Access the pruned program.
+    pub fn program(&self) -> &SatisfiedProgram<J> {
+        &self.program
+    }
+
+    /// Access the spans of the match arms that were removed from the program.
+    pub fn pruned_arms(&self) -> &[Span] {
+        &self.pruned_arms
+    }
+
+    /// Access the cost bound of the pruned program.
+    ///
+    /// The bound is computed statically from the pruned program,
+    /// which keeps only the branches that were executed in the environment of pruning.
+    /// It is the cost that consensus charges for the pruned program in this environment.
+    pub fn cost_bound(&self) -> Cost {
+        self.cost_bound
+    }
+}
+
+/// Failure of a SimplicityHL program that is run on the Bit Machine.
+#[derive(Debug)]
+pub struct ExecutionFailure {
+    error: ExecutionError,
+    failed_call: Option<FallibleCall>,
+}
+
+impl ExecutionFailure {
+    fn new(error: ExecutionError, failed_call: Option<FallibleCall>) -> Self {
+        Self { error, failed_call }
+    }
+
+    /// Access the error of the Bit Machine.
+    pub fn error(&self) -> &ExecutionError {
+        &self.error
+    }
+
+    /// Access the fallible call that failed, including its span and input value.
+    ///
+    /// Fallible calls are only tracked if the program was compiled with debug symbols.
+    pub fn failed_call(&self) -> Option<&FallibleCall> {
+        self.failed_call.as_ref()
+    }
+}
+
+impl fmt::Display for ExecutionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.failed_call() {
+            Some(call) => write!(f, "{}: {call}", self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Error of the SimplicityHL library API.
+///
+/// The variants keep the underlying errors,
+/// so an error in the program can be told apart from a missing witness or a failed execution.
+#[derive(Debug)]
+pub enum Error {
+    /// The program is not a valid SimplicityHL program.
+    ///
+    /// The error points to the faulty part of the source file.
+    Program(RichError),
+    /// The arguments are not consistent with the parameters of the program.
+    Arguments(error::Error),
+    /// The witness values are not consistent with the witness types of the program.
+    Witness(error::Error),
+    /// The delegated programs are not consistent with the delegate types of the program.
+    Delegates(error::Error),
+    /// The program uses a witness that is not assigned a value.
+    MissingWitness(WitnessName),
+    /// The program delegates to a program that is not supplied.
+    MissingDelegate(WitnessName),
+    /// The types of the compiled Simplicity program cannot be finalized.
+    Types(simplicity::types::Error),
+    /// The program fails when it is executed or pruned.
+    Execution(ExecutionError),
+    /// The serialized Simplicity target code or witness data cannot be decoded.
+    Decode(DecodeError),
+    /// The program compiles to a different CMR than expected.
+    ///
+    /// The CMR depends on the compiler version, so the error includes the version
+    /// that produced the actual CMR.
+    CmrMismatch {
+        expected: Cmr,
+        actual: Cmr,
+        compiler_version: &'static str,
+    },
+    /// The debug symbols belong to a different program than the decoded one.
+    ///
+    /// The first CMR is the one of the debug symbols and the second CMR is the one of the program.
+    DebugSymbolsMismatch(Cmr, Cmr),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Program(error) => write!(f, "{error}"),
+            Error::Arguments(error) | Error::Witness(error) | Error::Delegates(error) => {
+                write!(f, "{error}")
+            }
+            Error::MissingWitness(name) => write!(f, "missing witness for {name}"),
+            Error::MissingDelegate(name) => write!(f, "missing delegate for {name}"),
+            Error::Types(error) => write!(f, "{error}"),
+            Error::Execution(error) => write!(f, "{error}"),
+            Error::Decode(error) => write!(f, "{error}"),
+            Error::CmrMismatch {
+                expected,
+                actual,
+                compiler_version,
+            } => write!(
+                f,
+                "program compiles to CMR {actual} with compiler version {compiler_version}, but CMR {expected} was expected"
+            ),
+            Error::DebugSymbolsMismatch(expected, actual) => write!(
+                f,
+                "debug symbols belong to the program with CMR {expected}, but the program has CMR {actual}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Program(error) => Some(error),
+            Error::Arguments(error) | Error::Witness(error) | Error::Delegates(error) => {
+                Some(error)
+            }
+            Error::MissingWitness(..)
+            | Error::MissingDelegate(..)
+            | Error::CmrMismatch { .. }
+            | Error::DebugSymbolsMismatch(..) => None,
+            Error::Types(error) => Some(error),
+            Error::Execution(error) => Some(error),
+            Error::Decode(error) => Some(error),
+        }
+    }
+}
+
+impl From<RichError> for Error {
+    fn from(error: RichError) -> Self {
+        Self::Program(error)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(error: DecodeError) -> Self {
+        Self::Decode(error)
+    }
+}
+
+impl From<simplicity::types::Error> for Error {
+    fn from(error: simplicity::types::Error) -> Self {
+        Self::Types(error)
+    }
+}
+
+impl From<ExecutionError> for Error {
+    fn from(error: ExecutionError) -> Self {
+        Self::Execution(error)
+    }
 }
 
 /// Recursively implement [`PartialEq`], [`Eq`] and [`std::hash::Hash`]
@@ -271,17 +683,26 @@ pub trait ArbitraryOfType: Sized {
 pub(crate) mod tests {
     use base64::display::Base64Display;
     use base64::engine::general_purpose::STANDARD;
-    use simplicity::BitMachine;
+    use simplicity::elements::hex::FromHex;
     use std::borrow::Cow;
+    use std::collections::HashMap;
     use std::path::Path;
 
+    use crate::num::U256;
+    use crate::parse::ParseFromStr;
+    use crate::str::{Identifier, WitnessName};
+    use crate::value::UIntValue;
     use crate::*;
 
+    /// Companion script `OP_2 <1 * G> <2 * G> <3 * G> OP_3 OP_CHECKMULTISIG`.
+    pub(crate) const SCRIPT_2_OF_3: &str = "52210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f817982102c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee52102f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f953ae";
+
     pub(crate) struct TestCase<T> {
         program: T,
         lock_time: elements::LockTime,
         sequence: elements::Sequence,
         include_fee_output: bool,
+        script: elements::Script,
     }
 
     impl TestCase<TemplateProgram> {
@@ -300,6 +721,7 @@ pub(crate) mod tests {
                 lock_time: elements::LockTime::ZERO,
                 sequence: elements::Sequence::MAX,
                 include_fee_output: false,
+                script: dummy_env::default_script(),
             }
         }
 
@@ -326,6 +748,7 @@ pub(crate) mod tests {
                 lock_time: self.lock_time,
                 sequence: self.sequence,
                 include_fee_output: self.include_fee_output,
+                script: self.script,
             }
         }
     }
@@ -367,6 +790,30 @@ pub(crate) mod tests {
                 lock_time: self.lock_time,
                 sequence: self.sequence,
                 include_fee_output: self.include_fee_output,
+                script: self.script,
+            }
+        }
+    }
+
+    impl TestCase<CompiledProgram> {
+        pub fn with_delegates(
+            self,
+            witness_values: WitnessValues,
+            delegates: Delegates,
+        ) -> TestCase<SatisfiedProgram> {
+            let program = match self
+                .program
+                .satisfy_with_delegates(witness_values, delegates, None)
+            {
+                Ok(x) => x,
+                Err(error) => panic!("{error}"),
+            };
+            TestCase {
+                program,
+                lock_time: self.lock_time,
+                sequence: self.sequence,
+                include_fee_output: self.include_fee_output,
+                script: self.script,
             }
         }
     }
@@ -388,9 +835,30 @@ pub(crate) mod tests {
             self
         }
 
+        #[allow(dead_code)]
+        pub fn with_fee_output(mut self) -> Self {
+            self.include_fee_output = true;
+            self
+        }
+
+        #[allow(dead_code)]
+        pub fn with_script(mut self, script_hex: &str) -> Self {
+            self.script = elements::Script::from_hex(script_hex).unwrap();
+            self
+        }
+
+        fn env(&self) -> simplicity_unchained::jets::environments::UnchainedEnv {
+            dummy_env::dummy_with_script(
+                self.lock_time,
+                self.sequence,
+                self.include_fee_output,
+                self.script.clone(),
+            )
+        }
+
         #[allow(dead_code)]
         pub fn print_sighash_all(self) -> Self {
-            let env = dummy_env::dummy_with(self.lock_time, self.sequence, self.include_fee_output);
+            let env = self.env();
             dbg!(env.elements_env.c_tx_env().sighash_all());
             self
         }
@@ -412,7 +880,7 @@ pub(crate) mod tests {
         }
 
         fn run(self) -> Result<(), simplicity::bit_machine::ExecutionError> {
-            let env = dummy_env::dummy_with(self.lock_time, self.sequence, self.include_fee_output);
+            let env = self.env();
             let pruned = self.program.redeem().prune(&env)?;
             let mut mac = BitMachine::for_program(&pruned)
                 .expect("program should be within reasonable bounds");
@@ -425,6 +893,11 @@ pub(crate) mod tests {
                 Err(error) => panic!("Unexpected error: {error}"),
             }
         }
+
+        #[allow(dead_code)]
+        pub fn assert_run_failure(self) {
+            assert!(self.run().is_err(), "Expected the program to fail");
+        }
     }
 
     #[test]
@@ -514,6 +987,24 @@ pub(crate) mod tests {
             .assert_run_success();
     }
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn script_multisig() {
+        TestCase::program_file("./examples/script_multisig.simf")
+            .with_script(SCRIPT_2_OF_3)
+            .with_witness_file("./examples/script_multisig.wit")
+            .assert_run_success();
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn script_multisig_wrong_script() {
+        // The default script is a 2-of-2 multisig
+        TestCase::program_file("./examples/script_multisig.simf")
+            .with_witness_file("./examples/script_multisig.wit")
+            .assert_run_failure();
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn p2pk() {
@@ -592,6 +1083,343 @@ pub(crate) mod tests {
             .assert_run_success();
     }
 
+    #[test]
+    fn disconnect_delegate() {
+        let delegate = SatisfiedProgram::new(
+            "fn main() {}",
+            Arguments::default(),
+            WitnessValues::default(),
+            false,
+        )
+        .unwrap();
+        let cmr = U256::from_byte_array(delegate.redeem().cmr().to_byte_array());
+        let prog_text = r#"fn check_cmr(cmr: u256, expected: u256) -> ((), ()) {
+    assert!(jet::eq_256(cmr, expected));
+    ((), ())
+}
+
+fn main() {
+    let (_, _): ((), ()) = disconnect::<check_cmr, DELEGATE>(param::DELEGATE_CMR);
+}
+"#;
+        let arguments = Arguments::from(HashMap::from([(
+            WitnessName::from_str_unchecked("DELEGATE_CMR"),
+            Value::from(UIntValue::from(cmr)),
+        )]));
+        let delegates = Delegates::from(HashMap::from([(
+            WitnessName::from_str_unchecked("DELEGATE"),
+            Arc::clone(delegate.redeem()),
+        )]));
+        TestCase::<TemplateProgram>::template_text(Cow::Borrowed(prog_text))
+            .with_arguments(arguments)
+            .with_delegates(WitnessValues::default(), delegates)
+            .assert_run_success();
+    }
+
+    #[test]
+    fn disconnect_missing_delegate() {
+        let prog_text = r#"fn ignore_cmr(cmr: u256, x: ()) -> ((), ()) {
+    ((), x)
+}
+
+fn main() {
+    let (_, _): ((), ()) = disconnect::<ignore_cmr, DELEGATE>(());
+}
+"#;
+        let program = CompiledProgram::new(prog_text, Arguments::default(), false).unwrap();
+        match program.satisfy(WitnessValues::default()) {
+            Ok(_) => panic!("Missing delegate was falsely accepted"),
+            Err(Error::MissingDelegate(name)) => {
+                assert_eq!(WitnessName::from_str_unchecked("DELEGATE"), name)
+            }
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn typed_errors() {
+        match CompiledProgram::new("fn main() { let x: u8 = y; }", Arguments::default(), false) {
+            Err(Error::Program(error)) => {
+                assert_eq!(
+                    &error::Error::UndefinedVariable(Identifier::from_str_unchecked("y")),
+                    error.error()
+                );
+                assert_eq!(1, error.span().start.line.get());
+            }
+            Ok(_) => panic!("Undefined variable was falsely accepted"),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+
+        let prog_text = "fn main() { assert!(jet::is_zero_32(witness::A)); }";
+        let program = CompiledProgram::new(prog_text, Arguments::default(), false).unwrap();
+        match program.satisfy(WitnessValues::default()) {
+            Err(Error::MissingWitness(name)) => {
+                assert_eq!(WitnessName::from_str_unchecked("A"), name)
+            }
+            Ok(_) => panic!("Missing witness was falsely accepted"),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+
+        let witness_values = WitnessValues::from(HashMap::from([(
+            WitnessName::from_str_unchecked("A"),
+            Value::u32(1),
+        )]));
+        let env = dummy_env::dummy();
+        match program.satisfy_with_env(witness_values, Some(&env)) {
+            Err(error @ Error::Execution(..)) => {
+                assert!(std::error::Error::source(&error).is_some())
+            }
+            Ok(_) => panic!("Failing program was falsely pruned"),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn run_and_prune() {
+        let prog_text = r#"fn main() {
+    let amount: u32 = match witness::IS_BIG {
+        false => 1,
+        true => 1000,
+    };
+    assert!(jet::lt_32(amount, 10), "amount too big");
+}"#;
+        let program = CompiledProgram::new(prog_text, Arguments::default(), true).unwrap();
+        let env = dummy_env::dummy();
+        let witness_values = |is_big: bool| {
+            WitnessValues::from(HashMap::from([(
+                WitnessName::from_str_unchecked("IS_BIG"),
+                Value::from(is_big),
+            )]))
+        };
+
+        let satisfied = program.satisfy(witness_values(false)).unwrap();
+        let mut tracker = tracker::DefaultTracker::new(satisfied.debug_symbols());
+        let cost = satisfied.run(&env, &mut tracker).unwrap();
+        assert!(cost <= satisfied.redeem().bounds().cost);
+        let pruned = satisfied.prune(&env, &mut tracker).unwrap();
+        assert!(pruned.cost_bound() <= satisfied.redeem().bounds().cost);
+        assert!(cost <= pruned.cost_bound());
+        assert_eq!(1, pruned.pruned_arms().len());
+        assert_eq!(4, pruned.pruned_arms()[0].start.line.get());
+        pruned.program().run(&env, &mut tracker).unwrap();
+
+        let satisfied = program.satisfy(witness_values(true)).unwrap();
+        let mut tracker = tracker::DefaultTracker::new(satisfied.debug_symbols());
+        let failure = satisfied.run(&env, &mut tracker).unwrap_err();
+        let failed_call = failure.failed_call().expect("assertion is tracked");
+        assert_eq!(Some("amount too big"), failed_call.message());
+        assert_eq!(6, failed_call.span().start.line.get());
+        assert!(satisfied.prune(&env, &mut tracker).is_err());
+    }
+
+    #[test]
+    fn run_cost() {
+        let prog_text = r#"fn main() {
+    let hash: u256 = match witness::CHEAP {
+        true => 0,
+        false => jet::sha_256_ctx_8_finalize(jet::sha_256_ctx_8_init()),
+    };
+    assert!(jet::eq_256(hash, hash));
+}"#;
+        let program = CompiledProgram::new(prog_text, Arguments::default(), false).unwrap();
+        let env = dummy_env::dummy();
+        let run = |cheap: bool| {
+            let witness_values = WitnessValues::from(HashMap::from([(
+                WitnessName::from_str_unchecked("CHEAP"),
+                Value::from(cheap),
+            )]));
+            let satisfied = program.satisfy(witness_values).unwrap();
+            let cost = satisfied
+                .run(
+                    &env,
+                    &mut tracker::DefaultTracker::new(satisfied.debug_symbols()),
+                )
+                .unwrap();
+            (cost, satisfied.redeem().bounds().cost)
+        };
+
+        let (cheap_cost, bound) = run(true);
+        let (expensive_cost, _) = run(false);
+        assert!(cheap_cost < expensive_cost);
+        assert!(expensive_cost <= bound);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn decode_with_debug_symbols() {
+        let prog_text = r#"fn main() {
+    let amount: u32 = witness::AMOUNT;
+    assert!(jet::lt_32(amount, 10), "amount too big");
+}"#;
+        let program = CompiledProgram::new(prog_text, Arguments::default(), true).unwrap();
+        let witness_values = WitnessValues::from(HashMap::from([(
+            WitnessName::from_str_unchecked("AMOUNT"),
+            Value::from(UIntValue::from(1000u32)),
+        )]));
+        let satisfied = program.satisfy(witness_values).unwrap();
+        let (program_bytes, witness_bytes) = satisfied.redeem().to_vec_with_witness();
+        let sidecar = serde_json::to_string(satisfied.debug_symbols()).unwrap();
+
+        let debug_symbols = serde_json::from_str::<DebugSymbols>(&sidecar).unwrap();
+        let decoded = SatisfiedProgram::<ElementsExtension>::decode(
+            &program_bytes,
+            &witness_bytes,
+            debug_symbols,
+        )
+        .unwrap();
+        assert_eq!(satisfied.redeem().cmr(), decoded.redeem().cmr());
+
+        let env = dummy_env::dummy();
+        let mut tracker = tracker::DefaultTracker::new(decoded.debug_symbols());
+        let failure = decoded.run(&env, &mut tracker).unwrap_err();
+        let failed_call = failure.failed_call().expect("assertion is tracked");
+        assert_eq!(Some("amount too big"), failed_call.message());
+        assert_eq!(3, failed_call.span().start.line.get());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn decode_with_foreign_debug_symbols() {
+        let other = CompiledProgram::new(
+            "fn main() { assert!(jet::eq_32(1, 1)); }",
+            Arguments::default(),
+            true,
+        )
+        .unwrap();
+        let sidecar = serde_json::to_string(other.debug_symbols()).unwrap();
+        let program = SatisfiedProgram::new(
+            "fn main() { assert!(jet::eq_32(2, 2)); }",
+            Arguments::default(),
+            WitnessValues::default(),
+            true,
+        )
+        .unwrap();
+        let (program_bytes, witness_bytes) = program.redeem().to_vec_with_witness();
+
+        let debug_symbols = serde_json::from_str::<DebugSymbols>(&sidecar).unwrap();
+        match SatisfiedProgram::<ElementsExtension>::decode(
+            &program_bytes,
+            &witness_bytes,
+            debug_symbols,
+        ) {
+            Err(Error::DebugSymbolsMismatch(expected, actual)) => {
+                assert_eq!(other.commit().cmr(), expected);
+                assert_eq!(program.redeem().cmr(), actual);
+            }
+            _ => panic!("Debug symbols of a different program were falsely accepted"),
+        }
+        // Empty debug symbols are accepted for any program
+        SatisfiedProgram::<ElementsExtension>::decode(
+            &program_bytes,
+            &witness_bytes,
+            DebugSymbols::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn verify_cmr() {
+        let prog_text = std::fs::read_to_string("./examples/p2pk.simf").unwrap();
+        let args_text = std::fs::read_to_string("./examples/p2pk.args").unwrap();
+        let arguments = serde_json::from_str::<Arguments>(&args_text).unwrap();
+        let template = TemplateProgram::new(prog_text.as_str()).unwrap();
+        let cmr = template
+            .instantiate(arguments.clone(), false)
+            .unwrap()
+            .commit()
+            .cmr();
+
+        // A separately parsed template compiles to the same CMR
+        let template = TemplateProgram::new(prog_text.as_str()).unwrap();
+        template.verify_cmr(arguments.clone(), cmr, false).unwrap();
+        match template.verify_cmr(arguments, cmr, true) {
+            Err(Error::CmrMismatch {
+                expected,
+                actual,
+                compiler_version,
+            }) => {
+                assert_eq!(cmr, expected);
+                assert_ne!(cmr, actual);
+                assert_eq!(COMPILER_VERSION, compiler_version);
+            }
+            _ => panic!("Debug symbols should change the CMR"),
+        }
+    }
+
+    /// CMR of `fail` with the entropy [`simplicity::FailEntropy::ZERO`].
+    ///
+    /// Each `panic!` compiles to this node, so its CMR must never change.
+    const PANIC_CMR: &str = "3765d39e7859f9adc0b8d2a2ac2102bf78a20841973bffbf273518e066f36380";
+
+    #[test]
+    fn panic_cmr_is_stable() {
+        let prog_text = r#"fn main() {
+    let fail: bool = false;
+    match fail {
+        false => {},
+        true => panic!(),
+    };
+}"#;
+        let program = TemplateProgram::new(prog_text)
+            .unwrap()
+            .instantiate(Arguments::default(), false)
+            .unwrap()
+            .commit();
+        let expected = Cmr::from_byte_array(<[u8; 32]>::from_hex(PANIC_CMR).unwrap());
+
+        let fail_cmrs: Vec<_> = program
+            .as_ref()
+            .pre_order_iter::<InternalSharing>()
+            .filter(|node| matches!(node.inner(), Inner::Fail(_)))
+            .map(|node| node.cmr())
+            .collect();
+        assert!(!fail_cmrs.is_empty());
+        assert!(fail_cmrs.iter().all(|cmr| *cmr == expected));
+    }
+
+    #[test]
+    fn jet_sets() {
+        use simplicity::jet::{Core, Elements};
+
+        let prog_text = r#"fn main() {
+    let (_, sum): (bool, u32) = jet::add_32(2, 3);
+    assert!(jet::eq_32(sum, 5));
+}
+"#;
+        let core = TemplateProgram::<Core>::new_with_jets(prog_text)
+            .and_then(|template| template.instantiate(Arguments::default(), false))
+            .unwrap();
+        let elements = TemplateProgram::<Elements>::new_with_jets(prog_text)
+            .and_then(|template| template.instantiate(Arguments::default(), false))
+            .unwrap();
+        core.satisfy(WitnessValues::default()).unwrap();
+        elements.satisfy(WitnessValues::default()).unwrap();
+    }
+
+    #[test]
+    fn unsupported_jet() {
+        use simplicity::jet::{Core, Elements};
+
+        let prog_text = "fn main() { let n: u32 = jet::num_inputs(); }";
+        TemplateProgram::<Elements>::new_with_jets(prog_text).unwrap();
+        match TemplateProgram::<Core>::new_with_jets(prog_text) {
+            Ok(_) => panic!("Elements jet was falsely accepted by Core"),
+            Err(error) => assert!(error
+                .to_string()
+                .contains("Jet `num_inputs` is not supported")),
+        }
+
+        let prog_text = "fn main() { let op: u8 = jet::get_opcode_from_script(0); }";
+        TemplateProgram::new(prog_text).unwrap();
+        match TemplateProgram::<Elements>::new_with_jets(prog_text) {
+            Ok(_) => panic!("Script extension jet was falsely accepted by Elements"),
+            Err(error) => assert!(error
+                .to_string()
+                .contains("Jet `get_opcode_from_script` is not supported")),
+        }
+    }
+
     #[test]
     fn redefined_variable() {
         let prog_text = r#"fn main() {
@@ -623,13 +1451,218 @@ fn main() {
             Ok(_) => panic!("Accepted faulty program"),
             Err(error) => {
                 assert!(
-                    error.contains("Expected expression of type `bool`, found type `()`"),
+                    error
+                        .to_string()
+                        .contains("Expected expression of type `bool`, found type `()`"),
                     "Unexpected error: {error}",
                 );
             }
         }
     }
 
+    #[test]
+    fn early_return() {
+        let prog_text = r#"fn checked_div(x: u32, y: u32) -> Option<u32> {
+    match jet::is_zero_32(y) {
+        false => {},
+        true => return None,
+    };
+    let (q, _r): (u32, u32) = jet::div_mod_32(x, y);
+    Some(q)
+}
+
+fn main() {
+    assert!(is_none::<u32>(checked_div(6, 0)));
+    assert!(jet::eq_32(unwrap(checked_div(6, 3)), 2));
+
+    let skip: bool = true;
+    match skip {
+        false => {},
+        true => return,
+    };
+    panic!();
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn try_operator() {
+        let prog_text = r#"fn add_three(a: Option<u32>, b: Option<u32>, c: Option<u32>) -> Option<u32> {
+    let (_, ab): (bool, u32) = jet::add_32(a?, b?);
+    let (_, abc): (bool, u32) = jet::add_32(ab, c?);
+    Some(abc)
+}
+
+fn increment(x: Either<u8, u32>) -> Either<u8, u32> {
+    let (_, y): (bool, u32) = jet::increment_32(x?);
+    Right(y)
+}
+
+fn main() {
+    assert!(jet::eq_32(unwrap(add_three(Some(1), Some(2), Some(3))), 6));
+    assert!(is_none::<u32>(add_three(None, Some(2), Some(3))));
+    assert!(is_none::<u32>(add_three(Some(1), None, Some(3))));
+    assert!(is_none::<u32>(add_three(Some(1), Some(2), None)));
+
+    assert!(jet::eq_32(unwrap_right::<u8>(increment(Right(41))), 42));
+    assert!(jet::eq_8(unwrap_left::<u32>(increment(Left(7))), 7));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn try_unsupported_return_type() {
+        let prog_text = r#"fn unwrap_or_fail(x: Option<u8>) -> u8 {
+    x?
+}
+
+fn main() {
+    assert!(jet::eq_8(unwrap_or_fail(Some(1)), 1));
+}"#;
+        match SatisfiedProgram::new(
+            prog_text,
+            Arguments::default(),
+            WitnessValues::default(),
+            false,
+        ) {
+            Ok(_) => panic!("Accepted faulty program"),
+            Err(error) => {
+                assert!(
+                    error.to_string().contains(
+                        "can only be used in a function that returns `Option` or `Either`"
+                    ),
+                    "Unexpected error: {error}",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn const_items() {
+        let prog_text = r#"const FEE: u64 = 1000;
+const DOUBLE_FEE: u64 = {
+    let (_, fee): (bool, u64) = jet::add_64(FEE, FEE);
+    fee
+};
+const PAIR: (u8, Option<u8>) = (1, Some(2));
+const TAG_HASH: u256 = sha256::<8>(b"BIP0340/");
+
+fn main() {
+    assert!(jet::eq_64(DOUBLE_FEE, 2000));
+    let (one, two): (u8, Option<u8>) = PAIR;
+    assert!(jet::eq_8(one, 1));
+    assert!(jet::eq_8(unwrap(two), 2));
+    assert!(jet::eq_256(TAG_HASH, sha256::<8>(b"BIP0340/")));
+
+    let FEE: u64 = 5;
+    assert!(jet::eq_64(FEE, 5));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn const_fn() {
+        let prog_text = r#"const fn tagged_hash(tag: [u8; 8], msg: u256) -> u256 {
+    let tag_hash: u256 = sha256::<8>(tag);
+    let ctx: Ctx8 = jet::sha_256_ctx_8_init();
+    let ctx: Ctx8 = jet::sha_256_ctx_8_add_32(ctx, tag_hash);
+    let ctx: Ctx8 = jet::sha_256_ctx_8_add_32(ctx, tag_hash);
+    let ctx: Ctx8 = jet::sha_256_ctx_8_add_32(ctx, msg);
+    jet::sha_256_ctx_8_finalize(ctx)
+}
+
+const COMMITMENT: u256 = tagged_hash(b"Commitme", 1);
+
+fn main() {
+    let msg: u256 = 1;
+    assert!(jet::eq_256(COMMITMENT, tagged_hash(b"Commitme", msg)));
+    assert!(jet::eq_256(tagged_hash(b"Commitme", 1), tagged_hash(b"Commitme", msg)));
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn const_errors() {
+        let programs = [
+            (
+                "const PK: Pubkey = witness::PK;\nfn main() {}",
+                "Expression cannot be evaluated at compile time",
+            ),
+            (
+                "const fn inputs() -> u32 { jet::num_inputs() }\nfn main() {}",
+                "Expression cannot be evaluated at compile time",
+            ),
+            (
+                "fn one() -> u8 { 1 }\nconst ONE: u8 = one();\nfn main() {}",
+                "Function `one` cannot be called at compile time",
+            ),
+            (
+                "const ONE: u8 = 1;\nconst ONE: u8 = 2;\nfn main() {}",
+                "Constant `ONE` was defined multiple times",
+            ),
+            (
+                "const fn fail(x: u8) -> u8 { panic!() }\nfn main() { let x: u8 = fail(1); }",
+                "Evaluation at compile time failed",
+            ),
+        ];
+        for (prog_text, expected) in programs {
+            match CompiledProgram::new(prog_text, Arguments::default(), false) {
+                Ok(_) => panic!("Accepted faulty program:\n{prog_text}"),
+                Err(error) => assert!(
+                    error.to_string().contains(expected),
+                    "Unexpected error: {error}"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn assert_message() {
+        let prog_text = r#"fn main() {
+    assert!(jet::eq_8(1, 1), "one is one");
+    let ok: bool = true;
+    match ok {
+        true => {},
+        false => panic!("unreachable \"path\"\n"),
+    };
+}"#;
+        TestCase::program_text(Cow::Borrowed(prog_text))
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+
+        // Messages are debug symbols and don't change the CMR
+        let without_messages = prog_text
+            .replace(r#", "one is one""#, "")
+            .replace(r#""unreachable \"path\"\n""#, "");
+        for include_debug_symbols in [false, true] {
+            let with = CompiledProgram::new(prog_text, Arguments::default(), include_debug_symbols)
+                .unwrap();
+            let without = CompiledProgram::new(
+                without_messages.as_str(),
+                Arguments::default(),
+                include_debug_symbols,
+            )
+            .unwrap();
+            assert_eq!(with.commit().cmr(), without.commit().cmr());
+        }
+
+        let prog_text = r#"fn main() {
+    let x: u8 = unwrap(Some(1), "x is missing");
+}"#;
+        match CompiledProgram::new(prog_text, Arguments::default(), false) {
+            Ok(_) => panic!("Accepted faulty program"),
+            Err(error) => assert!(error.to_string().contains("does not take a message")),
+        }
+    }
+
     #[test]
     fn fuzz_regression_2() {
         parse::Program::parse_from_str("fn dbggscas(h: bool, asyxhaaaa: a) {\nfalse}\n\n").unwrap();
@@ -0,0 +1,722 @@
+//! Lints that warn about suspicious but well-formed programs.
+//!
+//! Lints run after the program has been analyzed successfully.
+//! Each lint has a [`Level`] that can be changed on the command line or in the program itself:
+//! `#![allow(...)]` at the start of the file applies to the entire program,
+//! and `#[allow(...)]` in front of a function applies to that function.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use either::Either;
+use miniscript::iter::TreeLike;
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::Span;
+use crate::parse::{
+    Attribute, CallName, ExprTree, Expression, ExpressionInner, Function, Item, Match,
+    MatchPattern, Program, SingleExpression, SingleExpressionInner, Statement,
+};
+use crate::str::{FunctionName, Identifier, WitnessName};
+use crate::WitnessValues;
+
+/// Name that refers to all lints at once, such as in `#![deny(warnings)]`.
+const ALL_LINTS: &str = "warnings";
+
+/// A lint.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum Lint {
+    /// A variable is bound but never used.
+    UnusedVariables,
+    /// A function is never called from `main`.
+    UnusedFunctions,
+    /// A witness value is assigned but never used by the program.
+    UnusedWitnesses,
+    /// A variable is bound with the same name as a variable that is in scope.
+    ShadowedBindings,
+    /// A match expression matches a constant, so one of its arms is never taken.
+    UnreachablePatterns,
+    /// A `dbg!` call is left in the program.
+    DbgMacro,
+    /// An attribute refers to a lint that does not exist.
+    UnknownLints,
+}
+
+impl Lint {
+    /// List of all lints.
+    pub const ALL: [Self; 7] = [
+        Self::UnusedVariables,
+        Self::UnusedFunctions,
+        Self::UnusedWitnesses,
+        Self::ShadowedBindings,
+        Self::UnreachablePatterns,
+        Self::DbgMacro,
+        Self::UnknownLints,
+    ];
+
+    /// Access the name of the lint, as used in attributes and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::UnusedVariables => "unused_variables",
+            Self::UnusedFunctions => "unused_functions",
+            Self::UnusedWitnesses => "unused_witnesses",
+            Self::ShadowedBindings => "shadowed_bindings",
+            Self::UnreachablePatterns => "unreachable_patterns",
+            Self::DbgMacro => "dbg_macro",
+            Self::UnknownLints => "unknown_lints",
+        }
+    }
+
+    /// Access the level of the lint if it is not configured otherwise.
+    pub fn default_level(self) -> Level {
+        match self {
+            // Rebinding a variable such as `ctx` or `hasher` is idiomatic,
+            // so shadowing must be enabled explicitly.
+            Self::ShadowedBindings => Level::Allow,
+            _ => Level::Warn,
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Lint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|lint| lint.name() == s)
+            .ok_or_else(|| format!("Unknown lint `{s}`"))
+    }
+}
+
+/// Level of a lint.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Level {
+    /// The lint is not checked.
+    Allow,
+    /// The lint produces a warning.
+    Warn,
+    /// The lint produces an error.
+    Deny,
+}
+
+impl Level {
+    /// Get the severity of the diagnostics that a lint of this level produces.
+    ///
+    /// Allowed lints produce no diagnostics.
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            Self::Allow => None,
+            Self::Warn => Some(Severity::Warning),
+            Self::Deny => Some(Severity::Error),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => f.write_str("allow"),
+            Self::Warn => f.write_str("warn"),
+            Self::Deny => f.write_str("deny"),
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "deny" => Ok(Self::Deny),
+            _ => Err(format!("Unknown lint level `{s}`")),
+        }
+    }
+}
+
+/// Levels of all lints.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LintConfig {
+    levels: HashMap<Lint, Level>,
+}
+
+impl LintConfig {
+    /// Get the level of the given `lint`.
+    pub fn level(&self, lint: Lint) -> Level {
+        self.levels
+            .get(&lint)
+            .copied()
+            .unwrap_or_else(|| lint.default_level())
+    }
+
+    /// Set the level of the given `lint`.
+    pub fn set(&mut self, lint: Lint, level: Level) {
+        self.levels.insert(lint, level);
+    }
+
+    /// Set the level of the lint with the given `name`.
+    ///
+    /// The name `warnings` sets the level of all lints that currently produce warnings.
+    /// Allowed lints stay allowed, so `deny(warnings)` doesn't enable lints such as
+    /// `shadowed_bindings`.
+    ///
+    /// ## Errors
+    ///
+    /// There is no lint with the given name.
+    pub fn set_by_name(&mut self, name: &str, level: Level) -> Result<(), String> {
+        if name == ALL_LINTS {
+            for lint in Lint::ALL {
+                if self.level(lint) == Level::Warn {
+                    self.set(lint, level);
+                }
+            }
+            return Ok(());
+        }
+        let lint = Lint::from_str(name)?;
+        self.set(lint, level);
+        Ok(())
+    }
+
+    /// Apply the given `attributes` on top of the current levels.
+    ///
+    /// Return the names of unknown lints with the span of their attribute.
+    fn apply(&mut self, attributes: &[Attribute]) -> Vec<(String, Span)> {
+        let mut unknown = Vec::new();
+        for attribute in attributes {
+            for name in attribute.lints() {
+                if self
+                    .set_by_name(name.as_inner(), attribute.level())
+                    .is_err()
+                {
+                    unknown.push((name.to_string(), *attribute.span()));
+                }
+            }
+        }
+        unknown
+    }
+}
+
+/// Run the lints over the given `program`.
+///
+/// The program should have been analyzed successfully.
+/// Lints don't check that the program is well-formed.
+pub fn lint_program(program: &Program, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut linter = Linter::new(config.clone());
+    let unknown = linter.config.apply(program.attributes());
+    linter.unknown_lints(unknown);
+
+    let reachable = reachable_functions(program);
+    for item in program.items() {
+        if let Item::Function(function) = item {
+            linter.function(function, reachable.contains(function.name()));
+        }
+    }
+
+    linter.diagnostics
+}
+
+/// Run the lints that concern the given witness `values` of the given `program`.
+pub fn lint_witness_values(
+    program: &Program,
+    values: &WitnessValues,
+    config: &LintConfig,
+) -> Vec<Diagnostic> {
+    let mut linter = Linter::new(config.clone());
+    // Unknown lints are reported by `lint_program`.
+    let _unknown = linter.config.apply(program.attributes());
+
+    let used: HashSet<&WitnessName> = program
+        .items()
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(function) => Some(function),
+            _ => None,
+        })
+        .flat_map(|function| ExprTree::Expression(function.body()).pre_order_iter())
+        .filter_map(|node| match node {
+            ExprTree::Single(single) => match single.inner() {
+                SingleExpressionInner::Witness(name) => Some(name),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    let mut unused: Vec<&WitnessName> = values
+        .iter()
+        .map(|(name, _)| name)
+        .filter(|name| !used.contains(name))
+        .collect();
+    unused.sort_unstable();
+    for name in unused {
+        linter.emit(
+            Lint::UnusedWitnesses,
+            Diagnostic::warning(format!("witness `{name}` is never used by the program"))
+                .with_note("Remove the value from the witness file."),
+        );
+    }
+
+    linter.diagnostics
+}
+
+/// Return the functions that are called inside the given `expression`.
+fn called_functions(expression: &Expression) -> Vec<&FunctionName> {
+    ExprTree::Expression(expression)
+        .pre_order_iter()
+        .filter_map(|node| match node {
+            ExprTree::Call(call) => match call.name() {
+                CallName::Custom(name)
+                | CallName::Fold(name, _)
+                | CallName::ArrayFold(name, _)
+                | CallName::ArrayMap(name, _)
+                | CallName::ForWhile(name)
+                | CallName::Disconnect(name, _) => Some(name),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Compute the set of functions that are called from `main` or from constants,
+/// directly or indirectly.
+fn reachable_functions(program: &Program) -> HashSet<FunctionName> {
+    let callees: HashMap<&FunctionName, Vec<&FunctionName>> = program
+        .items()
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(function) => Some(function),
+            _ => None,
+        })
+        .map(|function| (function.name(), called_functions(function.body())))
+        .collect();
+
+    let mut reachable = HashSet::new();
+    let mut stack = vec![FunctionName::main()];
+    for item in program.items() {
+        if let Item::Const(constant) = item {
+            stack.extend(called_functions(constant.expression()).into_iter().cloned());
+        }
+    }
+    while let Some(name) = stack.pop() {
+        if let Some(callees) = callees.get(&name) {
+            stack.extend(
+                callees
+                    .iter()
+                    .filter(|callee| !reachable.contains(**callee))
+                    .map(|callee| (*callee).clone()),
+            );
+        }
+        reachable.insert(name);
+    }
+    reachable
+}
+
+/// Variable that is bound inside a scope.
+struct Binding {
+    identifier: Identifier,
+    span: Span,
+    kind: BindingKind,
+    used: bool,
+}
+
+/// Construct that binds a variable.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum BindingKind {
+    /// Pattern of a `let` statement.
+    Let,
+    /// Parameter of a function.
+    Parameter,
+    /// Pattern of a match arm.
+    MatchArm,
+}
+
+impl BindingKind {
+    /// Explain how to silence a warning about an unused variable of this kind.
+    fn unused_note(self) -> &'static str {
+        match self {
+            Self::Let => "Use the pattern `_` to ignore a value.",
+            Self::Parameter | Self::MatchArm => {
+                "Prefix the name with an underscore, such as `_x`, if this is intentional."
+            }
+        }
+    }
+}
+
+/// State of the lint pass.
+struct Linter {
+    config: LintConfig,
+    scopes: Vec<Vec<Binding>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter {
+    fn new(config: LintConfig) -> Self {
+        Self {
+            config,
+            scopes: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Emit the `diagnostic` of the given `lint` at the currently configured level.
+    fn emit(&mut self, lint: Lint, diagnostic: Diagnostic) {
+        if let Some(severity) = self.config.level(lint).severity() {
+            self.diagnostics.push(Diagnostic {
+                severity,
+                ..diagnostic.with_code(lint.name())
+            });
+        }
+    }
+
+    fn unknown_lints(&mut self, unknown: Vec<(String, Span)>) {
+        for (name, span) in unknown {
+            self.emit(
+                Lint::UnknownLints,
+                Diagnostic::warning(format!("unknown lint: `{name}`")).with_span(span),
+            );
+        }
+    }
+
+    fn function(&mut self, function: &Function, reachable: bool) {
+        let outer = self.config.clone();
+        let unknown = self.config.apply(function.attributes());
+        self.unknown_lints(unknown);
+
+        if !reachable {
+            self.emit(
+                Lint::UnusedFunctions,
+                Diagnostic::warning(format!("function `{}` is never used", function.name()))
+                    .with_span(*function.span())
+                    .with_note("Functions that are not called from `main` are not compiled."),
+            );
+        }
+
+        self.scopes.push(Vec::new());
+        for param in function.params() {
+            self.bind(param.identifier(), *param.span(), BindingKind::Parameter);
+        }
+        self.expression(function.body());
+        self.pop_scope();
+
+        self.config = outer;
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match expression.inner() {
+            ExpressionInner::Single(single) => self.single_expression(single),
+            ExpressionInner::Block(statements, maybe_expr) => {
+                self.scopes.push(Vec::new());
+                for statement in statements.iter() {
+                    match statement {
+                        Statement::Assignment(assignment) => {
+                            self.expression(assignment.expression());
+                            for (identifier, span) in assignment.bindings() {
+                                self.bind(identifier, *span, BindingKind::Let);
+                            }
+                        }
+                        Statement::Expression(expression) => self.expression(expression),
+                    }
+                }
+                if let Some(expression) = maybe_expr {
+                    self.expression(expression);
+                }
+                self.pop_scope();
+            }
+        }
+    }
+
+    fn single_expression(&mut self, single: &SingleExpression) {
+        use SingleExpressionInner as S;
+
+        match single.inner() {
+            S::Boolean(_)
+            | S::Decimal(_)
+            | S::Binary(_)
+            | S::Hexadecimal(_)
+            | S::Witness(_)
+            | S::Parameter(_)
+            | S::Option(None)
+            | S::Return(None) => {}
+            S::Option(Some(inner))
+            | S::Either(Either::Left(inner))
+            | S::Either(Either::Right(inner))
+            | S::Expression(inner)
+            | S::Return(Some(inner))
+            | S::Try(inner) => self.expression(inner),
+            S::Variable(identifier) => self.use_variable(identifier),
+            S::Call(call) => {
+                if let CallName::Debug = call.name() {
+                    self.emit(
+                        Lint::DbgMacro,
+                        Diagnostic::warning("`dbg!` call left in the program")
+                            .with_span(*call.span())
+                            .with_note("Remove the call before deploying the program."),
+                    );
+                }
+                for arg in call.args() {
+                    self.expression(arg);
+                }
+            }
+            S::Match(match_) => self.match_expression(match_),
+            S::Tuple(elements) | S::Array(elements) | S::List(elements) => {
+                for element in elements.iter() {
+                    self.expression(element);
+                }
+            }
+            S::Index(array, index) => {
+                self.expression(array);
+                self.expression(index);
+            }
+            S::Slice(array, ..) => self.expression(array),
+        }
+    }
+
+    fn match_expression(&mut self, match_: &Match) {
+        self.expression(match_.scrutinee());
+
+        if let Some(taken) = constant_arm(match_) {
+            self.emit(
+                Lint::UnreachablePatterns,
+                Diagnostic::warning(format!("match expression always takes the `{taken}` arm"))
+                    .with_span(*match_.span())
+                    .with_note(
+                        "The matched expression is a constant, so the other arm is unreachable.",
+                    ),
+            );
+        }
+
+        for arm in [match_.left(), match_.right()] {
+            self.scopes.push(Vec::new());
+            if let (Some(identifier), Some(span)) =
+                (arm.pattern().as_variable(), arm.variable_span())
+            {
+                self.bind(identifier, *span, BindingKind::MatchArm);
+            }
+            self.expression(arm.expression());
+            self.pop_scope();
+        }
+    }
+
+    fn bind(&mut self, identifier: &Identifier, span: Span, kind: BindingKind) {
+        let previous = self
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|binding| &binding.identifier == identifier)
+            .map(|binding| binding.span);
+        if let Some(previous) = previous {
+            self.emit(
+                Lint::ShadowedBindings,
+                Diagnostic::warning(format!("`{identifier}` shadows an earlier binding"))
+                    .with_span(span)
+                    .with_label(previous, format!("previous binding of `{identifier}`")),
+            );
+        }
+
+        self.scopes
+            .last_mut()
+            .expect("bindings happen inside a scope")
+            .push(Binding {
+                identifier: identifier.clone(),
+                span,
+                kind,
+                used: false,
+            });
+    }
+
+    fn use_variable(&mut self, identifier: &Identifier) {
+        let binding = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|binding| &binding.identifier == identifier);
+        if let Some(binding) = binding {
+            binding.used = true;
+        }
+    }
+
+    fn pop_scope(&mut self) {
+        let scope = self.scopes.pop().expect("scopes are balanced");
+        // Names that start with an underscore are intentionally unused
+        let unused = scope
+            .into_iter()
+            .filter(|binding| !binding.used && !binding.identifier.as_inner().starts_with('_'));
+        for binding in unused {
+            self.emit(
+                Lint::UnusedVariables,
+                Diagnostic::warning(format!("unused variable: `{}`", binding.identifier))
+                    .with_span(binding.span)
+                    .with_note(binding.kind.unused_note()),
+            );
+        }
+    }
+}
+
+/// Return the pattern of the arm that is always taken
+/// if the scrutinee of the given match expression is a constant.
+fn constant_arm(match_: &Match) -> Option<&MatchPattern> {
+    use SingleExpressionInner as S;
+
+    let mut scrutinee = match_.scrutinee();
+    let taken = loop {
+        let ExpressionInner::Single(single) = scrutinee.inner() else {
+            return None;
+        };
+        match single.inner() {
+            S::Expression(inner) => scrutinee = inner,
+            S::Boolean(false) | S::Either(Either::Left(_)) | S::Option(None) => {
+                break match_.left()
+            }
+            S::Boolean(true) | S::Either(Either::Right(_)) | S::Option(Some(_)) => {
+                break match_.right()
+            }
+            _ => return None,
+        }
+    };
+    Some(taken.pattern())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Position;
+    use crate::parse::ParseFromStr;
+
+    fn lint(program: &str) -> Vec<Diagnostic> {
+        let program = Program::parse_from_str(program).expect("program should parse");
+        crate::ast::Program::analyze(&program).expect("program should be well-formed");
+        lint_program(&program, &LintConfig::default())
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<&'static str> {
+        diagnostics.iter().filter_map(|d| d.code).collect()
+    }
+
+    #[test]
+    fn unused_variable() {
+        let diagnostics =
+            lint("fn main() { let a: u8 = 1; let b: u8 = 2; assert!(jet::eq_8(b, 2)); }");
+        assert_eq!(codes(&diagnostics), ["unused_variables"]);
+        assert!(diagnostics[0].message.contains("`a`"));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].span,
+            Some(Span::new(Position::new(1, 17), Position::new(1, 18)))
+        );
+    }
+
+    #[test]
+    fn unused_variable_spans() {
+        let program = "fn f(x: u8, y: u8) -> u8 { y }
+fn main() {
+    let (a, _b): (u8, u8) = (1, 2);
+    let c: u8 = match witness::A { Some(z: u8) => f(a, z), None => 0, };
+    assert!(jet::eq_8(f(c, 0), 0));
+}";
+        let diagnostics = lint(program);
+        assert_eq!(codes(&diagnostics), ["unused_variables"]);
+        assert!(diagnostics[0].message.contains("`x`"));
+        assert_eq!(
+            diagnostics[0].span,
+            Some(Span::new(Position::new(1, 6), Position::new(1, 7)))
+        );
+
+        let diagnostics =
+            lint("fn f(_x: u8) {} fn main() { let _a: u8 = 1; f(2); match witness::A { Some(_y: u8) => {}, None => {}, } }");
+        assert!(codes(&diagnostics).is_empty());
+    }
+
+    #[test]
+    fn unused_function() {
+        let diagnostics =
+            lint("fn helper() {} fn also_unused() { helper() } fn used() {} fn main() { used() }");
+        assert_eq!(
+            codes(&diagnostics),
+            ["unused_functions", "unused_functions"]
+        );
+    }
+
+    #[test]
+    fn dbg_and_constant_match() {
+        let diagnostics = lint(
+            "fn main() { let x: u8 = match true { false => 0, true => 1, }; assert!(jet::eq_8(dbg!(x), 1)); }",
+        );
+        assert_eq!(codes(&diagnostics), ["unreachable_patterns", "dbg_macro"]);
+    }
+
+    #[test]
+    fn attributes_set_levels() {
+        let program = "#![deny(dbg_macro)]
+#[allow(unused_variables)]
+fn f() { let a: u8 = 1; }
+fn main() { f(); let b: u8 = dbg!(2); }";
+        let diagnostics = lint(program);
+        assert_eq!(codes(&diagnostics), ["dbg_macro", "unused_variables"]);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn unknown_lint() {
+        let diagnostics = lint("#[allow(no_such_lint)]\nfn main() {}");
+        assert_eq!(codes(&diagnostics), ["unknown_lints"]);
+    }
+
+    #[test]
+    fn shadowing_is_opt_in() {
+        let program = "fn main() { let a: u8 = 1; let a: u8 = a; assert!(jet::eq_8(a, 1)); }";
+        assert!(lint(program).is_empty());
+
+        let program = Program::parse_from_str(program).unwrap();
+        let mut config = LintConfig::default();
+        config
+            .set_by_name("shadowed_bindings", Level::Warn)
+            .unwrap();
+        let diagnostics = lint_program(&program, &config);
+        assert_eq!(codes(&diagnostics), ["shadowed_bindings"]);
+        assert_eq!(diagnostics[0].labels.len(), 1);
+    }
+
+    #[test]
+    fn deny_warnings() {
+        let program = Program::parse_from_str("fn main() { let a: u8 = 1; }").unwrap();
+        let mut config = LintConfig::default();
+        config.set_by_name("warnings", Level::Deny).unwrap();
+        let diagnostics = lint_program(&program, &config);
+        assert!(diagnostics.iter().all(Diagnostic::is_error));
+        assert_eq!(config.level(Lint::ShadowedBindings), Level::Allow);
+        assert!(config.set_by_name("no_such_lint", Level::Deny).is_err());
+
+        let diagnostics = lint(
+            "#![deny(warnings)]\nfn main() { let a: u8 = 1; let a: u8 = a; assert!(jet::eq_8(a, 1)); }",
+        );
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn unused_witness() {
+        let program = Program::parse_from_str(
+            "fn main() { let a: u8 = witness::A; assert!(jet::eq_8(a, 1)); }",
+        )
+        .unwrap();
+        let values =
+            WitnessValues::parse_from_str("mod witness { const A: u8 = 1; const B: u8 = 2; }")
+                .unwrap();
+        let diagnostics = lint_witness_values(&program, &values, &LintConfig::default());
+        assert_eq!(codes(&diagnostics), ["unused_witnesses"]);
+        assert!(diagnostics[0].message.contains("`B`"));
+    }
+}
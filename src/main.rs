@@ -2,12 +2,32 @@ use base64::display::Base64Display;
 use base64::engine::general_purpose::STANDARD;
 use clap::{Arg, ArgAction, Command};
 
+#[cfg(feature = "serde")]
+use simplicity_unchained::jets::unchained::ElementsExtension;
+use simplicityhl::coverage::{Coverage, CoverageFormat};
+#[cfg(feature = "serde")]
+use simplicityhl::debug::DebugSymbols;
+#[cfg(feature = "serde")]
+use simplicityhl::decompile;
+use simplicityhl::diagnostic::{self, Diagnostic};
+use simplicityhl::dummy_env;
+use simplicityhl::elements::hex::FromHex;
+use simplicityhl::elements::{LockTime, Script, Sequence};
+use simplicityhl::lint::{Level, LintConfig};
+#[cfg(feature = "serde")]
+use simplicityhl::simplicity::bit_machine::{ExecTracker, FrameIter, NodeOutput, PruneTracker};
+#[cfg(feature = "serde")]
+use simplicityhl::simplicity::{Ihr, RedeemNode};
+use simplicityhl::tracker::{DefaultTracker, Profile, ProfileWeight, ProfilingTracker};
 use simplicityhl::{Arguments, CompiledProgram};
+use std::sync::Arc;
 use std::{env, fmt};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// The compilation output.
 struct Output {
+    /// Version of the compiler that produced the output.
+    compiler_version: &'static str,
     /// Simplicity program result, base64 encoded.
     program: String,
     /// Simplicity witness result, base64 encoded, if the .wit file was provided.
@@ -36,7 +56,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             )
             .arg(
                 Arg::new("prog_file")
-                    .required(true)
+                    .required_unless_present("explain")
                     .value_name("PROGRAM_FILE")
                     .action(ArgAction::Set)
                     .help("SimplicityHL program file to build"),
@@ -53,23 +73,294 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     .action(ArgAction::SetTrue)
                     .help("Include debug symbols in the output"),
             )
+            .arg(
+                Arg::new("emit_debug_symbols")
+                    .long("emit-debug-symbols")
+                    .value_name("PATH")
+                    .requires("debug")
+                    .action(ArgAction::Set)
+                    .help("Write the debug symbols to the given sidecar file, such as PROGRAM.simdbg (requires feature 'serde')"),
+            )
             .arg(
                 Arg::new("json")
                     .long("json")
                     .action(ArgAction::SetTrue)
                     .help("Output in JSON"),
             )
+            .arg(
+                Arg::new("error_format")
+                    .long("error-format")
+                    .value_name("FORMAT")
+                    .value_parser(["human", "json", "sarif"])
+                    .default_value("human")
+                    .action(ArgAction::Set)
+                    .help("Format of compiler diagnostics (printed to stdout if compilation fails and to stderr otherwise)"),
+            )
+            .arg(
+                Arg::new("script")
+                    .long("script")
+                    .value_name("HEX")
+                    .requires("wit_file")
+                    .action(ArgAction::Set)
+                    .help("Run the program in a test environment with the given companion Bitcoin script and prune it"),
+            )
+            .arg(
+                Arg::new("explain")
+                    .long("explain")
+                    .value_name("CODE")
+                    .action(ArgAction::Set)
+                    .help("Print the explanation of the given error code, such as E0012"),
+            )
+            .arg(
+                Arg::new("warn")
+                    .short('W')
+                    .long("warn")
+                    .value_name("LINT")
+                    .action(ArgAction::Append)
+                    .help("Set the lint to warn"),
+            )
+            .arg(
+                Arg::new("deny")
+                    .short('D')
+                    .long("deny")
+                    .value_name("LINT")
+                    .action(ArgAction::Append)
+                    .help("Set the lint to deny (use `warnings` for all lints)"),
+            )
+            .arg(
+                Arg::new("allow")
+                    .short('A')
+                    .long("allow")
+                    .value_name("LINT")
+                    .action(ArgAction::Append)
+                    .help("Set the lint to allow"),
+            )
+            .subcommand_negates_reqs(true)
+            .args_conflicts_with_subcommands(true)
+            .subcommand(
+                Command::new("test")
+                    .about(
+                        "\
+                        Run the given SimplicityHL program once for each witness file.\n\
+                        If no witness files are given, then use the files PROGRAM.wit and \
+                        PROGRAM.*.wit next to the program file. \
+                        If no arguments file is given, then use the file PROGRAM.args \
+                        next to the program file, if it exists.\
+                        ",
+                    )
+                    .arg(
+                        Arg::new("prog_file")
+                            .required(true)
+                            .value_name("PROGRAM_FILE")
+                            .action(ArgAction::Set)
+                            .help("SimplicityHL program file to test"),
+                    )
+                    .arg(
+                        Arg::new("wit_files")
+                            .value_name("WITNESS_FILE")
+                            .action(ArgAction::Append)
+                            .help("Files containing the witness data of each test case"),
+                    )
+                    .arg(
+                        Arg::new("args")
+                            .long("args")
+                            .value_name("ARGUMENTS_FILE")
+                            .action(ArgAction::Set)
+                            .help("File containing the arguments of the program [default: PROGRAM.args if it exists]"),
+                    )
+                    .arg(
+                        Arg::new("script")
+                            .long("script")
+                            .value_name("HEX")
+                            .action(ArgAction::Set)
+                            .help("Run the program in a test environment with the given companion Bitcoin script"),
+                    )
+                    .arg(
+                        Arg::new("coverage")
+                            .long("coverage")
+                            .value_name("FORMAT")
+                            .value_parser(["lcov", "cobertura"])
+                            .num_args(0..=1)
+                            .default_missing_value("lcov")
+                            .action(ArgAction::Set)
+                            .help("Write a coverage report of the match arms and functions that the test cases execute"),
+                    )
+                    .arg(
+                        Arg::new("coverage_output")
+                            .long("coverage-output")
+                            .value_name("FILE")
+                            .requires("coverage")
+                            .action(ArgAction::Set)
+                            .help("File of the coverage report [default: lcov.info or coverage.xml]"),
+                    )
+                    .arg(
+                        Arg::new("profile")
+                            .long("profile")
+                            .value_name("FILE")
+                            .action(ArgAction::Set)
+                            .help("Write an execution profile of the test cases as flamegraph folded stacks"),
+                    )
+                    .arg(
+                        Arg::new("profile_weight")
+                            .long("profile-weight")
+                            .value_name("WEIGHT")
+                            .value_parser(["nodes", "jet-cost"])
+                            .default_value("jet-cost")
+                            .requires("profile")
+                            .action(ArgAction::Set)
+                            .help("Resource that the folded stacks of the profile measure"),
+                    ),
+            )
+            .subcommand(
+                Command::new("run")
+                    .about(
+                        "\
+                        Run the given encoded Simplicity program without its SimplicityHL source.\n\
+                        Debug symbols are loaded from the sidecar file that `simc --emit-debug-symbols` writes, \
+                        so failures name the failed call and `dbg!` output is printed.\
+                        ",
+                    )
+                    .arg(
+                        Arg::new("prog_file")
+                            .required(true)
+                            .value_name("PROGRAM_FILE")
+                            .action(ArgAction::Set)
+                            .help("File containing the base64 encoded Simplicity program"),
+                    )
+                    .arg(
+                        Arg::new("witness")
+                            .long("witness")
+                            .value_name("WITNESS_FILE")
+                            .action(ArgAction::Set)
+                            .help("File containing the base64 encoded witness data [default: no witness data]"),
+                    )
+                    .arg(
+                        Arg::new("debug_symbols")
+                            .long("debug-symbols")
+                            .value_name("FILE")
+                            .action(ArgAction::Set)
+                            .help("Sidecar file with the debug symbols [default: PROGRAM.simdbg if it exists]"),
+                    )
+                    .arg(
+                        Arg::new("script")
+                            .long("script")
+                            .value_name("HEX")
+                            .action(ArgAction::Set)
+                            .help("Run the program in a test environment with the given companion Bitcoin script"),
+                    ),
+            )
+            .subcommand(
+                Command::new("decode")
+                    .about(
+                        "\
+                        Decode the given encoded Simplicity program and print it as a tree of combinators.\n\
+                        Each node is printed with its type. Shared subexpressions are named by their CMR. \
+                        Debug symbols or the SimplicityHL source annotate the nodes with source text.\
+                        ",
+                    )
+                    .arg(
+                        Arg::new("prog_file")
+                            .required(true)
+                            .value_name("PROGRAM_FILE")
+                            .action(ArgAction::Set)
+                            .help("File containing the base64 encoded Simplicity program"),
+                    )
+                    .arg(
+                        Arg::new("witness")
+                            .long("witness")
+                            .value_name("WITNESS_FILE")
+                            .action(ArgAction::Set)
+                            .help("File containing the base64 encoded witness data"),
+                    )
+                    .arg(
+                        Arg::new("debug_symbols")
+                            .long("debug-symbols")
+                            .value_name("FILE")
+                            .conflicts_with("source")
+                            .action(ArgAction::Set)
+                            .help("Sidecar file with the debug symbols [default: PROGRAM.simdbg if it exists]"),
+                    )
+                    .arg(
+                        Arg::new("source")
+                            .long("source")
+                            .value_name("SOURCE_FILE")
+                            .action(ArgAction::Set)
+                            .help("SimplicityHL source file of the program"),
+                    ),
+            )
+            .subcommand(
+                Command::new("verify")
+                    .about(
+                        "\
+                        Verify that the given SimplicityHL program compiles to the given CMR.\n\
+                        The compiler output is deterministic, so the same program, arguments \
+                        and compiler version always produce the same CMR.\
+                        ",
+                    )
+                    .arg(
+                        Arg::new("prog_file")
+                            .required(true)
+                            .value_name("PROGRAM_FILE")
+                            .action(ArgAction::Set)
+                            .help("SimplicityHL program file to verify"),
+                    )
+                    .arg(
+                        Arg::new("args")
+                            .long("args")
+                            .value_name("ARGUMENTS_FILE")
+                            .action(ArgAction::Set)
+                            .help("File containing the arguments of the program (requires feature 'serde')"),
+                    )
+                    .arg(
+                        Arg::new("cmr")
+                            .long("cmr")
+                            .value_name("HEX")
+                            .required(true)
+                            .action(ArgAction::Set)
+                            .help("Expected CMR of the program"),
+                    )
+                    .arg(
+                        Arg::new("debug")
+                            .long("debug")
+                            .action(ArgAction::SetTrue)
+                            .help("Compile the program with debug symbols, like `simc --debug`"),
+                    ),
+            )
     };
 
     let matches = command.get_matches();
 
+    if let Some(("test", matches)) = matches.subcommand() {
+        return run_tests(matches);
+    }
+    if let Some(("run", matches)) = matches.subcommand() {
+        return run_encoded(matches);
+    }
+    if let Some(("decode", matches)) = matches.subcommand() {
+        return decode_program(matches);
+    }
+    if let Some(("verify", matches)) = matches.subcommand() {
+        return verify_program(matches);
+    }
+
+    if let Some(code) = matches.get_one::<String>("explain") {
+        let explanation = simplicityhl::error::explain(code)
+            .ok_or_else(|| format!("`{code}` is not a valid error code"))?;
+        print!("{explanation}");
+        return Ok(());
+    }
+
     let prog_file = matches.get_one::<String>("prog_file").unwrap();
     let prog_path = std::path::Path::new(prog_file);
     let prog_text = std::fs::read_to_string(prog_path).map_err(|e| e.to_string())?;
     let include_debug_symbols = matches.get_flag("debug");
     let output_json = matches.get_flag("json");
-
-    let compiled = CompiledProgram::new(prog_text, Arguments::default(), include_debug_symbols)?;
+    let error_format = matches.get_one::<String>("error_format").unwrap();
+    let lint_config = lint_config(&matches)?;
+    let script_opt = matches
+        .get_one::<String>("script")
+        .map(|hex| Script::from_hex(hex).map_err(|e| format!("Invalid script: {e}")))
+        .transpose()?;
 
     #[cfg(feature = "serde")]
     let witness_opt = matches
@@ -91,10 +382,54 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
 
+    let (compiled, mut diagnostics) = diagnostic::compile_program_with(
+        &prog_text,
+        Arguments::default(),
+        witness_opt.as_ref(),
+        include_debug_symbols,
+        &lint_config,
+    );
+    let compiled = match compiled {
+        Ok(compiled) => compiled,
+        Err(error) if error_format != "human" => {
+            diagnostics.push(Diagnostic::from(&error));
+            println!(
+                "{}",
+                format_diagnostics(&diagnostics, error_format, prog_file)?
+            );
+            std::process::exit(1);
+        }
+        Err(error) => return Err(error.into()),
+    };
+    if !diagnostics.is_empty() {
+        eprintln!(
+            "{}",
+            format_diagnostics(&diagnostics, error_format, prog_file)?
+        );
+    }
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        std::process::exit(1);
+    }
+
+    if let Some(debug_file) = matches.get_one::<String>("emit_debug_symbols") {
+        write_debug_symbols(compiled.debug_symbols(), debug_file)?;
+    }
+
     let (program_bytes, witness_bytes) = match witness_opt {
         Some(witness) => {
             let satisfied = compiled.satisfy(witness)?;
-            let (program_bytes, witness_bytes) = satisfied.redeem().to_vec_with_witness();
+            let redeem = match script_opt {
+                Some(script) => {
+                    let env =
+                        dummy_env::dummy_with_script(LockTime::ZERO, Sequence::MAX, false, script);
+                    let mut tracker = DefaultTracker::new(satisfied.debug_symbols());
+                    // The failure names the failed call, including its message, if there are debug symbols
+                    let pruned = satisfied.prune(&env, &mut tracker)?;
+                    Arc::clone(pruned.program().redeem())
+                }
+                None => Arc::clone(satisfied.redeem()),
+            };
+            let (program_bytes, witness_bytes) = redeem.to_vec_with_witness();
             (program_bytes, Some(witness_bytes))
         }
         None => {
@@ -104,6 +439,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let output = Output {
+        compiler_version: simplicityhl::COMPILER_VERSION,
         program: Base64Display::new(&program_bytes, &STANDARD).to_string(),
         witness: witness_bytes.map(|bytes| Base64Display::new(&bytes, &STANDARD).to_string()),
     };
@@ -121,3 +457,451 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Run the `test` subcommand.
+///
+/// Each witness file is one test case.
+/// The test cases pass if the program succeeds with the given witness data.
+#[cfg(feature = "serde")]
+fn run_tests(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let prog_file = matches.get_one::<String>("prog_file").unwrap();
+    let prog_path = std::path::Path::new(prog_file);
+    let prog_text = std::fs::read_to_string(prog_path).map_err(|e| e.to_string())?;
+    let env = match matches.get_one::<String>("script") {
+        Some(hex) => {
+            let script = Script::from_hex(hex).map_err(|e| format!("Invalid script: {e}"))?;
+            dummy_env::dummy_with_script(LockTime::ZERO, Sequence::MAX, false, script)
+        }
+        None => dummy_env::dummy(),
+    };
+    let wit_files = match matches.get_many::<String>("wit_files") {
+        Some(wit_files) => wit_files.map(std::path::PathBuf::from).collect(),
+        None => find_witness_files(prog_path)?,
+    };
+    if wit_files.is_empty() {
+        return Err(format!("No witness files found for {prog_file}").into());
+    }
+
+    let args_file = match matches.get_one::<String>("args") {
+        Some(args_file) => Some(std::path::PathBuf::from(args_file)),
+        None => Some(prog_path.with_extension("args")).filter(|path| path.exists()),
+    };
+    let arguments = match args_file {
+        Some(args_file) => {
+            let args_text = std::fs::read_to_string(&args_file).map_err(|e| e.to_string())?;
+            serde_json::from_str::<Arguments>(&args_text)
+                .map_err(|e| format!("Invalid arguments in {}: {e}", args_file.display()))?
+        }
+        None => Arguments::default(),
+    };
+
+    let compiled = CompiledProgram::new(prog_text.as_str(), arguments, true)?;
+    let mut coverage = Coverage::new(compiled.debug_symbols());
+    let mut profile = matches
+        .get_one::<String>("profile")
+        .map(|_| Profile::default());
+    let mut failed = 0;
+
+    println!("running {} tests", wit_files.len());
+    for wit_file in &wit_files {
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            let wit_text = std::fs::read_to_string(wit_file).map_err(|e| e.to_string())?;
+            let witness = serde_json::from_str::<simplicityhl::WitnessValues>(&wit_text)?;
+            let satisfied = compiled.satisfy(witness)?;
+            let mut tracker = TestTracker {
+                tracker: DefaultTracker::new(satisfied.debug_symbols()),
+                profiler: profile.as_ref().map(|_| {
+                    ProfilingTracker::new(satisfied.redeem().as_ref(), satisfied.debug_symbols())
+                }),
+            };
+            let result = satisfied.run(&env, &mut tracker);
+            coverage.record(
+                satisfied.redeem().as_ref(),
+                satisfied.debug_symbols(),
+                &tracker,
+            );
+            if let (Some(profile), Some(profiler)) = (profile.as_mut(), &tracker.profiler) {
+                profile.merge(profiler.profile());
+            }
+            result?;
+            Ok(())
+        })();
+        match result {
+            Ok(()) => println!("test {} ... ok", wit_file.display()),
+            Err(error) => {
+                println!("test {} ... FAILED: {error}", wit_file.display());
+                failed += 1;
+            }
+        }
+    }
+    println!(
+        "test result: {}. {} passed; {failed} failed",
+        if failed == 0 { "ok" } else { "FAILED" },
+        wit_files.len() - failed
+    );
+
+    if let Some(format) = matches.get_one::<String>("coverage") {
+        let format = format.parse::<CoverageFormat>()?;
+        let output = match matches.get_one::<String>("coverage_output") {
+            Some(output) => output.as_str(),
+            None => match format {
+                CoverageFormat::Lcov => "lcov.info",
+                CoverageFormat::Cobertura => "coverage.xml",
+            },
+        };
+        std::fs::write(output, coverage.report(format, prog_file)).map_err(|e| e.to_string())?;
+        println!("coverage: {coverage}");
+        println!("wrote {format} report to {output}");
+    }
+
+    if let (Some(profile), Some(output)) = (&profile, matches.get_one::<String>("profile")) {
+        let weight = matches
+            .get_one::<String>("profile_weight")
+            .unwrap()
+            .parse::<ProfileWeight>()?;
+        std::fs::write(output, profile.to_folded(weight)).map_err(|e| e.to_string())?;
+        println!("wrote profile to {output}");
+    }
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Run the `test` subcommand.
+#[cfg(not(feature = "serde"))]
+fn run_tests(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    Err("Program was compiled without the 'serde' feature and cannot process .wit files.".into())
+}
+
+/// Run the `verify` subcommand.
+fn verify_program(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let prog_file = matches.get_one::<String>("prog_file").unwrap();
+    let prog_text = std::fs::read_to_string(prog_file).map_err(|e| e.to_string())?;
+    let expected = matches
+        .get_one::<String>("cmr")
+        .unwrap()
+        .parse::<simplicityhl::simplicity::Cmr>()
+        .map_err(|e| format!("Invalid CMR: {e}"))?;
+    let arguments =
+        match matches.get_one::<String>("args") {
+            #[cfg(feature = "serde")]
+            Some(args_file) => {
+                let args_text = std::fs::read_to_string(args_file).map_err(|e| e.to_string())?;
+                serde_json::from_str::<Arguments>(&args_text)?
+            }
+            #[cfg(not(feature = "serde"))]
+            Some(_) => return Err(
+                "Program was compiled without the 'serde' feature and cannot process .args files."
+                    .into(),
+            ),
+            None => Arguments::default(),
+        };
+
+    let template = simplicityhl::TemplateProgram::new(prog_text.as_str())?;
+    match template.verify_cmr(arguments, expected, matches.get_flag("debug")) {
+        Ok(()) => {
+            println!(
+                "{prog_file} compiles to CMR {expected} (compiler version {})",
+                simplicityhl::COMPILER_VERSION
+            );
+            Ok(())
+        }
+        Err(error @ simplicityhl::Error::CmrMismatch { .. }) => {
+            eprintln!("error: {error}");
+            std::process::exit(1);
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Tracker of the `test` subcommand.
+///
+/// The tracker records the executed branches for the coverage report
+/// and profiles the run if a profile is requested, so each test case runs once.
+#[cfg(feature = "serde")]
+struct TestTracker<'a> {
+    tracker: DefaultTracker<'a>,
+    profiler: Option<ProfilingTracker>,
+}
+
+#[cfg(feature = "serde")]
+impl PruneTracker<ElementsExtension> for TestTracker<'_> {
+    fn contains_left(&self, ihr: Ihr) -> bool {
+        PruneTracker::<ElementsExtension>::contains_left(&self.tracker, ihr)
+    }
+
+    fn contains_right(&self, ihr: Ihr) -> bool {
+        PruneTracker::<ElementsExtension>::contains_right(&self.tracker, ihr)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ExecTracker<ElementsExtension> for TestTracker<'_> {
+    fn visit_node(
+        &mut self,
+        node: &RedeemNode<ElementsExtension>,
+        input: FrameIter,
+        output: NodeOutput,
+    ) {
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.visit_node(node, input.clone(), output.clone());
+        }
+        self.tracker.visit_node(node, input, output);
+    }
+}
+
+/// Run the `run` subcommand.
+#[cfg(feature = "serde")]
+fn run_encoded(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let prog_file = matches.get_one::<String>("prog_file").unwrap();
+    let program_bytes = read_base64(prog_file)?;
+    let witness_bytes = match matches.get_one::<String>("witness") {
+        Some(wit_file) => read_base64(wit_file)?,
+        None => Vec::new(),
+    };
+    let debug_symbols = load_debug_symbols(matches, prog_file)?;
+    let env = match matches.get_one::<String>("script") {
+        Some(hex) => {
+            let script = Script::from_hex(hex).map_err(|e| format!("Invalid script: {e}"))?;
+            dummy_env::dummy_with_script(LockTime::ZERO, Sequence::MAX, false, script)
+        }
+        None => dummy_env::dummy(),
+    };
+
+    let satisfied =
+        simplicityhl::SatisfiedProgram::decode(&program_bytes, &witness_bytes, debug_symbols)?;
+    let mut tracker = DefaultTracker::new(satisfied.debug_symbols()).with_default_debug_sink();
+    satisfied.run(&env, &mut tracker)?;
+    println!("Program succeeded");
+    Ok(())
+}
+
+/// Run the `run` subcommand.
+#[cfg(not(feature = "serde"))]
+fn run_encoded(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    Err("Program was compiled without the 'serde' feature and cannot process .simdbg files.".into())
+}
+
+/// Run the `decode` subcommand.
+#[cfg(feature = "serde")]
+fn decode_program(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    use simplicityhl::simplicity::{BitIter, CommitNode};
+
+    let prog_file = matches.get_one::<String>("prog_file").unwrap();
+    let program_bytes = read_base64(prog_file)?;
+    let rendered = match matches.get_one::<String>("witness") {
+        Some(wit_file) => {
+            let witness_bytes = read_base64(wit_file)?;
+            let program = RedeemNode::<ElementsExtension>::decode(
+                BitIter::from(program_bytes.as_slice()),
+                BitIter::from(witness_bytes.as_slice()),
+            )?;
+            let debug_symbols = annotations(matches, prog_file, program.cmr())?;
+            decompile::render_redeem(&program, &debug_symbols)
+        }
+        None => {
+            let program =
+                CommitNode::<ElementsExtension>::decode(BitIter::from(program_bytes.as_slice()))?;
+            let debug_symbols = annotations(matches, prog_file, program.cmr())?;
+            decompile::render_commit(&program, &debug_symbols)
+        }
+    };
+    print!("{rendered}");
+    Ok(())
+}
+
+/// Run the `decode` subcommand.
+#[cfg(not(feature = "serde"))]
+fn decode_program(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    Err("Program was compiled without the 'serde' feature and cannot process .simdbg files.".into())
+}
+
+/// Get the debug symbols that annotate the decoded program with the given CMR.
+///
+/// If a source file is given, then it is compiled with and without debug symbols,
+/// and the debug symbols of the compilation that reproduces the CMR are used.
+/// Otherwise, the debug symbols are loaded from the sidecar file,
+/// which must belong to the program with the given CMR.
+#[cfg(feature = "serde")]
+fn annotations(
+    matches: &clap::ArgMatches,
+    prog_file: &str,
+    cmr: simplicityhl::simplicity::Cmr,
+) -> Result<DebugSymbols, Box<dyn std::error::Error>> {
+    let Some(source_file) = matches.get_one::<String>("source") else {
+        let debug_symbols = load_debug_symbols(matches, prog_file)?;
+        if let Some(expected) = debug_symbols
+            .program_cmr()
+            .filter(|expected| *expected != cmr)
+        {
+            return Err(simplicityhl::Error::DebugSymbolsMismatch(expected, cmr).into());
+        }
+        return Ok(debug_symbols);
+    };
+    let source = std::fs::read_to_string(source_file).map_err(|e| e.to_string())?;
+    let with_debug = CompiledProgram::new(source.as_str(), Arguments::default(), true)?;
+    if with_debug.commit().cmr() == cmr {
+        return Ok(with_debug.debug_symbols().clone());
+    }
+    let without_debug = CompiledProgram::new(source.as_str(), Arguments::default(), false)?;
+    if without_debug.commit().cmr() == cmr {
+        return Ok(without_debug.debug_symbols().clone());
+    }
+    eprintln!("warning: {source_file} does not compile to the decoded program, so the annotations may be wrong");
+    Ok(with_debug.debug_symbols().clone())
+}
+
+/// Load the debug symbols from the file of the `--debug-symbols` flag.
+///
+/// Without the flag, load the sidecar file `PROGRAM.simdbg` next to the program file, if it exists.
+#[cfg(feature = "serde")]
+fn load_debug_symbols(
+    matches: &clap::ArgMatches,
+    prog_file: &str,
+) -> Result<DebugSymbols, Box<dyn std::error::Error>> {
+    let debug_path = match matches.get_one::<String>("debug_symbols") {
+        Some(file) => Some(std::path::PathBuf::from(file)),
+        None => Some(std::path::Path::new(prog_file).with_extension("simdbg"))
+            .filter(|path| path.exists()),
+    };
+    match debug_path {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            let debug_symbols = serde_json::from_str::<DebugSymbols>(&text)
+                .map_err(|e| format!("Invalid debug symbols in {}: {e}", path.display()))?;
+            Ok(debug_symbols)
+        }
+        None => Ok(DebugSymbols::default()),
+    }
+}
+
+/// Write the debug symbols to the given sidecar file.
+#[cfg(feature = "serde")]
+fn write_debug_symbols(
+    debug_symbols: &DebugSymbols,
+    debug_file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sidecar = serde_json::to_string(debug_symbols)?;
+    std::fs::write(debug_file, sidecar).map_err(|e| format!("Cannot write {debug_file}: {e}"))?;
+    Ok(())
+}
+
+/// Write the debug symbols to the given sidecar file.
+#[cfg(not(feature = "serde"))]
+fn write_debug_symbols(
+    _debug_symbols: &simplicityhl::debug::DebugSymbols,
+    _debug_file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("Program was compiled without the 'serde' feature and cannot write .simdbg files.".into())
+}
+
+/// Read a file that contains base64 encoded bytes.
+#[cfg(feature = "serde")]
+fn read_base64(file: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(file).map_err(|e| e.to_string())?;
+    let bytes = base64::Engine::decode(&STANDARD, text.trim())
+        .map_err(|e| format!("Invalid base64 in {file}: {e}"))?;
+    Ok(bytes)
+}
+
+/// Find the witness files `PROGRAM.wit` and `PROGRAM.*.wit` next to the given program file.
+#[cfg(feature = "serde")]
+fn find_witness_files(
+    prog_path: &std::path::Path,
+) -> Result<Vec<std::path::PathBuf>, Box<dyn std::error::Error>> {
+    let stem = prog_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or("Invalid program file name")?;
+    let dir = match prog_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => std::path::Path::new("."),
+    };
+
+    let mut wit_files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(case) = name
+            .strip_prefix(stem)
+            .and_then(|rest| rest.strip_suffix(".wit"))
+        else {
+            continue;
+        };
+        if case.is_empty() || (case.starts_with('.') && case.len() > 1) {
+            wit_files.push(path);
+        }
+    }
+    wit_files.sort();
+    Ok(wit_files)
+}
+
+/// Collect the lint levels from the `-W`, `-D` and `-A` flags.
+///
+/// Later flags override earlier flags, regardless of their level.
+fn lint_config(matches: &clap::ArgMatches) -> Result<LintConfig, String> {
+    let mut flags = Vec::new();
+    for (id, level) in [
+        ("warn", Level::Warn),
+        ("deny", Level::Deny),
+        ("allow", Level::Allow),
+    ] {
+        if let (Some(indices), Some(names)) =
+            (matches.indices_of(id), matches.get_many::<String>(id))
+        {
+            flags.extend(indices.zip(names).map(|(index, name)| (index, level, name)));
+        }
+    }
+    flags.sort_by_key(|(index, _, _)| *index);
+
+    let mut config = LintConfig::default();
+    for (_, level, name) in flags {
+        config.set_by_name(name, level)?;
+    }
+    Ok(config)
+}
+
+/// Format the diagnostics in the given format.
+fn format_diagnostics(
+    diagnostics: &[Diagnostic],
+    error_format: &str,
+    prog_file: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match error_format {
+        "human" => Ok(diagnostics
+            .iter()
+            .map(|diagnostic| format!("{prog_file}: {diagnostic}"))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        _ => format_machine_diagnostics(diagnostics, error_format, prog_file),
+    }
+}
+
+/// Format the diagnostics in the given machine-readable format.
+#[cfg(feature = "serde")]
+fn format_machine_diagnostics(
+    diagnostics: &[Diagnostic],
+    error_format: &str,
+    prog_file: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let output = match error_format {
+        "json" => serde_json::to_string(diagnostics)?,
+        "sarif" => serde_json::to_string(&diagnostic::to_sarif(diagnostics, prog_file))?,
+        _ => unreachable!("clap rejects other formats"),
+    };
+    Ok(output)
+}
+
+/// Format the diagnostics in the given machine-readable format.
+#[cfg(not(feature = "serde"))]
+fn format_machine_diagnostics(
+    _diagnostics: &[Diagnostic],
+    _error_format: &str,
+    _prog_file: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    Err("Program was compiled without the 'serde' feature and cannot output JSON.".into())
+}
@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
-use simplicity::dag::{InternalSharing, PostOrderIterItem};
+use std::collections::BTreeMap;
+
+use simplicity::dag::{Dag, InternalSharing, PostOrderIterItem};
 use simplicity::jet::Jet;
 use simplicity::node::{
-    self, Converter, CoreConstructible, Inner, NoDisconnect, NoWitness, Node, WitnessConstructible,
+    self, Converter, CoreConstructible, DisconnectConstructible, Disconnectable, Inner,
+    NoDisconnect, NoWitness, Node, WitnessConstructible,
 };
 use simplicity::Cmr;
 use simplicity::{types, FailEntropy};
@@ -18,9 +21,10 @@ pub struct WithNames<T>(T);
 impl<M: node::Marker> node::Marker for WithNames<M> {
     type CachedData = M::CachedData;
     type Witness = WitnessName;
-    // It's quite difficult to wrap M::Disconnect because of Rust's lack of HKTs, and
-    // we don't use disconnect in this library right now, so punt on it for now.
-    type Disconnect = NoDisconnect;
+    // It's quite difficult to wrap M::Disconnect because of Rust's lack of HKTs.
+    // Instead, disconnected expressions are named like witnesses,
+    // and the delegated program is supplied when witnesses are populated.
+    type Disconnect = WitnessName;
     type SharingId = M::SharingId;
     type Jet = M::Jet;
 
@@ -53,14 +57,14 @@ impl Nullable for NoDisconnect {
     }
 }
 
-/// [`simplicity::ConstructNode`] with named witness nodes.
+/// [`simplicity::ConstructNode`] with named witness and disconnect nodes.
 ///
-/// Nodes other than witness don't have names.
+/// Nodes other than witness and disconnect don't have names.
 pub type ConstructNode<'brand, J> = Node<WithNames<node::Construct<'brand, J>>>;
 
-/// [`simplicity::CommitNode`] with named witness nodes.
+/// [`simplicity::CommitNode`] with named witness and disconnect nodes.
 ///
-/// Nodes other than witness don't have names.
+/// Nodes other than witness and disconnect don't have names.
 pub type CommitNode<J> = Node<WithNames<node::Commit<J>>>;
 
 // FIXME: The following methods cannot be implemented for simplicity::node::Node because that is a foreign type
@@ -71,7 +75,9 @@ pub fn finalize_types<J: Jet>(
     // to unit. This is a bit annoying to do, and anyway these types will already
     // be unit by construction.
     translate(node, |node, inner| {
-        let inner = inner.map_witness(|_| &NoWitness);
+        let inner = inner
+            .map_witness(|_| &NoWitness)
+            .map_disconnect(|_| &NoDisconnect);
         node::CommitData::new(node.cached_data().arrow(), inner).map(Arc::new)
     })
 }
@@ -86,7 +92,7 @@ where
     N::Witness: Nullable,
     F: FnMut(
         &Node<WithNames<M>>,
-        Inner<&N::CachedData, N::Jet, &NoDisconnect, &WitnessName>,
+        Inner<&N::CachedData, N::Jet, &WitnessName, &WitnessName>,
     ) -> Result<N::CachedData, E>,
 {
     struct Translator<F>(F);
@@ -98,7 +104,7 @@ where
         N::Witness: Nullable,
         F: FnMut(
             &Node<WithNames<M>>,
-            Inner<&N::CachedData, N::Jet, &NoDisconnect, &WitnessName>,
+            Inner<&N::CachedData, N::Jet, &WitnessName, &WitnessName>,
         ) -> Result<N::CachedData, E>,
     {
         type Error = E;
@@ -115,15 +121,15 @@ where
             &mut self,
             _: &PostOrderIterItem<&Node<WithNames<M>>>,
             _: Option<&Arc<Node<WithNames<N>>>>,
-            _: &NoDisconnect,
-        ) -> Result<NoDisconnect, Self::Error> {
-            Ok(NoDisconnect)
+            disc: &WitnessName,
+        ) -> Result<WitnessName, Self::Error> {
+            Ok(disc.shallow_clone())
         }
 
         fn convert_data(
             &mut self,
             data: &PostOrderIterItem<&Node<WithNames<M>>>,
-            inner: Inner<&Arc<Node<WithNames<N>>>, N::Jet, &NoDisconnect, &WitnessName>,
+            inner: Inner<&Arc<Node<WithNames<N>>>, N::Jet, &WitnessName, &WitnessName>,
         ) -> Result<N::CachedData, Self::Error> {
             let new_inner = inner.map(|node| node.cached_data());
             self.0(data.node, new_inner)
@@ -133,7 +139,7 @@ where
     node.convert::<InternalSharing, _, _>(&mut Translator(translatefn))
 }
 
-/// Convert [`ConstructNode`] into [`CommitNode`] by dropping the name of witness nodes.
+/// Convert [`ConstructNode`] into [`CommitNode`] by dropping the name of witness and disconnect nodes.
 pub fn forget_names<M>(node: &Node<WithNames<M>>) -> Arc<Node<M>>
 where
     M: node::Marker,
@@ -162,7 +168,7 @@ where
             &mut self,
             _: &PostOrderIterItem<&Node<WithNames<M>>>,
             _: Option<&Arc<Node<M>>>,
-            _: &NoDisconnect,
+            _: &WitnessName,
         ) -> Result<M::Disconnect, Self::Error> {
             Ok(M::Disconnect::none())
         }
@@ -183,27 +189,30 @@ where
 }
 
 /// Converts a named [`ConstructNode`] into a standard [`node::ConstructNode`], by populating
-/// witness nodes with their assigned values.
+/// witness nodes with their assigned values and disconnect nodes with their delegated programs.
 ///
 /// Each witness node has a name. If there is no value assigned to this name, an error is
 /// returned. This is true even if the witness node is ultimately unused in the final
-/// program.
+/// program. The same holds for disconnect nodes and `delegates`.
 ///
 /// ## Soundness
 ///
-/// It is the responsibility of the caller to ensure that the given witness `values` match the
-/// types in the construct `node`. This can be done by calling [`WitnessValues::is_consistent`]
+/// It is the responsibility of the caller to ensure that the given witness `values` and
+/// `delegates` match the types in the construct `node`. This can be done by calling
+/// [`WitnessValues::is_consistent`] and [`crate::Delegates::is_consistent`]
 /// on the original SimplicityHL program before it is compiled to Simplicity.
 pub fn populate_witnesses<J: Jet>(
     node: &CommitNode<J>,
     values: WitnessValues,
-) -> Result<Arc<node::RedeemNode<J>>, String> {
-    struct Populator {
+    delegates: &BTreeMap<WitnessName, Arc<node::RedeemNode<J>>>,
+) -> Result<Arc<node::RedeemNode<J>>, crate::Error> {
+    struct Populator<'a, J: Jet> {
         values: WitnessValues,
+        delegates: &'a BTreeMap<WitnessName, Arc<node::RedeemNode<J>>>,
     }
 
-    impl<J: Jet> Converter<WithNames<node::Commit<J>>, node::Redeem<J>> for Populator {
-        type Error = String;
+    impl<J: Jet> Converter<WithNames<node::Commit<J>>, node::Redeem<J>> for Populator<'_, J> {
+        type Error = crate::Error;
 
         fn convert_witness(
             &mut self,
@@ -212,7 +221,7 @@ pub fn populate_witnesses<J: Jet>(
         ) -> Result<simplicity::Value, Self::Error> {
             match self.values.get(witness) {
                 Some(val) => Ok(simplicity::Value::from(StructuralValue::from(val))),
-                None => Err(format!("missing witness for {witness}")),
+                None => Err(crate::Error::MissingWitness(witness.clone())),
             }
         }
 
@@ -220,9 +229,12 @@ pub fn populate_witnesses<J: Jet>(
             &mut self,
             _: &PostOrderIterItem<&CommitNode<J>>,
             _: Option<&Arc<node::RedeemNode<J>>>,
-            _: &NoDisconnect,
+            delegate: &WitnessName,
         ) -> Result<Arc<node::RedeemNode<J>>, Self::Error> {
-            unreachable!("SimplicityHL does not use disconnect right now")
+            match self.delegates.get(delegate) {
+                Some(program) => Ok(Arc::clone(program)),
+                None => Err(crate::Error::MissingDelegate(delegate.clone())),
+            }
         }
 
         fn convert_data(
@@ -246,7 +258,7 @@ pub fn populate_witnesses<J: Jet>(
         }
     }
 
-    let mut populator = Populator { values };
+    let mut populator = Populator { values, delegates };
     node.convert::<InternalSharing, _, _>(&mut populator)
 }
 
@@ -259,6 +271,29 @@ impl<'brand, J: Jet> WitnessConstructible<'brand, WitnessName> for node::Constru
     }
 }
 
+// Disconnect nodes are named like witness nodes. The delegated program is not part of the
+// named node, so the name behaves like a missing right child.
+impl<L> Disconnectable<L> for WitnessName {
+    fn disconnect_dag_arc(self, other: Arc<L>) -> Dag<Arc<L>> {
+        Dag::Unary(other)
+    }
+
+    fn disconnect_dag_ref<'s>(&'s self, other: &'s L) -> Dag<&'s L> {
+        Dag::Unary(other)
+    }
+}
+
+// See the comment on WitnessConstructible above.
+impl<'brand, J: Jet> DisconnectConstructible<'brand, WitnessName>
+    for node::ConstructData<'brand, J>
+{
+    fn disconnect(left: &Self, _: &WitnessName) -> Result<Self, types::Error> {
+        DisconnectConstructible::<Option<Arc<node::ConstructNode<'brand, J>>>>::disconnect(
+            left, &None,
+        )
+    }
+}
+
 /// More constructors for types that implement [`CoreConstructible`].
 pub trait CoreExt<'brand>: CoreConstructible<'brand> + Sized {
     fn h(inference_context: &types::Context<'brand>) -> PairBuilder<Self> {
@@ -14,11 +14,12 @@ use pest_derive::Parser;
 
 use crate::error::{Error, RichError, Span, WithFile, WithSpan};
 use crate::impl_eq_hash;
+use crate::lint::Level;
 use crate::num::NonZeroPow2Usize;
 use crate::pattern::Pattern;
 use crate::str::{
-    AliasName, Binary, Decimal, FunctionName, Hexadecimal, Identifier, JetName, ModuleName,
-    WitnessName,
+    AliasName, Binary, Decimal, FunctionName, Hexadecimal, Identifier, JetName, LintName,
+    ModuleName, WitnessName,
 };
 use crate::types::{AliasedType, BuiltinAlias, TypeConstructible, UIntType};
 
@@ -29,18 +30,60 @@ struct IdentParser;
 /// A program is a sequence of items.
 #[derive(Clone, Debug)]
 pub struct Program {
+    attributes: Arc<[Attribute]>,
     items: Arc<[Item]>,
     span: Span,
 }
 
 impl Program {
+    /// Access the attributes that apply to the entire program.
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
     /// Access the items of the program.
     pub fn items(&self) -> &[Item] {
         &self.items
     }
+
+    /// Create a copy of the program with the given `items`.
+    pub(crate) fn with_items(&self, items: Arc<[Item]>) -> Self {
+        Self {
+            attributes: Arc::clone(&self.attributes),
+            items,
+            span: self.span,
+        }
+    }
+}
+
+impl_eq_hash!(Program; attributes, items);
+
+/// An attribute sets the level of lints, such as `#[allow(unused_variables)]`.
+#[derive(Clone, Debug)]
+pub struct Attribute {
+    level: Level,
+    lints: Arc<[LintName]>,
+    span: Span,
+}
+
+impl Attribute {
+    /// Access the level that the attribute sets.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Access the names of the lints whose level is set.
+    pub fn lints(&self) -> &[LintName] {
+        &self.lints
+    }
+
+    /// Access the span of the attribute.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
 }
 
-impl_eq_hash!(Program; items);
+impl_eq_hash!(Attribute; level, lints);
 
 /// An item is a component of a program.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -50,13 +93,36 @@ pub enum Item {
     TypeAlias(TypeAlias),
     /// A function.
     Function(Function),
+    /// A constant.
+    Const(Const),
     /// A module, which is ignored.
     Module,
+    /// An import of a module of the standard library.
+    Use(Use),
 }
 
+/// An import of a module of the standard library, such as `use std::script;`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Use {
+    module: Identifier,
+    span: Span,
+}
+
+impl Use {
+    /// Access the name of the imported module.
+    pub fn module(&self) -> &Identifier {
+        &self.module
+    }
+}
+
+impl_eq_hash!(Use; module);
+
 /// Definition of a function.
 #[derive(Clone, Debug)]
 pub struct Function {
+    attributes: Arc<[Attribute]>,
+    is_const: bool,
     name: FunctionName,
     params: Arc<[FunctionParam]>,
     ret: Option<AliasedType>,
@@ -65,6 +131,18 @@ pub struct Function {
 }
 
 impl Function {
+    /// Access the attributes of the function.
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    /// Check if the function is declared `const fn`.
+    ///
+    /// Calls of const functions with constant arguments are evaluated at compile time.
+    pub fn is_const(&self) -> bool {
+        self.is_const
+    }
+
     /// Access the name of the function.
     pub fn name(&self) -> &FunctionName {
         &self.name
@@ -93,14 +171,14 @@ impl Function {
     }
 }
 
-impl_eq_hash!(Function; name, params, ret, body);
+impl_eq_hash!(Function; attributes, is_const, name, params, ret, body);
 
 /// Parameter of a function.
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug)]
 pub struct FunctionParam {
     identifier: Identifier,
     ty: AliasedType,
+    span: Span,
 }
 
 impl FunctionParam {
@@ -113,8 +191,15 @@ impl FunctionParam {
     pub fn ty(&self) -> &AliasedType {
         &self.ty
     }
+
+    /// Access the span of the identifier of the parameter.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
 }
 
+impl_eq_hash!(FunctionParam; identifier, ty);
+
 /// A statement is a component of a block expression.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Statement {
@@ -131,6 +216,8 @@ pub struct Assignment {
     ty: AliasedType,
     expression: Expression,
     span: Span,
+    /// Spans of the identifiers of the pattern, from left to right.
+    identifier_spans: Arc<[Span]>,
 }
 
 impl Assignment {
@@ -139,6 +226,18 @@ impl Assignment {
         &self.pattern
     }
 
+    /// Iterate over the variables that the pattern binds, from left to right,
+    /// together with the spans of their identifiers.
+    pub fn bindings(&self) -> impl Iterator<Item = (&Identifier, &Span)> {
+        self.pattern
+            .pre_order_iter()
+            .filter_map(|node| match node {
+                Pattern::Identifier(identifier) => Some(identifier),
+                _ => None,
+            })
+            .zip(self.identifier_spans.iter())
+    }
+
     /// Access the return type of assigned expression.
     pub fn ty(&self) -> &AliasedType {
         &self.ty
@@ -162,6 +261,7 @@ impl_eq_hash!(Assignment; pattern, ty, expression);
 pub struct Call {
     name: CallName,
     args: Arc<[Expression]>,
+    message: Option<Arc<str>>,
     span: Span,
 }
 
@@ -176,13 +276,20 @@ impl Call {
         self.args.as_ref()
     }
 
+    /// Access the failure message of the call, such as `assert!(cond, "message")`.
+    ///
+    /// The message is part of the debug symbols and does not affect the compiled program.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
     /// Access the span of the call.
     pub fn span(&self) -> &Span {
         &self.span
     }
 }
 
-impl_eq_hash!(Call; name, args);
+impl_eq_hash!(Call; name, args, message);
 
 /// Name of a call.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -214,6 +321,31 @@ pub enum CallName {
     ArrayFold(FunctionName, NonZeroUsize),
     /// Loop over the given function a bounded number of times until it returns success.
     ForWhile(FunctionName),
+    /// Call the given function with the CMR of a delegated program,
+    /// which is supplied at redemption time under the given name.
+    Disconnect(FunctionName, WitnessName),
+    /// SHA256 hash of a byte array of the given size.
+    Sha256(NonZeroUsize),
+    /// Add a byte array of the given size to a SHA256 hash context.
+    Sha256Add(NonZeroUsize),
+    /// Concatenation of two arrays of the given sizes.
+    ArrayConcat(NonZeroUsize, NonZeroUsize),
+    /// Map of an array of the given size with the given function.
+    ArrayMap(FunctionName, NonZeroUsize),
+    /// Zip of two arrays into an array of pairs.
+    ArrayZip,
+    /// Reversal of an array.
+    ArrayReverse,
+    /// Check if an array of the given element type and size contains a value.
+    ArrayContains(AliasedType, NonZeroUsize),
+    /// Element of an array of the given size at a runtime index of the given type.
+    ArrayGet(AliasedType, NonZeroUsize),
+    /// Length of a bounded list of the given element type.
+    ListLen(AliasedType, NonZeroPow2Usize),
+    /// Append an element to a bounded list.
+    ListPush,
+    /// Element of a bounded list at a runtime index.
+    ListGet(NonZeroPow2Usize),
 }
 
 /// A type alias.
@@ -247,6 +379,41 @@ impl TypeAlias {
 
 impl_eq_hash!(TypeAlias; name, ty);
 
+/// A constant, such as `const ALICE: Pubkey = 0x…;`.
+///
+/// The value of the constant is computed at compile time.
+#[derive(Clone, Debug)]
+pub struct Const {
+    name: Identifier,
+    ty: AliasedType,
+    expression: Expression,
+    span: Span,
+}
+
+impl Const {
+    /// Access the name of the constant.
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// Access the type of the constant.
+    pub fn ty(&self) -> &AliasedType {
+        &self.ty
+    }
+
+    /// Access the expression that computes the value of the constant.
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    /// Access the span of the constant.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+impl_eq_hash!(Const; name, ty, expression);
+
 /// An expression is something that returns a value.
 #[derive(Clone, Debug)]
 pub struct Expression {
@@ -347,6 +514,16 @@ pub enum SingleExpressionInner {
     ///
     /// The exclusive upper bound on the list size is not known at this point
     List(Arc<[Expression]>),
+    /// Array element at the given index
+    Index(Arc<Expression>, Arc<Expression>),
+    /// Subarray over the given range of indices
+    Slice(Arc<Expression>, usize, usize),
+    /// Early return from the enclosing function
+    ///
+    /// Returns nothing (unit) if there is no expression
+    Return(Option<Arc<Expression>>),
+    /// Question mark operator that returns early on `None` or `Left`
+    Try(Arc<Expression>),
 }
 
 /// Match expression.
@@ -395,10 +572,11 @@ impl Match {
 impl_eq_hash!(Match; scrutinee, left, right);
 
 /// Arm of a match expression.
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug)]
 pub struct MatchArm {
     pattern: MatchPattern,
     expression: Arc<Expression>,
+    variable_span: Option<Span>,
 }
 
 impl MatchArm {
@@ -411,8 +589,15 @@ impl MatchArm {
     pub fn expression(&self) -> &Expression {
         &self.expression
     }
+
+    /// Access the span of the identifier of the variable that the pattern binds, if any.
+    pub fn variable_span(&self) -> Option<&Span> {
+        self.variable_span.as_ref()
+    }
 }
 
+impl_eq_hash!(MatchArm; pattern, expression);
+
 /// Pattern of a match arm.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -532,6 +717,9 @@ impl ModuleAssignment {
 
 impl fmt::Display for Program {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for attribute in self.attributes() {
+            writeln!(f, "#![{attribute}]")?;
+        }
         for item in self.items() {
             writeln!(f, "{item}")?;
         }
@@ -544,22 +732,54 @@ impl fmt::Display for Item {
         match self {
             Self::TypeAlias(alias) => write!(f, "{alias}"),
             Self::Function(function) => write!(f, "{function}"),
+            Self::Const(constant) => write!(f, "{constant}"),
             // The parse tree contains no information about the contents of modules.
             // We print a random empty module `mod witness {}` here
             // so that `from_string(to_string(x)) = x` holds for all trees `x`.
             Self::Module => write!(f, "mod witness {{}}"),
+            Self::Use(import) => write!(f, "{import}"),
         }
     }
 }
 
+impl fmt::Display for Use {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "use std::{};", self.module())
+    }
+}
+
+impl fmt::Display for Const {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "const {}: {} = {};",
+            self.name(),
+            self.ty(),
+            self.expression()
+        )
+    }
+}
+
 impl fmt::Display for TypeAlias {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "type {} = {};", self.name(), self.ty())
     }
 }
 
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.level(), self.lints().iter().join(", "))
+    }
+}
+
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for attribute in self.attributes() {
+            writeln!(f, "#[{attribute}]")?;
+        }
+        if self.is_const() {
+            write!(f, "const ")?;
+        }
         write!(f, "fn {}(", self.name())?;
         for (i, param) in self.params().iter().enumerate() {
             if 0 < i {
@@ -623,16 +843,23 @@ impl TreeLike for ExprTree<'_> {
                 | S::Variable(_)
                 | S::Witness(_)
                 | S::Parameter(_)
-                | S::Option(None) => Tree::Nullary,
+                | S::Option(None)
+                | S::Return(None) => Tree::Nullary,
                 S::Option(Some(l))
                 | S::Either(Either::Left(l))
                 | S::Either(Either::Right(l))
-                | S::Expression(l) => Tree::Unary(Self::Expression(l)),
+                | S::Expression(l)
+                | S::Return(Some(l))
+                | S::Try(l) => Tree::Unary(Self::Expression(l)),
                 S::Call(call) => Tree::Unary(Self::Call(call)),
                 S::Match(match_) => Tree::Unary(Self::Match(match_)),
                 S::Tuple(elements) | S::Array(elements) | S::List(elements) => {
                     Tree::Nary(elements.iter().map(Self::Expression).collect())
                 }
+                S::Index(array, index) => {
+                    Tree::Nary(Arc::new([Self::Expression(array), Self::Expression(index)]))
+                }
+                S::Slice(array, ..) => Tree::Unary(Self::Expression(array)),
             },
             Self::Call(call) => Tree::Nary(call.args().iter().map(Self::Expression).collect()),
             Self::Match(match_) => Tree::Nary(Arc::new([
@@ -734,6 +961,30 @@ impl fmt::Display for ExprTree<'_> {
                             write!(f, "]")?;
                         }
                     }
+                    S::Index(..) => match data.n_children_yielded {
+                        0 => {}
+                        1 => write!(f, "[")?,
+                        n => {
+                            debug_assert_eq!(n, 2);
+                            write!(f, "]")?;
+                        }
+                    },
+                    S::Slice(_, start, end) => {
+                        if data.is_complete {
+                            write!(f, "[{start}..{end}]")?;
+                        }
+                    }
+                    S::Return(None) => write!(f, "return")?,
+                    S::Return(Some(_)) => {
+                        if data.n_children_yielded == 0 {
+                            write!(f, "return ")?;
+                        }
+                    }
+                    S::Try(_) => {
+                        if data.is_complete {
+                            write!(f, "?")?;
+                        }
+                    }
                 },
                 Self::Call(call) => {
                     if data.n_children_yielded == 0 {
@@ -742,6 +993,12 @@ impl fmt::Display for ExprTree<'_> {
                         write!(f, ", ")?;
                     }
                     if data.is_complete {
+                        if let Some(message) = call.message() {
+                            if !call.args().is_empty() {
+                                write!(f, ", ")?;
+                            }
+                            write_string_literal(f, message)?;
+                        }
                         write!(f, ")")?;
                     }
                 }
@@ -807,6 +1064,20 @@ impl fmt::Display for CallName {
             CallName::Fold(name, bound) => write!(f, "fold::<{name}, {bound}>"),
             CallName::ArrayFold(name, size) => write!(f, "array_fold::<{name}, {size}>"),
             CallName::ForWhile(name) => write!(f, "for_while::<{name}>"),
+            CallName::Disconnect(name, delegate) => write!(f, "disconnect::<{name}, {delegate}>"),
+            CallName::Sha256(size) => write!(f, "sha256::<{size}>"),
+            CallName::Sha256Add(size) => write!(f, "sha256_add::<{size}>"),
+            CallName::ArrayConcat(size_l, size_r) => {
+                write!(f, "array_concat::<{size_l}, {size_r}>")
+            }
+            CallName::ArrayMap(name, size) => write!(f, "array_map::<{name}, {size}>"),
+            CallName::ArrayZip => write!(f, "array_zip"),
+            CallName::ArrayReverse => write!(f, "array_reverse"),
+            CallName::ArrayContains(ty, size) => write!(f, "array_contains::<{ty}, {size}>"),
+            CallName::ArrayGet(ty, size) => write!(f, "array_get::<{ty}, {size}>"),
+            CallName::ListLen(ty, bound) => write!(f, "list_len::<{ty}, {bound}>"),
+            CallName::ListPush => write!(f, "list_push"),
+            CallName::ListGet(bound) => write!(f, "list_get::<{bound}>"),
         }
     }
 }
@@ -861,6 +1132,7 @@ impl_parse_wrapped_string!(Identifier, identifier);
 impl_parse_wrapped_string!(WitnessName, witness_name);
 impl_parse_wrapped_string!(AliasName, alias_name);
 impl_parse_wrapped_string!(ModuleName, module_name);
+impl_parse_wrapped_string!(LintName, lint_name);
 
 /// Copy of [`FromStr`] that internally uses the PEST parser.
 pub trait ParseFromStr: Sized {
@@ -884,14 +1156,43 @@ impl PestParse for Program {
     fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Self, RichError> {
         assert!(matches!(pair.as_rule(), Self::RULE));
         let span = Span::from(&pair);
-        let items = pair
-            .into_inner()
-            .filter_map(|pair| match pair.as_rule() {
-                Rule::item => Some(Item::parse(pair)),
-                _ => None,
-            })
-            .collect::<Result<Arc<[Item]>, RichError>>()?;
-        Ok(Program { items, span })
+        let mut attributes = Vec::new();
+        let mut items = Vec::new();
+        for pair in pair.into_inner() {
+            match pair.as_rule() {
+                Rule::inner_attribute => attributes.push(Attribute::parse(pair)?),
+                Rule::item => items.push(Item::parse(pair)?),
+                _ => {}
+            }
+        }
+        Ok(Program {
+            attributes: Arc::from(attributes),
+            items: Arc::from(items),
+            span,
+        })
+    }
+}
+
+impl PestParse for Attribute {
+    const RULE: Rule = Rule::attribute;
+
+    fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Self, RichError> {
+        assert!(matches!(
+            pair.as_rule(),
+            Rule::attribute | Rule::inner_attribute
+        ));
+        let span = Span::from(&pair);
+        let mut it = pair.into_inner();
+        let level = match it.next().unwrap().as_str() {
+            "allow" => Level::Allow,
+            "warn" => Level::Warn,
+            "deny" => Level::Deny,
+            _ => unreachable!("Corrupt grammar"),
+        };
+        let lints = it
+            .map(LintName::parse)
+            .collect::<Result<Arc<[LintName]>, RichError>>()?;
+        Ok(Self { level, lints, span })
     }
 }
 
@@ -904,6 +1205,8 @@ impl PestParse for Item {
         match pair.as_rule() {
             Rule::type_alias => TypeAlias::parse(pair).map(Item::TypeAlias),
             Rule::function => Function::parse(pair).map(Item::Function),
+            Rule::const_item => Const::parse(pair).map(Item::Const),
+            Rule::use_decl => Use::parse(pair).map(Item::Use),
             _ => Ok(Self::Module),
         }
     }
@@ -915,7 +1218,14 @@ impl PestParse for Function {
     fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Self, RichError> {
         assert!(matches!(pair.as_rule(), Self::RULE));
         let span = Span::from(&pair);
-        let mut it = pair.into_inner();
+        let mut it = pair.into_inner().peekable();
+        let mut attributes = Vec::new();
+        while let Some(pair) = it.next_if(|pair| matches!(pair.as_rule(), Rule::attribute)) {
+            attributes.push(Attribute::parse(pair)?);
+        }
+        let is_const = it
+            .next_if(|pair| matches!(pair.as_rule(), Rule::const_keyword))
+            .is_some();
         let _fn_keyword = it.next().unwrap();
         let name = FunctionName::parse(it.next().unwrap())?;
         let params = {
@@ -938,6 +1248,8 @@ impl PestParse for Function {
         let body = Expression::parse(it.next().unwrap())?;
 
         Ok(Self {
+            attributes: Arc::from(attributes),
+            is_const,
             name,
             params,
             ret,
@@ -953,9 +1265,15 @@ impl PestParse for FunctionParam {
     fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Self, RichError> {
         assert!(matches!(pair.as_rule(), Self::RULE));
         let mut it = pair.into_inner();
-        let identifier = Identifier::parse(it.next().unwrap())?;
+        let identifier_pair = it.next().unwrap();
+        let span = Span::from(&identifier_pair);
+        let identifier = Identifier::parse(identifier_pair)?;
         let ty = AliasedType::parse(it.next().unwrap())?;
-        Ok(Self { identifier, ty })
+        Ok(Self {
+            identifier,
+            ty,
+            span,
+        })
     }
 }
 
@@ -1020,7 +1338,15 @@ impl PestParse for Assignment {
         let span = Span::from(&pair);
         let mut it = pair.into_inner();
         let _let_keyword = it.next().unwrap();
-        let pattern = Pattern::parse(it.next().unwrap())?;
+        let pattern_pair = it.next().unwrap();
+        let identifier_spans = pattern_pair
+            .clone()
+            .into_inner()
+            .flatten()
+            .filter(|pair| matches!(pair.as_rule(), Rule::variable_pattern))
+            .map(|pair| Span::from(&pair))
+            .collect();
+        let pattern = Pattern::parse(pattern_pair)?;
         let ty = AliasedType::parse(it.next().unwrap())?;
         let expression = Expression::parse(it.next().unwrap())?;
         Ok(Assignment {
@@ -1028,6 +1354,7 @@ impl PestParse for Assignment {
             ty,
             expression,
             span,
+            identifier_spans,
         })
     }
 }
@@ -1040,15 +1367,23 @@ impl PestParse for Call {
         let span = Span::from(&pair);
         let mut it = pair.into_inner();
         let name = CallName::parse(it.next().unwrap())?;
-        let args = {
-            let pair = it.next().unwrap();
-            debug_assert!(matches!(pair.as_rule(), Rule::call_args));
-            pair.into_inner()
-                .map(Expression::parse)
-                .collect::<Result<Arc<[Expression]>, RichError>>()?
-        };
+        let mut args = Vec::new();
+        let mut message = None;
+        let args_pair = it.next().unwrap();
+        debug_assert!(matches!(args_pair.as_rule(), Rule::call_args));
+        for pair in args_pair.into_inner() {
+            match pair.as_rule() {
+                Rule::string_literal => message = Some(parse_string_literal(pair)),
+                _ => args.push(Expression::parse(pair)?),
+            }
+        }
 
-        Ok(Self { name, args, span })
+        Ok(Self {
+            name,
+            args: args.into(),
+            message,
+            span,
+        })
     }
 }
 
@@ -1089,14 +1424,7 @@ impl PestParse for CallName {
             Rule::array_fold => {
                 let mut it = pair.into_inner();
                 let name = FunctionName::parse(it.next().unwrap())?;
-                let non_zero_usize_parse =
-                    |pair: pest::iterators::Pair<Rule>| -> Result<NonZeroUsize, RichError> {
-                        let size = pair.as_str().parse::<usize>().with_span(&pair)?;
-                        NonZeroUsize::new(size)
-                            .ok_or(Error::ArraySizeNonZero(size))
-                            .with_span(&pair)
-                    };
-                let size = non_zero_usize_parse(it.next().unwrap())?;
+                let size = parse_array_size(it.next().unwrap())?;
                 Ok(Self::ArrayFold(name, size))
             }
             Rule::for_while => {
@@ -1104,12 +1432,72 @@ impl PestParse for CallName {
                 let name = FunctionName::parse(it.next().unwrap())?;
                 Ok(Self::ForWhile(name))
             }
+            Rule::disconnect => {
+                let mut it = pair.into_inner();
+                let name = FunctionName::parse(it.next().unwrap())?;
+                let delegate = WitnessName::parse(it.next().unwrap())?;
+                Ok(Self::Disconnect(name, delegate))
+            }
+            Rule::sha256 => {
+                let size = parse_array_size(pair.into_inner().next().unwrap())?;
+                Ok(Self::Sha256(size))
+            }
+            Rule::sha256_add => {
+                let size = parse_array_size(pair.into_inner().next().unwrap())?;
+                Ok(Self::Sha256Add(size))
+            }
+            Rule::array_concat => {
+                let mut it = pair.into_inner();
+                let size_l = parse_array_size(it.next().unwrap())?;
+                let size_r = parse_array_size(it.next().unwrap())?;
+                Ok(Self::ArrayConcat(size_l, size_r))
+            }
+            Rule::array_map => {
+                let mut it = pair.into_inner();
+                let name = FunctionName::parse(it.next().unwrap())?;
+                let size = parse_array_size(it.next().unwrap())?;
+                Ok(Self::ArrayMap(name, size))
+            }
+            Rule::array_zip => Ok(Self::ArrayZip),
+            Rule::array_reverse => Ok(Self::ArrayReverse),
+            Rule::array_contains => {
+                let mut it = pair.into_inner();
+                let ty = AliasedType::parse(it.next().unwrap())?;
+                let size = parse_array_size(it.next().unwrap())?;
+                Ok(Self::ArrayContains(ty, size))
+            }
+            Rule::array_get => {
+                let mut it = pair.into_inner();
+                let ty = AliasedType::parse(it.next().unwrap())?;
+                let size = parse_array_size(it.next().unwrap())?;
+                Ok(Self::ArrayGet(ty, size))
+            }
+            Rule::list_len => {
+                let mut it = pair.into_inner();
+                let ty = AliasedType::parse(it.next().unwrap())?;
+                let bound = NonZeroPow2Usize::parse(it.next().unwrap())?;
+                Ok(Self::ListLen(ty, bound))
+            }
+            Rule::list_push => Ok(Self::ListPush),
+            Rule::list_get => {
+                let bound = NonZeroPow2Usize::parse(pair.into_inner().next().unwrap())?;
+                Ok(Self::ListGet(bound))
+            }
             Rule::function_name => FunctionName::parse(pair).map(Self::Custom),
             _ => panic!("Corrupt grammar"),
         }
     }
 }
 
+/// Parse the non-zero size of an array.
+fn parse_array_size(pair: pest::iterators::Pair<Rule>) -> Result<NonZeroUsize, RichError> {
+    assert!(matches!(pair.as_rule(), Rule::array_size));
+    let size = pair.as_str().parse::<usize>().with_span(&pair)?;
+    NonZeroUsize::new(size)
+        .ok_or(Error::ArraySizeNonZero(size))
+        .with_span(&pair)
+}
+
 impl PestParse for JetName {
     const RULE: Rule = Rule::jet;
 
@@ -1134,6 +1522,39 @@ impl PestParse for TypeAlias {
     }
 }
 
+impl PestParse for Const {
+    const RULE: Rule = Rule::const_item;
+
+    fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Self, RichError> {
+        assert!(matches!(pair.as_rule(), Self::RULE));
+        let span = Span::from(&pair);
+        let mut it = pair.into_inner();
+        let _const_keyword = it.next().unwrap();
+        let name = Identifier::parse(it.next().unwrap())?;
+        let ty = AliasedType::parse(it.next().unwrap())?;
+        let expression = Expression::parse(it.next().unwrap())?;
+        Ok(Self {
+            name,
+            ty,
+            expression,
+            span,
+        })
+    }
+}
+
+impl PestParse for Use {
+    const RULE: Rule = Rule::use_decl;
+
+    fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Self, RichError> {
+        assert!(matches!(pair.as_rule(), Self::RULE));
+        let span = Span::from(&pair);
+        let mut it = pair.into_inner();
+        let _use_keyword = it.next().unwrap();
+        let module = Identifier::parse(it.next().unwrap())?;
+        Ok(Self { module, span })
+    }
+}
+
 impl PestParse for Expression {
     const RULE: Rule = Rule::expression;
 
@@ -1173,7 +1594,13 @@ impl PestParse for SingleExpression {
         assert!(matches!(pair.as_rule(), Self::RULE));
 
         let span = Span::from(&pair);
-        let inner_pair = pair.into_inner().next().unwrap();
+        let mut it = pair.into_inner();
+        let inner_pair = it.next().unwrap();
+        let suffixes: Vec<_> = it.collect();
+        let inner_span = match suffixes.is_empty() {
+            true => span,
+            false => Span::from(&inner_pair),
+        };
 
         let inner = match inner_pair.as_rule() {
             Rule::left_expr => {
@@ -1206,6 +1633,7 @@ impl PestParse for SingleExpression {
                 Hexadecimal::parse(inner_pair).map(SingleExpressionInner::Hexadecimal)?
             }
             Rule::dec_literal => Decimal::parse(inner_pair).map(SingleExpressionInner::Decimal)?,
+            Rule::byte_string => SingleExpressionInner::Hexadecimal(parse_byte_string(inner_pair)),
             Rule::witness_expr => SingleExpressionInner::Witness(WitnessName::parse(
                 inner_pair.into_inner().next().unwrap(),
             )?),
@@ -1239,13 +1667,61 @@ impl PestParse for SingleExpression {
                     .collect::<Result<Arc<_>, _>>()?;
                 SingleExpressionInner::List(elements)
             }
+            Rule::return_expr => {
+                let mut it = inner_pair.into_inner();
+                let _return_keyword = it.next().unwrap();
+                it.next()
+                    .map(|pair| Expression::parse(pair).map(Arc::new))
+                    .transpose()
+                    .map(SingleExpressionInner::Return)?
+            }
             _ => unreachable!("Corrupt grammar"),
         };
 
-        Ok(SingleExpression { inner, span })
+        // Apply the suffixes from left to right: `a[0][1]?` is `((a[0])[1])?`
+        let mut single = SingleExpression {
+            inner,
+            span: inner_span,
+        };
+        for suffix in suffixes {
+            let span = Span::new(span.start, Span::from(&suffix).end);
+            let operand = Arc::new(Expression {
+                span: single.span,
+                inner: ExpressionInner::Single(single),
+            });
+            let inner = match suffix.as_rule() {
+                Rule::try_suffix => SingleExpressionInner::Try(operand),
+                Rule::index_suffix => {
+                    let index_pair = suffix.into_inner().next().unwrap();
+                    match index_pair.as_rule() {
+                        Rule::index_range => {
+                            let mut it = index_pair.into_inner();
+                            let start = parse_array_index(it.next().unwrap())?;
+                            let end = parse_array_index(it.next().unwrap())?;
+                            SingleExpressionInner::Slice(operand, start, end)
+                        }
+                        Rule::expression => {
+                            let index = Expression::parse(index_pair).map(Arc::new)?;
+                            SingleExpressionInner::Index(operand, index)
+                        }
+                        _ => unreachable!("Corrupt grammar"),
+                    }
+                }
+                _ => unreachable!("Corrupt grammar"),
+            };
+            single = SingleExpression { inner, span };
+        }
+
+        Ok(single)
     }
 }
 
+/// Parse an index into an array.
+fn parse_array_index(pair: pest::iterators::Pair<Rule>) -> Result<usize, RichError> {
+    assert!(matches!(pair.as_rule(), Rule::array_size));
+    pair.as_str().parse::<usize>().with_span(&pair)
+}
+
 impl PestParse for Decimal {
     const RULE: Rule = Rule::dec_literal;
 
@@ -1276,6 +1752,83 @@ impl PestParse for Hexadecimal {
     }
 }
 
+/// Parse a byte string literal into the equivalent hexadecimal literal.
+///
+/// `b"ab\n"` is the same as `0x61620a`.
+fn parse_byte_string(pair: pest::iterators::Pair<Rule>) -> Hexadecimal {
+    assert!(matches!(pair.as_rule(), Rule::byte_string));
+    let content = pair
+        .as_str()
+        .strip_prefix("b\"")
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap();
+    let mut hexadecimal = String::with_capacity(content.len() * 2);
+    for byte in unescape_bytes(content) {
+        hexadecimal.push_str(&format!("{byte:02x}"));
+    }
+    Hexadecimal::from_str_unchecked(hexadecimal.as_str())
+}
+
+/// Parse a string literal, such as the message of `assert!(cond, "message")`.
+///
+/// Each byte of the literal becomes one character of the string.
+fn parse_string_literal(pair: pest::iterators::Pair<Rule>) -> Arc<str> {
+    assert!(matches!(pair.as_rule(), Rule::string_literal));
+    let content = pair
+        .as_str()
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap();
+    unescape_bytes(content)
+        .into_iter()
+        .map(char::from)
+        .collect::<String>()
+        .into()
+}
+
+/// Write the string literal that parses to the given `string`.
+///
+/// Characters that are not printable ASCII are escaped.
+fn write_string_literal(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in string.chars() {
+        match c {
+            '\\' => write!(f, "\\\\")?,
+            '"' => write!(f, "\\\"")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            ' '..='~' => write!(f, "{c}")?,
+            _ => write!(f, "\\x{:02x}", u32::from(c) & 0xff)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Resolve the escape sequences of the content of a byte string or string literal.
+fn unescape_bytes(content: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(content.len());
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        let byte = match c {
+            '\\' => match chars.next().unwrap() {
+                'n' => b'\n',
+                'r' => b'\r',
+                't' => b'\t',
+                '0' => b'\0',
+                'x' => {
+                    let digits: String = chars.by_ref().take(2).collect();
+                    u8::from_str_radix(&digits, 16).unwrap()
+                }
+                escaped => escaped as u8,
+            },
+            _ => c as u8,
+        };
+        bytes.push(byte);
+    }
+    bytes
+}
+
 impl PestParse for Match {
     const RULE: Rule = Rule::match_expr;
 
@@ -1316,11 +1869,20 @@ impl PestParse for MatchArm {
     fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Self, RichError> {
         assert!(matches!(pair.as_rule(), Self::RULE));
         let mut it = pair.into_inner();
-        let pattern = MatchPattern::parse(it.next().unwrap())?;
+        let pattern_pair = it.next().unwrap();
+        // Patterns that bind a variable start with its identifier
+        let variable_span = pattern_pair
+            .clone()
+            .into_inner()
+            .flat_map(pest::iterators::Pair::into_inner)
+            .find(|pair| matches!(pair.as_rule(), Rule::identifier))
+            .map(|pair| Span::from(&pair));
+        let pattern = MatchPattern::parse(pattern_pair)?;
         let expression = Expression::parse(it.next().unwrap()).map(Arc::new)?;
         Ok(MatchArm {
             pattern,
             expression,
+            variable_span,
         })
     }
 }
@@ -1651,6 +2213,18 @@ impl AsRef<Span> for TypeAlias {
     }
 }
 
+impl AsRef<Span> for Const {
+    fn as_ref(&self) -> &Span {
+        &self.span
+    }
+}
+
+impl AsRef<Span> for Use {
+    fn as_ref(&self) -> &Span {
+        &self.span
+    }
+}
+
 impl AsRef<Span> for Expression {
     fn as_ref(&self) -> &Span {
         &self.span
@@ -1697,16 +2271,51 @@ impl AsRef<Span> for ModuleAssignment {
 impl<'a> arbitrary::Arbitrary<'a> for Program {
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
         let len = u.int_in_range(0..=3)?;
+        let attributes = (0..u.int_in_range(0..=1)?)
+            .map(|_| Attribute::arbitrary(u))
+            .collect::<arbitrary::Result<Arc<[Attribute]>>>()?;
         let items = (0..len)
             .map(|_| Item::arbitrary(u))
             .collect::<arbitrary::Result<Arc<[Item]>>>()?;
         Ok(Self {
+            attributes,
             items,
             span: Span::DUMMY,
         })
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Attribute {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use arbitrary::Arbitrary;
+
+        let level = Level::arbitrary(u)?;
+        let len = u.int_in_range(1..=3)?;
+        let lints = (0..len)
+            .map(|_| LintName::arbitrary(u))
+            .collect::<arbitrary::Result<Arc<[LintName]>>>()?;
+        Ok(Self {
+            level,
+            lints,
+            span: Span::DUMMY,
+        })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FunctionParam {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let identifier = Identifier::arbitrary(u)?;
+        let ty = AliasedType::arbitrary(u)?;
+        Ok(Self {
+            identifier,
+            ty,
+            span: Span::DUMMY,
+        })
+    }
+}
+
 #[cfg(feature = "arbitrary")]
 impl<'a> arbitrary::Arbitrary<'a> for Function {
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
@@ -1719,6 +2328,10 @@ impl crate::ArbitraryRec for Function {
     fn arbitrary_rec(u: &mut arbitrary::Unstructured, budget: usize) -> arbitrary::Result<Self> {
         use arbitrary::Arbitrary;
 
+        let attributes = (0..u.int_in_range(0..=1)?)
+            .map(|_| Attribute::arbitrary(u))
+            .collect::<arbitrary::Result<Arc<[Attribute]>>>()?;
+        let is_const = bool::arbitrary(u)?;
         let name = FunctionName::arbitrary(u)?;
         let len = u.int_in_range(0..=3)?;
         let params = (0..len)
@@ -1727,6 +2340,8 @@ impl crate::ArbitraryRec for Function {
         let ret = Option::<AliasedType>::arbitrary(u)?;
         let body = Expression::arbitrary_rec(u, budget).map(Expression::into_block)?;
         Ok(Self {
+            attributes,
+            is_const,
             name,
             params,
             ret,
@@ -1736,6 +2351,23 @@ impl crate::ArbitraryRec for Function {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Const {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use arbitrary::Arbitrary;
+
+        let name = Identifier::arbitrary(u)?;
+        let ty = AliasedType::arbitrary(u)?;
+        let expression = <Expression as crate::ArbitraryRec>::arbitrary_rec(u, 3)?;
+        Ok(Self {
+            name,
+            ty,
+            expression,
+            span: Span::DUMMY,
+        })
+    }
+}
+
 #[cfg(feature = "arbitrary")]
 impl crate::ArbitraryRec for Expression {
     fn arbitrary_rec(u: &mut arbitrary::Unstructured, budget: usize) -> arbitrary::Result<Self> {
@@ -1787,12 +2419,18 @@ impl crate::ArbitraryRec for Assignment {
         let pattern = Pattern::arbitrary(u)?;
         let ty = AliasedType::arbitrary(u)?;
         let expression = Expression::arbitrary_rec(u, budget)?;
+        let identifier_spans = pattern
+            .pre_order_iter()
+            .filter(|node| matches!(node, Pattern::Identifier(_)))
+            .map(|_| Span::DUMMY)
+            .collect();
 
         Ok(Self {
             pattern,
             ty,
             expression,
             span: Span::DUMMY,
+            identifier_spans,
         })
     }
 }
@@ -1880,9 +2518,17 @@ impl crate::ArbitraryRec for Call {
         let args = (0..len)
             .map(|_| Expression::arbitrary_rec(u, budget))
             .collect::<arbitrary::Result<Arc<[Expression]>>>()?;
+        let message = match name {
+            CallName::Assert | CallName::Panic if bool::arbitrary(u)? => {
+                let bytes = Vec::<u8>::arbitrary(u)?;
+                Some(bytes.into_iter().map(char::from).collect::<String>().into())
+            }
+            _ => None,
+        };
         Ok(Self {
             name,
             args,
+            message,
             span: Span::DUMMY,
         })
     }
@@ -1918,10 +2564,12 @@ impl crate::ArbitraryRec for Match {
         Ok(Self {
             scrutinee,
             left: MatchArm {
+                variable_span: pat_l.as_variable().map(|_| Span::DUMMY),
                 pattern: pat_l,
                 expression: expr_l,
             },
             right: MatchArm {
+                variable_span: pat_r.as_variable().map(|_| Span::DUMMY),
                 pattern: pat_r,
                 expression: expr_r,
             },
@@ -1,10 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
 
 use serde::{de, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use simplicity::Cmr;
 
+use crate::debug::{DebugSymbols, TrackedCall, TrackedCallName, TrackedFunction, TrackedMatch};
+use crate::error::Span;
 use crate::parse::ParseFromStr;
-use crate::str::WitnessName;
+use crate::str::{FunctionName, WitnessName};
 use crate::types::ResolvedType;
 use crate::value::Value;
 use crate::witness::{Arguments, WitnessValues};
@@ -136,7 +141,7 @@ impl<'de> Deserialize<'de> for WitnessName {
     }
 }
 
-struct WitnessMapSerializer<'a>(&'a HashMap<WitnessName, Value>);
+struct WitnessMapSerializer<'a>(&'a BTreeMap<WitnessName, Value>);
 
 impl<'a> Serialize for WitnessMapSerializer<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -183,9 +188,199 @@ impl Serialize for Arguments {
     }
 }
 
+/// Version of the file format of serialized debug symbols.
+///
+/// Increment the version whenever the format changes.
+const DEBUG_SYMBOLS_VERSION: u32 = 1;
+
+/// File format of serialized debug symbols.
+///
+/// The maps are keyed by hex-encoded CMRs and are sorted, so the output is deterministic.
+/// The hex-encoded CMR of the compiled program is omitted for empty debug symbols.
+#[derive(Serialize, Deserialize)]
+struct DebugSymbolsFile {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    program: Option<String>,
+    calls: BTreeMap<String, TrackedCallEntry>,
+    matches: BTreeMap<String, TrackedMatchEntry>,
+    functions: BTreeMap<String, TrackedFunctionEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrackedCallEntry {
+    text: String,
+    span: Span,
+    name: TrackedCallNameEntry,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum TrackedCallNameEntry {
+    Assert {
+        message: Option<String>,
+    },
+    Panic {
+        message: Option<String>,
+    },
+    Jet,
+    UnwrapLeft {
+        #[serde(rename = "type")]
+        ty: String,
+    },
+    UnwrapRight {
+        #[serde(rename = "type")]
+        ty: String,
+    },
+    Unwrap,
+    ArrayIndex {
+        #[serde(rename = "type")]
+        ty: String,
+    },
+    Debug {
+        #[serde(rename = "type")]
+        ty: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrackedMatchEntry {
+    span: Span,
+    left: Span,
+    right: Span,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrackedFunctionEntry {
+    name: String,
+    span: Span,
+}
+
+impl From<&TrackedCallName> for TrackedCallNameEntry {
+    fn from(name: &TrackedCallName) -> Self {
+        match name {
+            TrackedCallName::Assert(msg) => Self::Assert {
+                message: msg.as_deref().map(str::to_string),
+            },
+            TrackedCallName::Panic(msg) => Self::Panic {
+                message: msg.as_deref().map(str::to_string),
+            },
+            TrackedCallName::Jet => Self::Jet,
+            TrackedCallName::UnwrapLeft(ty) => Self::UnwrapLeft { ty: ty.to_string() },
+            TrackedCallName::UnwrapRight(ty) => Self::UnwrapRight { ty: ty.to_string() },
+            TrackedCallName::Unwrap => Self::Unwrap,
+            TrackedCallName::ArrayIndex(ty) => Self::ArrayIndex { ty: ty.to_string() },
+            TrackedCallName::Debug(ty) => Self::Debug { ty: ty.to_string() },
+        }
+    }
+}
+
+impl TryFrom<TrackedCallNameEntry> for TrackedCallName {
+    type Error = String;
+
+    fn try_from(entry: TrackedCallNameEntry) -> Result<Self, Self::Error> {
+        let ty = |s: &str| ResolvedType::parse_from_str(s).map_err(|e| e.to_string());
+        match entry {
+            TrackedCallNameEntry::Assert { message } => Ok(Self::Assert(message.map(Arc::from))),
+            TrackedCallNameEntry::Panic { message } => Ok(Self::Panic(message.map(Arc::from))),
+            TrackedCallNameEntry::Jet => Ok(Self::Jet),
+            TrackedCallNameEntry::UnwrapLeft { ty: s } => ty(&s).map(Self::UnwrapLeft),
+            TrackedCallNameEntry::UnwrapRight { ty: s } => ty(&s).map(Self::UnwrapRight),
+            TrackedCallNameEntry::Unwrap => Ok(Self::Unwrap),
+            TrackedCallNameEntry::ArrayIndex { ty: s } => ty(&s).map(Self::ArrayIndex),
+            TrackedCallNameEntry::Debug { ty: s } => ty(&s).map(Self::Debug),
+        }
+    }
+}
+
+fn parse_cmr<E: de::Error>(s: &str) -> Result<Cmr, E> {
+    Cmr::from_str(s).map_err(|e| E::custom(format!("Invalid CMR `{s}`: {e}")))
+}
+
+impl Serialize for DebugSymbols {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let file = DebugSymbolsFile {
+            version: DEBUG_SYMBOLS_VERSION,
+            program: self.program_cmr().map(|cmr| cmr.to_string()),
+            calls: self
+                .calls()
+                .map(|(cmr, call)| {
+                    let entry = TrackedCallEntry {
+                        text: call.text().to_string(),
+                        span: *call.span(),
+                        name: TrackedCallNameEntry::from(call.name()),
+                    };
+                    (cmr.to_string(), entry)
+                })
+                .collect(),
+            matches: self
+                .matches_with_cmrs()
+                .map(|(cmr, tracked_match)| {
+                    let entry = TrackedMatchEntry {
+                        span: *tracked_match.span(),
+                        left: *tracked_match.left(),
+                        right: *tracked_match.right(),
+                    };
+                    (cmr.to_string(), entry)
+                })
+                .collect(),
+            functions: self
+                .functions_with_cmrs()
+                .map(|(cmr, function)| {
+                    let entry = TrackedFunctionEntry {
+                        name: function.name().to_string(),
+                        span: *function.span(),
+                    };
+                    (cmr.to_string(), entry)
+                })
+                .collect(),
+        };
+        file.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DebugSymbols {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let file = DebugSymbolsFile::deserialize(deserializer)?;
+        if file.version != DEBUG_SYMBOLS_VERSION {
+            return Err(de::Error::custom(format!(
+                "Unsupported version {} of debug symbols (expected version {DEBUG_SYMBOLS_VERSION})",
+                file.version
+            )));
+        }
+
+        let mut debug_symbols = DebugSymbols::default();
+        if let Some(cmr) = file.program {
+            debug_symbols.set_program_cmr(parse_cmr(&cmr)?);
+        }
+        for (cmr, entry) in file.calls {
+            let name = TrackedCallName::try_from(entry.name).map_err(de::Error::custom)?;
+            let call = TrackedCall::new(Arc::from(entry.text), entry.span, name);
+            debug_symbols.insert_call(parse_cmr(&cmr)?, call);
+        }
+        for (cmr, entry) in file.matches {
+            let tracked_match = TrackedMatch::new(entry.span, entry.left, entry.right);
+            debug_symbols.insert_match(parse_cmr(&cmr)?, tracked_match);
+        }
+        for (cmr, entry) in file.functions {
+            let name = FunctionName::parse_from_str(&entry.name).map_err(de::Error::custom)?;
+            let function = TrackedFunction::new(name, entry.span);
+            debug_symbols.insert_function(parse_cmr(&cmr)?, function);
+        }
+        Ok(debug_symbols)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Arguments, TemplateProgram};
 
     #[test]
     fn witness_serde_duplicate_assignment() {
@@ -199,4 +394,49 @@ mod tests {
             Err(error) => assert!(error.to_string().contains("Name `A` is assigned twice")),
         }
     }
+
+    #[test]
+    fn debug_symbols_serde_roundtrip() {
+        let prog_text = r#"fn checked_div(a: u32, b: u32) -> u32 {
+    assert!(jet::lt_32(0, b), "division by zero");
+    let (q, _): (u32, u32) = jet::div_mod_32(a, b);
+    q
+}
+
+fn main() {
+    let x: Either<u32, u16> = Left(8);
+    let y: u32 = match x {
+        Left(a: u32) => checked_div(a, 2),
+        Right(b: u16) => <u16>::into(dbg!(b)),
+    };
+    let z: Option<u32> = Some(y);
+    assert!(jet::eq_32(unwrap(z), 4));
+}
+"#;
+        let program = TemplateProgram::new(prog_text)
+            .unwrap()
+            .instantiate(Arguments::default(), true)
+            .unwrap();
+        let debug_symbols = program.debug_symbols();
+        assert!(debug_symbols.calls().next().is_some());
+        assert!(debug_symbols.matches().next().is_some());
+        assert!(debug_symbols.functions().next().is_some());
+
+        let json = serde_json::to_string(debug_symbols).unwrap();
+        let program_cmr = program.commit().cmr();
+        assert_eq!(Some(program_cmr), debug_symbols.program_cmr());
+        assert!(json.starts_with(&format!(r#"{{"version":1,"program":"{program_cmr}","#)));
+        let decoded = serde_json::from_str::<DebugSymbols>(&json).unwrap();
+        assert_eq!(debug_symbols, &decoded);
+        assert_eq!(json, serde_json::to_string(&decoded).unwrap());
+    }
+
+    #[test]
+    fn debug_symbols_serde_unsupported_version() {
+        let s = r#"{ "version": 2, "calls": {}, "matches": {}, "functions": {} }"#;
+        match serde_json::from_str::<DebugSymbols>(s) {
+            Ok(_) => panic!("Unsupported version was falsely accepted"),
+            Err(error) => assert!(error.to_string().contains("Unsupported version 2")),
+        }
+    }
 }
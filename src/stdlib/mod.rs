@@ -0,0 +1,429 @@
+//! Standard library of SimplicityHL.
+//!
+//! Each module of the standard library is a SimplicityHL source file that is shipped with the compiler.
+//! A program imports a module with `use std::<name>;`.
+//!
+//! Modules are linked into the program before the creation of the AST.
+//! The source of each imported module is appended to the program text,
+//! and the items of the imported modules are placed in front of the items of the program,
+//! so that the program can call the functions of the module.
+//! Because the program text stays at the start of the linked text,
+//! the positions inside the program remain unchanged.
+
+use std::cmp::Reverse;
+use std::sync::{Arc, OnceLock};
+
+use crate::error::{Error, RichError, Span, WithFile, WithSpan};
+use crate::parse::{self, ParseFromStr};
+
+/// Source files of the modules of the standard library, as pairs of name and source.
+const MODULE_FILES: [(&str, &str); 2] = [
+    ("prelude", include_str!("prelude.simf")),
+    ("script", include_str!("script.simf")),
+];
+
+/// Largest number of public keys of the multisig functions of the prelude.
+const MAX_MULTISIG_KEYS: usize = 20;
+
+/// Definition of the multisig functions of the prelude.
+///
+/// There is one function `k_of_n_multisig_{N}` for each number of public keys `{N}`,
+/// because the length of an array is part of its type.
+const K_OF_N_MULTISIG: &str = "
+// Check that at least k of the {N} public keys signed the transaction.
+// Each public key is paired with its signature, if there is one.
+#[allow(unused_functions)]
+fn k_of_n_multisig_{N}(k: u8, signers: [(Pubkey, Option<Signature>); {N}]) {
+    let counter: u8 = array_fold::<checksig_add, {N}>(signers, 0);
+    assert!(jet::le_8(k, counter));
+}
+";
+
+/// Return the modules of the standard library, as pairs of name and source.
+///
+/// The source of the prelude is completed with the generated multisig functions.
+fn modules_with_sources() -> &'static [(&'static str, String)] {
+    static MODULES: OnceLock<Vec<(&'static str, String)>> = OnceLock::new();
+    MODULES.get_or_init(|| {
+        MODULE_FILES
+            .iter()
+            .map(|(name, source)| {
+                let mut source = source.to_string();
+                if *name == "prelude" {
+                    for n in 1..=MAX_MULTISIG_KEYS {
+                        source.push_str(&K_OF_N_MULTISIG.replace("{N}", &n.to_string()));
+                    }
+                }
+                (*name, source)
+            })
+            .collect()
+    })
+}
+
+/// Return the names of the modules of the standard library.
+pub fn modules() -> impl Iterator<Item = &'static str> {
+    modules_with_sources().iter().map(|(name, _)| *name)
+}
+
+/// Return the source of the module of the standard library with the given `name`.
+pub fn module(name: &str) -> Option<&'static str> {
+    modules_with_sources()
+        .iter()
+        .find(|(module_name, _)| *module_name == name)
+        .map(|(_, source)| source.as_str())
+}
+
+/// Parse the given program `file` and link the modules that it imports.
+///
+/// Return the linked text and the parsed program.
+/// Errors refer to the linked text.
+///
+/// ## Errors
+///
+/// - The program or one of the imported modules is not a valid SimplicityHL program.
+/// - The program imports a module that does not exist.
+pub fn parse_and_link(file: &str) -> Result<(Arc<str>, parse::Program), RichError> {
+    let mut text = file.to_string();
+    // Name of each linked module and the line where its source starts
+    let mut linked: Vec<(&str, usize)> = Vec::new();
+
+    let program = loop {
+        let program = parse::Program::parse_from_str(&text)?;
+        let mut imported_new_module = false;
+        for item in program.items() {
+            let parse::Item::Use(import) = item else {
+                continue;
+            };
+            let name = import.module().as_inner();
+            if linked.iter().any(|(linked_name, _)| *linked_name == name) {
+                continue;
+            }
+            let (name, source) = modules_with_sources()
+                .iter()
+                .find(|(module_name, _)| *module_name == name)
+                .ok_or(Error::ModuleUndefined(import.module().clone()))
+                .with_span(import)
+                .with_file(text.as_str())?;
+            text.push('\n');
+            linked.push((name, text.matches('\n').count() + 1));
+            text.push_str(source);
+            imported_new_module = true;
+        }
+        if !imported_new_module {
+            break program;
+        }
+    };
+
+    let program = place_modules_first(&program, &linked);
+    Ok((Arc::from(text), program))
+}
+
+/// Place the items of the linked modules in front of the items of the program.
+///
+/// Modules that are linked later are placed further to the front,
+/// so each module comes after the modules that it imports.
+fn place_modules_first(program: &parse::Program, linked: &[(&str, usize)]) -> parse::Program {
+    let segment = |item: &parse::Item| -> usize {
+        let span: Span = match item {
+            parse::Item::TypeAlias(alias) => *alias.as_ref(),
+            parse::Item::Function(function) => *function.as_ref(),
+            parse::Item::Const(constant) => *constant.as_ref(),
+            parse::Item::Use(import) => *import.as_ref(),
+            parse::Item::Module => return 0,
+        };
+        linked
+            .iter()
+            .filter(|(_, first_line)| *first_line <= span.start.line.get())
+            .count()
+    };
+    let mut items = program.items().to_vec();
+    items.sort_by_key(|item| Reverse(segment(item)));
+    program.with_items(Arc::from(items))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::tests::{TestCase, SCRIPT_2_OF_3};
+    use crate::WitnessValues;
+
+    /// Companion script `OP_PUSHDATA1 <0xabcd> <1 * G> OP_CHECKSIG`.
+    const SCRIPT_PUSHDATA: &str =
+        "4c02abcd210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ac";
+
+    /// Signature of 1 * G over the SIGHASH_ALL of the default dummy environment.
+    const SIG_1: &str = "0xf74b3ca574647f8595624b129324afa2f38b598a9c1c7cfc5f08a9c036ec5acd3c0fbb9ed3dae5ca23a0a65a34b5d6cccdd6ba248985d6041f7b21262b17af6f";
+    /// Signature of 3 * G over the SIGHASH_ALL of the default dummy environment.
+    const SIG_3: &str = "0x29dbeab5628ae472bce3e08728ead1997ef789d4f04b5be39cc08b362dc229f553fd353f8a0acffdfbddd471d15a0dda3b306842416ff246bc07462e5667eb89";
+
+    fn program(module: &str, main: &str) -> TestCase<crate::CompiledProgram> {
+        let file = format!("use std::{module};\n\nfn main() {{\n{main}\n}}");
+        TestCase::program_text(Cow::Owned(file))
+    }
+
+    fn run_with_script(main: &str, script_hex: &str) -> TestCase<crate::SatisfiedProgram> {
+        program("script", main)
+            .with_script(script_hex)
+            .with_witness_values(WitnessValues::default())
+    }
+
+    fn run_prelude(main: &str) -> TestCase<crate::SatisfiedProgram> {
+        program("prelude", main).with_witness_values(WitnessValues::default())
+    }
+
+    #[test]
+    fn modules_are_valid() {
+        for name in modules() {
+            let file = format!("use std::{name};\n\nfn main() {{}}");
+            if let Err(error) = crate::TemplateProgram::new(file) {
+                panic!("Module `{name}` is invalid:\n{error}");
+            }
+        }
+    }
+
+    #[test]
+    fn link_module() {
+        let file = "use std::script;\n\nfn main() {\n    let op: u8 = op_checksig();\n}";
+        let (text, program) = parse_and_link(file).unwrap();
+        assert!(text.starts_with(file));
+        assert!(text.ends_with(module("script").unwrap()));
+
+        let function_names: Vec<_> = program
+            .items()
+            .iter()
+            .filter_map(|item| match item {
+                parse::Item::Function(function) => Some(function.name().as_inner()),
+                _ => None,
+            })
+            .collect();
+        assert!(function_names.contains(&"op_checksig"));
+        assert_eq!(Some(&"main"), function_names.last());
+    }
+
+    #[test]
+    fn link_module_once() {
+        let file = "use std::script;\nuse std::script;\n\nfn main() {}";
+        let (text, _) = parse_and_link(file).unwrap();
+        assert_eq!(1, text.matches("fn op_checksig()").count());
+    }
+
+    #[test]
+    fn undefined_module() {
+        let file = "use std::scripts;\n\nfn main() {}";
+        let error = parse_and_link(file).unwrap_err();
+        assert!(matches!(error.error(), Error::ModuleUndefined(..)));
+        assert_eq!(1, error.span().start.line.get());
+    }
+
+    #[test]
+    fn small_int() {
+        let main = r#"
+    assert!(jet::eq_8(0, unwrap(small_int(op_0()))));
+    assert!(jet::eq_8(1, unwrap(small_int(op_1()))));
+    assert!(jet::eq_8(16, unwrap(small_int(op_16()))));
+    assert!(is_none::<u8>(small_int(op_checksig())));
+    assert!(is_none::<u8>(small_int(op_pushbytes_33())));
+"#;
+        run_with_script(main, SCRIPT_2_OF_3).assert_run_success();
+    }
+
+    #[test]
+    fn iterate_instructions() {
+        let main = r#"
+    assert!(jet::eq_8(1, next_instruction(0)));
+    assert!(jet::eq_8(35, next_instruction(1)));
+    assert!(jet::eq_8(103, nth_instruction(4)));
+    assert!(jet::eq_8(0x53, opcode_at(nth_instruction(4))));
+    assert!(jet::eq_8(69, nth_push(2)));
+"#;
+        run_with_script(main, SCRIPT_2_OF_3).assert_run_success();
+    }
+
+    #[test]
+    fn iterate_pushdata() {
+        let main = r#"
+    assert!(jet::eq_8(4, next_instruction(0)));
+    assert!(jet::eq_8(4, nth_push(1)));
+    assert!(jet::eq_8(op_checksig(), opcode_at(nth_instruction(2))));
+    let pk: Pubkey = 0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798;
+    assert!(jet::eq_256(pk, nth_pubkey(1)));
+"#;
+        run_with_script(main, SCRIPT_PUSHDATA).assert_run_success();
+        run_with_script("    let pk: Pubkey = nth_pubkey(0);", SCRIPT_PUSHDATA)
+            .assert_run_failure();
+    }
+
+    #[test]
+    fn multisig_template() {
+        let main = r#"
+    let (threshold, n): (u8, u8) = multisig_template();
+    assert!(jet::eq_8(2, threshold));
+    assert!(jet::eq_8(3, n));
+    let pk: Pubkey = 0xf9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9;
+    assert!(jet::eq_256(pk, nth_pubkey(2)));
+"#;
+        run_with_script(main, SCRIPT_2_OF_3).assert_run_success();
+        run_with_script(
+            "    let (k, n): (u8, u8) = multisig_template();",
+            SCRIPT_PUSHDATA,
+        )
+        .assert_run_failure();
+    }
+
+    #[test]
+    fn default_script() {
+        let main = r#"
+    let (threshold, n): (u8, u8) = multisig_template();
+    assert!(jet::eq_8(2, threshold));
+    assert!(jet::eq_8(2, n));
+    let pk: Pubkey = 0x5eb4655feae15a304653e27441ca8e8ced2bef89c22ab6b20424b4c07b3d14cc;
+    assert!(jet::eq_256(pk, nth_pubkey(1)));
+"#;
+        program("script", main)
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+    }
+
+    #[test]
+    fn checksig() {
+        let main = format!(
+            r#"
+    let pk: Pubkey = 0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798;
+    checksig(pk, {SIG_1});
+"#
+        );
+        run_prelude(&main).assert_run_success();
+        let main = format!(
+            r#"
+    let pk: Pubkey = 0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798;
+    checksig(pk, {SIG_3});
+"#
+        );
+        run_prelude(&main).assert_run_failure();
+    }
+
+    #[test]
+    fn checksig_from_stack() {
+        // Signature of 1 * G over the SHA256 hash of the big-endian u32 values 1000 and 100000
+        let main = r#"
+    let pk: Pubkey = 0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798;
+    let sig: Signature = 0x90231b8de96a1f940ddcf406fe8389417ca8fb0b03151608e2f94b31b443a7e0d26a12e437df69028f09027c37d5f6742a10c1e8864061d119b8bbce962d26d3;
+    let hasher: Ctx8 = array_fold::<sha256_add_u32, 2>([1000, 100000], jet::sha_256_ctx_8_init());
+    checksig_from_stack(pk, hasher, sig);
+"#;
+        run_prelude(main).assert_run_success();
+    }
+
+    #[test]
+    fn k_of_n_multisig() {
+        let main = format!(
+            r#"
+    let signers: [(Pubkey, Option<Signature>); 3] = [
+        (0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798, Some({SIG_1})),
+        (0xc6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5, None),
+        (0xf9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9, Some({SIG_3})),
+    ];
+    k_of_n_multisig_3(THRESHOLD, signers);
+"#
+        );
+        run_prelude(&main.replace("THRESHOLD", "2")).assert_run_success();
+        run_prelude(&main.replace("THRESHOLD", "3")).assert_run_failure();
+
+        let main = format!(
+            r#"
+    let signers: [(Pubkey, Option<Signature>); 4] = [
+        (0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798, Some({SIG_1})),
+        (0xc6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5, None),
+        (0xf9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9, Some({SIG_3})),
+        (0xe493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13, None),
+    ];
+    k_of_n_multisig_4(2, signers);
+"#
+        );
+        run_prelude(&main).assert_run_success();
+    }
+
+    #[test]
+    fn k_of_n_multisig_sizes() {
+        let prelude = module("prelude").unwrap();
+        for n in 1..=MAX_MULTISIG_KEYS {
+            assert!(prelude.contains(&format!(
+                "fn k_of_n_multisig_{n}(k: u8, signers: [(Pubkey, Option<Signature>); {n}])"
+            )));
+        }
+    }
+
+    #[test]
+    fn sha256() {
+        let main = r#"
+    let zeros_hash: u256 = 0x66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925;
+    assert!(jet::eq_256(zeros_hash, sha256_u256(0)));
+    let bytes: [u8; 3] = [0x61, 0x62, 0x63];
+    let hasher: Ctx8 = array_fold::<sha256_add_u8, 3>(bytes, jet::sha_256_ctx_8_init());
+    let abc: u256 = 0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad;
+    assert!(jet::eq_256(abc, jet::sha_256_ctx_8_finalize(hasher)));
+"#;
+        run_prelude(main).assert_run_success();
+    }
+
+    #[test]
+    fn hash_lock() {
+        let main = r#"
+    let hash: u256 = 0x66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925;
+    hash_lock(0, hash);
+"#;
+        run_prelude(main).assert_run_success();
+        run_prelude(&main.replace("hash_lock(0,", "hash_lock(1,")).assert_run_failure();
+    }
+
+    #[test]
+    fn check_after() {
+        let main = "    check_after(1000);";
+        program("prelude", main)
+            .with_lock_time(1000)
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+        program("prelude", main)
+            .with_lock_time(999)
+            .with_witness_values(WitnessValues::default())
+            .assert_run_failure();
+    }
+
+    #[test]
+    fn check_older() {
+        let main = "    check_older(10);";
+        program("prelude", main)
+            .with_sequence(10)
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+        program("prelude", main)
+            .with_sequence(9)
+            .with_witness_values(WitnessValues::default())
+            .assert_run_failure();
+    }
+
+    #[test]
+    fn output_assertions() {
+        // The fee output of the dummy environment has the explicit amount 1000
+        // and the explicit asset zero
+        let main = r#"
+    assert_output_value(1, 1000);
+    assert_output_value_at_least(1, 999);
+    assert_output_asset(1, 0x0000000000000000000000000000000000000000000000000000000000000000);
+"#;
+        program("prelude", main)
+            .with_fee_output()
+            .with_witness_values(WitnessValues::default())
+            .assert_run_success();
+        program("prelude", "    assert_output_value(1, 999);")
+            .with_fee_output()
+            .with_witness_values(WitnessValues::default())
+            .assert_run_failure();
+        program("prelude", "    assert_output_value(2, 1000);")
+            .with_fee_output()
+            .with_witness_values(WitnessValues::default())
+            .assert_run_failure();
+    }
+}
@@ -83,15 +83,17 @@ wrapped_string!(FunctionName, "function name");
 #[cfg(feature = "arbitrary")]
 impl<'a> arbitrary::Arbitrary<'a> for FunctionName {
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
-        const RESERVED_NAMES: [&str; 11] = [
+        const RESERVED_NAMES: [&str; 13] = [
             "unwrap_left",
             "unwrap_right",
             "for_while",
+            "disconnect",
             "is_none",
             "unwrap",
             "assert",
             "panic",
             "match",
+            "return",
             "into",
             "fold",
             "dbg",
@@ -269,6 +271,13 @@ impl<'a> arbitrary::Arbitrary<'a> for Hexadecimal {
     }
 }
 
+/// The name of a lint.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct LintName(Arc<str>);
+
+wrapped_string!(LintName, "lint name");
+impl_arbitrary_lowercase_alpha!(LintName);
+
 /// The name of a module.
 #[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct ModuleName(Arc<str>);
@@ -1,13 +1,18 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
 use simplicity_unchained::jets::unchained::ElementsExtension;
 
 use simplicity::bit_machine::{ExecTracker, FrameIter, NodeOutput, PruneTracker, SetTracker};
+use simplicity::dag::{Dag, DagLike};
 use simplicity::jet::Jet;
 use simplicity::node::Inner;
-use simplicity::{Ihr, RedeemNode, Value as SimValue, ValueRef};
+use simplicity::{Cost, Ihr, RedeemNode, Value as SimValue, ValueRef};
 
-use crate::debug::{DebugSymbols, TrackedCallName};
+use crate::debug::{DebugSymbols, FallibleCall, TrackedCallName};
 use crate::either::Either;
-use crate::jet::{source_type, target_type};
+use crate::jet::JetSet;
 use crate::str::AliasName;
 use crate::types::AliasedType;
 use crate::value::StructuralValue;
@@ -21,8 +26,8 @@ type DebugSink<'a> = Box<dyn FnMut(&str, &Value) + 'a>;
 
 /// Callback signature for receiving jet execution traces.
 ///
-/// Arguments are: the jet that was executed, its input arguments (if successfully parsed),
-/// and the result (`None` if the jet failed).
+/// Arguments are: the jet that was executed (converted to [`ElementsExtension`]),
+/// its input arguments (if successfully parsed), and the result (`None` if the jet failed).
 type JetTraceSink<'a> = Box<dyn FnMut(ElementsExtension, Option<&[Value]>, Option<Value>) + 'a>;
 
 /// Callback signature for receiving warnings during execution.
@@ -75,6 +80,8 @@ fn default_warning_sink(message: &str) {
 /// - Decodes and forwards `dbg!()` calls to a configurable sink, using
 ///   the provided [`DebugSymbols`] to resolve CMRs to debug information.
 /// - Optionally traces jet invocations with decoded arguments and return values.
+/// - Remembers the fallible call that was executed last, so the cause of a failed
+///   execution can be reported together with its `assert!` or `panic!` message.
 ///
 /// # Example
 ///
@@ -82,13 +89,20 @@ fn default_warning_sink(message: &str) {
 /// let tracker = DefaultTracker::new(&debug_symbols)
 ///     .with_log_level(TrackerLogLevel::Debug);
 ///
-/// let pruned = program.prune_with_tracker(&env, &mut tracker)?;
+/// match program.prune_with_tracker(&env, &mut tracker) {
+///     Ok(pruned) => { /* ... */ }
+///     Err(error) => match tracker.failed_call() {
+///         Some(call) => eprintln!("{error}: {call}"),
+///         None => eprintln!("{error}"),
+///     },
+/// }
 /// ```
 pub struct DefaultTracker<'a> {
     debug_symbols: &'a DebugSymbols,
     debug_sink: Option<DebugSink<'a>>,
     jet_trace_sink: Option<JetTraceSink<'a>>,
     warning_sink: Option<WarningSink<'a>>,
+    last_fallible_call: Option<FallibleCall>,
     inner: SetTracker,
 }
 
@@ -100,6 +114,7 @@ impl<'a> DefaultTracker<'a> {
             debug_sink: None,
             jet_trace_sink: None,
             warning_sink: None,
+            last_fallible_call: None,
             inner: SetTracker::default(),
         }
     }
@@ -146,6 +161,16 @@ impl<'a> DefaultTracker<'a> {
         self.with_warning_sink(default_warning_sink)
     }
 
+    /// Returns the fallible call that was executed last.
+    ///
+    /// If the execution failed, then this is the call that caused the failure,
+    /// such as a failed `assert!` or a `panic!`, including its message.
+    ///
+    /// Fallible calls are only tracked if the program was compiled with debug symbols.
+    pub fn failed_call(&self) -> Option<&FallibleCall> {
+        self.last_fallible_call.as_ref()
+    }
+
     /// Configures the tracker based on the specified log level.
     ///
     /// - [`TrackerLogLevel::None`]: No sinks enabled.
@@ -173,10 +198,10 @@ impl<'a> DefaultTracker<'a> {
     }
 
     /// Handles jet node execution by decoding arguments and results.
-    fn handle_jet(
+    fn handle_jet<J: JetSet>(
         &mut self,
-        node: &RedeemNode<ElementsExtension>,
-        jet: ElementsExtension,
+        node: &RedeemNode<J>,
+        jet: J,
         input: &FrameIter,
         output: &NodeOutput,
     ) {
@@ -199,7 +224,7 @@ impl<'a> DefaultTracker<'a> {
                 // Still call the sink to report the jet execution, but without arguments.
                 let result = Self::parse_jet_result(node, jet, output);
                 if let Some(sink) = self.jet_trace_sink.as_mut() {
-                    sink(jet, None, result);
+                    sink(jet.into_extension(), None, result);
                 }
 
                 return;
@@ -209,14 +234,14 @@ impl<'a> DefaultTracker<'a> {
         let result = Self::parse_jet_result(node, jet, output);
 
         if let Some(sink) = self.jet_trace_sink.as_mut() {
-            sink(jet, Some(&args), result);
+            sink(jet.into_extension(), Some(&args), result);
         }
     }
 
     /// Parses the result of a jet execution from the output frame.
-    fn parse_jet_result(
-        node: &RedeemNode<ElementsExtension>,
-        jet: ElementsExtension,
+    fn parse_jet_result<J: JetSet>(
+        node: &RedeemNode<J>,
+        jet: J,
         output: &NodeOutput,
     ) -> Option<Value> {
         match output.clone() {
@@ -233,7 +258,7 @@ impl<'a> DefaultTracker<'a> {
                 let output_value = SimValue::from_padded_bits(&mut output_frame, target_ty)
                     .expect("output from bit machine is always well-formed");
 
-                let target_ty = resolve_jet_type(&target_type(jet));
+                let target_ty = resolve_jet_type(&jet.target_type());
                 Value::reconstruct(&StructuralValue::from(output_value), &target_ty)
             }
             _ => None,
@@ -248,9 +273,9 @@ impl<'a> DefaultTracker<'a> {
     }
 
     /// Handles debug node execution by resolving symbols and decoding values.
-    fn handle_debug(
+    fn handle_debug<J: Jet>(
         &mut self,
-        node: &RedeemNode<ElementsExtension>,
+        node: &RedeemNode<J>,
         input: &FrameIter,
         cmr: &simplicity::Cmr,
     ) {
@@ -290,11 +315,43 @@ impl<'a> DefaultTracker<'a> {
             sink(debug_value.text(), debug_value.value());
         }
     }
+
+    /// Handles the entry into a fallible call by remembering the call with its input value.
+    fn handle_fallible<J: Jet>(
+        &mut self,
+        node: &RedeemNode<J>,
+        input: &FrameIter,
+        cmr: &simplicity::Cmr,
+    ) {
+        let Some(tracked_call) = self.debug_symbols.get(cmr) else {
+            return;
+        };
+        if let TrackedCallName::Debug(_) = tracked_call.name() {
+            return;
+        }
+
+        // The input of the debug node is the bit of the Case combinator (see handle_jet),
+        // followed by the input of the fallible call.
+        let Ok(input_val) = SimValue::from_padded_bits(&mut input.clone(), &node.arrow().source)
+        else {
+            self.warn(&format!("Failed to decode call input for CMR {cmr}"));
+            return;
+        };
+        let Some((_, call_input)) = input_val.as_ref().as_product() else {
+            return;
+        };
+
+        if let Some(Either::Left(fallible_call)) =
+            tracked_call.map_value(&StructuralValue::from(call_input.to_value()))
+        {
+            self.last_fallible_call = Some(fallible_call);
+        }
+    }
 }
 
-impl PruneTracker<ElementsExtension> for DefaultTracker<'_> {
+impl<J: JetSet> PruneTracker<J> for DefaultTracker<'_> {
     fn contains_left(&self, ihr: Ihr) -> bool {
-        if PruneTracker::<ElementsExtension>::contains_left(&self.inner, ihr) {
+        if PruneTracker::<J>::contains_left(&self.inner, ihr) {
             return true;
         }
 
@@ -306,7 +363,7 @@ impl PruneTracker<ElementsExtension> for DefaultTracker<'_> {
     }
 
     fn contains_right(&self, ihr: Ihr) -> bool {
-        if PruneTracker::<ElementsExtension>::contains_right(&self.inner, ihr) {
+        if PruneTracker::<J>::contains_right(&self.inner, ihr) {
             return true;
         }
 
@@ -318,16 +375,14 @@ impl PruneTracker<ElementsExtension> for DefaultTracker<'_> {
     }
 }
 
-impl ExecTracker<ElementsExtension> for DefaultTracker<'_> {
-    fn visit_node(
-        &mut self,
-        node: &RedeemNode<ElementsExtension>,
-        input: FrameIter,
-        output: NodeOutput,
-    ) {
+impl<J: JetSet> ExecTracker<J> for DefaultTracker<'_> {
+    fn visit_node(&mut self, node: &RedeemNode<J>, input: FrameIter, output: NodeOutput) {
         match node.inner() {
             Inner::Jet(jet) => self.handle_jet(node, *jet, &input, &output),
-            Inner::AssertL(_, cmr) => self.handle_debug(node, &input, cmr),
+            Inner::AssertL(_, cmr) => {
+                self.handle_debug(node, &input, cmr);
+                self.handle_fallible(node, &input, cmr);
+            }
             _ => {}
         }
 
@@ -335,12 +390,331 @@ impl ExecTracker<ElementsExtension> for DefaultTracker<'_> {
     }
 }
 
+/// Tracker that runs alongside an arbitrary tracker of the caller.
+///
+/// The tracker records the executed branches, so the program can be pruned,
+/// the fallible call that was executed last, so a failure can be decoded,
+/// and the cost of the executed nodes.
+pub(crate) struct RunTracker<'a, T> {
+    tracker: &'a mut T,
+    inner: DefaultTracker<'a>,
+    /// Cost of the executed nodes in milliweight.
+    cost: u32,
+}
+
+impl<'a, T> RunTracker<'a, T> {
+    /// Creates a new tracker that forwards all nodes to the given `tracker`.
+    pub fn new(debug_symbols: &'a DebugSymbols, tracker: &'a mut T) -> Self {
+        Self {
+            tracker,
+            inner: DefaultTracker::new(debug_symbols),
+            cost: 0,
+        }
+    }
+
+    /// Returns the cost of the nodes that were executed so far.
+    pub fn cost(&self) -> Cost {
+        Cost::from_milliweight(self.cost)
+    }
+
+    /// Returns the fallible call that was executed last.
+    pub fn failed_call(&self) -> Option<&FallibleCall> {
+        self.inner.failed_call()
+    }
+
+    /// Returns whether the left child of the `case` node with the given IHR was executed.
+    pub fn executed_left<J: JetSet>(&self, ihr: Ihr) -> bool {
+        PruneTracker::<J>::contains_left(&self.inner.inner, ihr)
+    }
+
+    /// Returns whether the right child of the `case` node with the given IHR was executed.
+    pub fn executed_right<J: JetSet>(&self, ihr: Ihr) -> bool {
+        PruneTracker::<J>::contains_right(&self.inner.inner, ihr)
+    }
+}
+
+impl<J: JetSet, T: ExecTracker<J>> PruneTracker<J> for RunTracker<'_, T> {
+    fn contains_left(&self, ihr: Ihr) -> bool {
+        self.executed_left::<J>(ihr)
+    }
+
+    fn contains_right(&self, ihr: Ihr) -> bool {
+        self.executed_right::<J>(ihr)
+    }
+}
+
+impl<J: JetSet, T: ExecTracker<J>> ExecTracker<J> for RunTracker<'_, T> {
+    fn visit_node(&mut self, node: &RedeemNode<J>, input: FrameIter, output: NodeOutput) {
+        self.cost = self.cost.saturating_add(node_cost(node));
+        self.tracker.visit_node(node, input.clone(), output.clone());
+        self.inner.visit_node(node, input, output);
+    }
+}
+
+/// Returns the cost of executing the given node, without the cost of its children.
+///
+/// The cost bound of a node is its own cost plus the cost bounds of its children.
+/// Only one child of a `case` node is executed, so its bound includes the larger bound of its children.
+fn node_cost<J: Jet>(node: &RedeemNode<J>) -> u32 {
+    let bound = |node: &RedeemNode<J>| u32::from(node.bounds().cost);
+    let children = match (node.inner(), node.as_dag_node()) {
+        (_, Dag::Nullary) => 0,
+        (_, Dag::Unary(child)) => bound(child),
+        (Inner::Case(..), Dag::Binary(left, right)) => bound(left).max(bound(right)),
+        (_, Dag::Binary(left, right)) => bound(left).saturating_add(bound(right)),
+    };
+    bound(node).saturating_sub(children)
+}
+
+/// Resource of an execution profile that is used as the weight of folded stacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ProfileWeight {
+    /// Number of executed nodes.
+    Nodes,
+    /// Cost of the executed jets, in milliweight units.
+    #[default]
+    JetCost,
+}
+
+impl std::str::FromStr for ProfileWeight {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nodes" => Ok(Self::Nodes),
+            "jet-cost" => Ok(Self::JetCost),
+            _ => Err(format!("Unknown profile weight `{s}`")),
+        }
+    }
+}
+
+/// Resources that executed nodes use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ProfileEntry {
+    nodes: u64,
+    jet_cost: u64,
+}
+
+impl ProfileEntry {
+    /// Access the number of executed nodes.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Access the cost of the executed jets, in milliweight units.
+    pub fn jet_cost(&self) -> u64 {
+        self.jet_cost
+    }
+
+    /// Return the given resource.
+    pub fn weight(&self, weight: ProfileWeight) -> u64 {
+        match weight {
+            ProfileWeight::Nodes => self.nodes,
+            ProfileWeight::JetCost => self.jet_cost,
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.nodes += other.nodes;
+        self.jet_cost += other.jet_cost;
+    }
+}
+
+/// Execution profile of a SimplicityHL program.
+///
+/// The resources of the executed nodes are attributed to stacks and to source lines.
+/// A stack starts at `main` and continues with the called functions
+/// and the tracked call expressions, such as jet calls, that contain the node.
+/// Profiles of multiple runs can be merged.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Profile {
+    stacks: HashMap<Arc<str>, ProfileEntry>,
+    lines: BTreeMap<usize, ProfileEntry>,
+}
+
+impl Profile {
+    /// Iterate over the resources of each stack.
+    ///
+    /// The frames of a stack are separated by semicolons.
+    /// The resources of a stack do not include the resources of its substacks.
+    pub fn stacks(&self) -> impl Iterator<Item = (&str, &ProfileEntry)> {
+        self.stacks
+            .iter()
+            .map(|(stack, entry)| (stack.as_ref(), entry))
+    }
+
+    /// Iterate over the resources of each source line, in ascending order of lines.
+    ///
+    /// Nodes are attributed to the innermost function or tracked call expression that contains them.
+    /// Nodes of `main` outside of tracked calls are not attributed to any line.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &ProfileEntry)> {
+        self.lines.iter().map(|(line, entry)| (*line, entry))
+    }
+
+    /// Add the resources of the `other` profile to this profile.
+    pub fn merge(&mut self, other: &Self) {
+        for (stack, entry) in &other.stacks {
+            self.stacks
+                .entry(Arc::clone(stack))
+                .or_default()
+                .merge(entry);
+        }
+        for (line, entry) in &other.lines {
+            self.lines.entry(*line).or_default().merge(entry);
+        }
+    }
+
+    /// Write the profile in the folded-stack format of flamegraph tools.
+    ///
+    /// Each line consists of a stack and the given resource of that stack.
+    /// Stacks with a zero resource are omitted.
+    pub fn to_folded(&self, weight: ProfileWeight) -> String {
+        let mut stacks: Vec<_> = self
+            .stacks()
+            .map(|(stack, entry)| (stack, entry.weight(weight)))
+            .filter(|(_, value)| 0 < *value)
+            .collect();
+        stacks.sort_unstable();
+        let mut folded = String::new();
+        for (stack, value) in stacks {
+            folded.push_str(&format!("{stack} {value}\n"));
+        }
+        folded
+    }
+
+    fn add(&mut self, location: &Location, entry: &ProfileEntry) {
+        self.stacks
+            .entry(Arc::clone(&location.stack))
+            .or_default()
+            .merge(entry);
+        if let Some(line) = location.line {
+            self.lines.entry(line).or_default().merge(entry);
+        }
+    }
+}
+
+/// Stack and source line of a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Location {
+    stack: Arc<str>,
+    line: Option<usize>,
+}
+
+impl Location {
+    fn main() -> Self {
+        Self {
+            stack: Arc::from("main"),
+            line: None,
+        }
+    }
+
+    fn push<F: fmt::Display>(&self, frame: F, line: usize) -> Self {
+        // Semicolons separate the frames of folded stacks
+        let frame = frame.to_string().replace(';', ",");
+        Self {
+            stack: Arc::from(format!("{};{frame}", self.stack)),
+            line: Some(line),
+        }
+    }
+}
+
+/// Tracker that profiles the execution of a SimplicityHL program.
+///
+/// The tracker counts the executed nodes and the cost of the executed jets.
+/// These resources are attributed to SimplicityHL functions, tracked call expressions
+/// and source lines via the [`DebugSymbols`] of the program.
+/// Call expressions are only tracked if the program was compiled with debug symbols.
+///
+/// Nodes that are shared between multiple stacks are attributed to one of them.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut profiler = ProfilingTracker::new(program.redeem().as_ref(), program.debug_symbols());
+/// program.run(&env, &mut profiler)?;
+/// std::fs::write("profile.folded", profiler.profile().to_folded(ProfileWeight::JetCost))?;
+/// ```
+pub struct ProfilingTracker {
+    locations: HashMap<usize, Location>,
+    profile: Profile,
+}
+
+impl ProfilingTracker {
+    /// Creates a new tracker for the given program with the given debug symbols.
+    ///
+    /// The tracker must be used to run this exact program.
+    pub fn new<J: JetSet>(program: &RedeemNode<J>, debug_symbols: &DebugSymbols) -> Self {
+        let mut locations = HashMap::new();
+        let mut stack = vec![(program, Location::main())];
+
+        while let Some((node, parent)) = stack.pop() {
+            if locations.contains_key(&node_key(node)) {
+                continue;
+            }
+            let function = debug_symbols.get_function(&node.cmr());
+            let call = match node.inner() {
+                Inner::AssertL(_, cmr) => debug_symbols.get(cmr),
+                _ => None,
+            };
+            let location = match (function, call) {
+                (Some(function), _) => {
+                    parent.push(function.name(), function.span().start.line.get())
+                }
+                (None, Some(call)) => parent.push(
+                    format!("{} (line {})", call.text(), call.span().start.line),
+                    call.span().start.line.get(),
+                ),
+                (None, None) => parent,
+            };
+
+            match node.as_dag_node() {
+                Dag::Nullary => {}
+                Dag::Unary(child) => stack.push((child, location.clone())),
+                Dag::Binary(left, right) => {
+                    stack.push((left, location.clone()));
+                    stack.push((right, location.clone()));
+                }
+            }
+            locations.insert(node_key(node), location);
+        }
+
+        Self {
+            locations,
+            profile: Profile::default(),
+        }
+    }
+
+    /// Returns the profile of the execution so far.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+}
+
+impl<J: JetSet> ExecTracker<J> for ProfilingTracker {
+    fn visit_node(&mut self, node: &RedeemNode<J>, _input: FrameIter, _output: NodeOutput) {
+        let jet_cost = match node.inner() {
+            Inner::Jet(jet) => u64::from(u32::from(jet.cost())),
+            _ => 0,
+        };
+        let entry = ProfileEntry { nodes: 1, jet_cost };
+        match self.locations.get(&node_key(node)) {
+            Some(location) => self.profile.add(location, &entry),
+            None => self.profile.add(&Location::main(), &entry),
+        }
+    }
+}
+
+/// Identifies a node of a program by its address.
+fn node_key<J: Jet>(node: &RedeemNode<J>) -> usize {
+    std::ptr::from_ref(node) as usize
+}
+
 /// Parses jet input arguments from the bit machine's read frame.
-fn parse_jet_arguments(
-    jet: ElementsExtension,
+fn parse_jet_arguments<J: JetSet>(
+    jet: J,
     input_frame: &mut FrameIter,
 ) -> Result<Vec<Value>, String> {
-    let source_types = source_type(jet);
+    let source_types = jet.source_type();
     if source_types.is_empty() {
         return Ok(vec![]);
     }
@@ -542,4 +916,85 @@ mod tests {
             Some("Some((Right(0x6d521c38ec1ea15734ae22b7c46064412829c0d0579f0a713d1c04ede979026f), Right(1000)))")
         );
     }
+
+    #[test]
+    fn test_failed_call_message() {
+        let prog_text = r#"
+            fn check_amount(amount: u64) {
+                assert!(jet::le_64(1000, amount), "amount too low");
+            }
+
+            fn main() {
+                check_amount(5000);
+                check_amount(10);
+            }
+        "#;
+        let program = TemplateProgram::new(prog_text).unwrap();
+        let program = program.instantiate(Arguments::default(), true).unwrap();
+        let satisfied = program.satisfy(WitnessValues::default()).unwrap();
+
+        let mut tracker = DefaultTracker::new(&satisfied.debug_symbols);
+        let env = create_test_env();
+
+        assert!(satisfied
+            .redeem()
+            .prune_with_tracker(&env, &mut tracker)
+            .is_err());
+
+        let failed_call = tracker.failed_call().expect("assertion is tracked");
+        assert_eq!(
+            failed_call.text(),
+            r#"assert!(jet::le_64(1000, amount), "amount too low")"#
+        );
+        assert_eq!(failed_call.message(), Some("amount too low"));
+    }
+
+    #[test]
+    fn test_profiling() {
+        let prog_text = r#"
+fn double(x: u32) -> u32 {
+    let (_, y): (bool, u32) = jet::add_32(x, x);
+    y
+}
+
+fn main() {
+    let a: u32 = double(2);
+    assert!(jet::eq_32(a, 4));
+}
+"#;
+        let program = TemplateProgram::new(prog_text).unwrap();
+        let program = program.instantiate(Arguments::default(), true).unwrap();
+        let satisfied = program.satisfy(WitnessValues::default()).unwrap();
+
+        let mut profiler =
+            ProfilingTracker::new(satisfied.redeem().as_ref(), &satisfied.debug_symbols);
+        let env = create_test_env();
+        satisfied.run(&env, &mut profiler).unwrap();
+        let profile = profiler.profile();
+
+        let (_, add) = profile
+            .stacks()
+            .find(|(stack, _)| *stack == "main;double;jet::add_32(x, x) (line 3)")
+            .expect("jet call inside function is profiled");
+        assert!(0 < add.nodes());
+        assert!(0 < add.jet_cost());
+        assert!(profile
+            .stacks()
+            .any(|(stack, _)| stack == "main;assert!(jet::eq_32(a, 4)) (line 9)"));
+        assert!(profile.lines().any(|(line, _)| line == 3));
+
+        let folded = profile.to_folded(ProfileWeight::JetCost);
+        assert!(folded
+            .lines()
+            .any(|line| line.starts_with("main;double;jet::add_32(x, x) (line 3) ")));
+
+        let mut merged = profile.clone();
+        merged.merge(profile);
+        let (_, merged_add) = merged
+            .stacks()
+            .find(|(stack, _)| *stack == "main;double;jet::add_32(x, x) (line 3)")
+            .unwrap();
+        assert_eq!(merged_add.nodes(), 2 * add.nodes());
+        assert_eq!(merged_add.jet_cost(), 2 * add.jet_cost());
+    }
 }
@@ -1,12 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::sync::Arc;
 
+use simplicity::jet::Jet;
+use simplicity::RedeemNode;
+use simplicity_unchained::jets::unchained::ElementsExtension;
+
 use crate::error::{Error, RichError, WithFile, WithSpan};
 use crate::parse;
 use crate::parse::ParseFromStr;
 use crate::str::WitnessName;
-use crate::types::{AliasedType, ResolvedType};
+use crate::types::{AliasedType, ResolvedType, StructuralType};
 use crate::value::Value;
 
 macro_rules! impl_name_type_map {
@@ -17,7 +21,7 @@ macro_rules! impl_name_type_map {
                 self.0.get(name)
             }
 
-            /// Create an iterator over all name-type pairs.
+            /// Create an iterator over all name-type pairs, in ascending order of names.
             pub fn iter(&self) -> impl Iterator<Item = (&WitnessName, &ResolvedType)> {
                 self.0.iter()
             }
@@ -30,7 +34,7 @@ macro_rules! impl_name_type_map {
 
         impl From<HashMap<WitnessName, ResolvedType>> for $wrapper {
             fn from(value: HashMap<WitnessName, ResolvedType>) -> Self {
-                Self(Arc::new(value))
+                Self(Arc::new(value.into_iter().collect()))
             }
         }
     };
@@ -41,7 +45,7 @@ macro_rules! impl_name_value_map {
         impl $wrapper {
             /// Access the inner map.
             #[cfg(feature = "serde")]
-            pub(crate) fn as_inner(&self) -> &HashMap<WitnessName, Value> {
+            pub(crate) fn as_inner(&self) -> &BTreeMap<WitnessName, Value> {
                 &self.0
             }
 
@@ -50,7 +54,7 @@ macro_rules! impl_name_value_map {
                 self.0.get(name)
             }
 
-            /// Create an iterator over all name-value pairs.
+            /// Create an iterator over all name-value pairs, in ascending order of names.
             pub fn iter(&self) -> impl Iterator<Item = (&WitnessName, &Value)> {
                 self.0.iter()
             }
@@ -63,7 +67,7 @@ macro_rules! impl_name_value_map {
 
         impl From<HashMap<WitnessName, Value>> for $wrapper {
             fn from(value: HashMap<WitnessName, Value>) -> Self {
-                Self(Arc::new(value))
+                Self(Arc::new(value.into_iter().collect()))
             }
         }
 
@@ -75,11 +79,8 @@ macro_rules! impl_name_value_map {
 
         impl fmt::Display for $wrapper {
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                use itertools::Itertools;
-
                 writeln!(f, "mod {} {{", $module_name)?;
-                for name in self.0.keys().sorted_unstable() {
-                    let value = self.0.get(name).unwrap();
+                for (name, value) in self.0.iter() {
                     writeln!(f, "    const {name}: {} = {value};", value.ty())?;
                 }
                 write!(f, "}}")
@@ -90,14 +91,14 @@ macro_rules! impl_name_value_map {
 
 /// Map of witness types.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
-pub struct WitnessTypes(Arc<HashMap<WitnessName, ResolvedType>>);
+pub struct WitnessTypes(Arc<BTreeMap<WitnessName, ResolvedType>>);
 
 impl_name_type_map!(WitnessTypes);
 
 /// Map of witness values.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
-pub struct WitnessValues(Arc<HashMap<WitnessName, Value>>);
+pub struct WitnessValues(Arc<BTreeMap<WitnessName, Value>>);
 
 impl_name_value_map!(WitnessValues, "witness");
 
@@ -131,6 +132,104 @@ impl WitnessValues {
     }
 }
 
+/// Map of delegate types.
+///
+/// A delegate is a program that is disconnected from the SimplicityHL program.
+/// Its type consists of the source type and the target type of the delegated program.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct DelegateTypes(Arc<BTreeMap<WitnessName, (ResolvedType, ResolvedType)>>);
+
+impl DelegateTypes {
+    /// Get the source type and the target type that are assigned to the given name.
+    pub fn get(&self, name: &WitnessName) -> Option<&(ResolvedType, ResolvedType)> {
+        self.0.get(name)
+    }
+
+    /// Create an iterator over all name-type pairs, in ascending order of names.
+    pub fn iter(&self) -> impl Iterator<Item = (&WitnessName, &(ResolvedType, ResolvedType))> {
+        self.0.iter()
+    }
+
+    /// Make a cheap copy of the map.
+    pub fn shallow_clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl From<HashMap<WitnessName, (ResolvedType, ResolvedType)>> for DelegateTypes {
+    fn from(value: HashMap<WitnessName, (ResolvedType, ResolvedType)>) -> Self {
+        Self(Arc::new(value.into_iter().collect()))
+    }
+}
+
+/// Map of delegated programs.
+///
+/// A delegated program is a Simplicity program that is supplied when the SimplicityHL program
+/// is satisfied. The delegated program is not part of the commitment of the SimplicityHL program.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delegates<J: Jet = ElementsExtension>(Arc<BTreeMap<WitnessName, Arc<RedeemNode<J>>>>);
+
+impl<J: Jet> Delegates<J> {
+    /// Access the inner map.
+    pub(crate) fn as_inner(&self) -> &BTreeMap<WitnessName, Arc<RedeemNode<J>>> {
+        &self.0
+    }
+
+    /// Get the program that is assigned to the given name.
+    pub fn get(&self, name: &WitnessName) -> Option<&Arc<RedeemNode<J>>> {
+        self.0.get(name)
+    }
+
+    /// Create an iterator over all name-program pairs, in ascending order of names.
+    pub fn iter(&self) -> impl Iterator<Item = (&WitnessName, &Arc<RedeemNode<J>>)> {
+        self.0.iter()
+    }
+
+    /// Make a cheap copy of the map.
+    pub fn shallow_clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+
+    /// Check if the delegated programs are consistent with the declared delegate types.
+    ///
+    /// 1. Programs that occur in the SimplicityHL program are type checked.
+    /// 2. Programs that don't occur in the SimplicityHL program are skipped.
+    ///
+    /// Like witness values, delegated programs may lie on pruned branches,
+    /// so this check skips unassigned delegates.
+    pub fn is_consistent(&self, delegate_types: &DelegateTypes) -> Result<(), Error> {
+        for (name, program) in self.0.iter() {
+            let Some((source, target)) = delegate_types.get(name) else {
+                continue;
+            };
+            let arrow = program.arrow();
+            if arrow.source.as_ref() != StructuralType::from(source).as_ref()
+                || arrow.target.as_ref() != StructuralType::from(target).as_ref()
+            {
+                return Err(Error::DelegateTypeMismatch(
+                    name.clone(),
+                    format!("{source} → {target}"),
+                    format!("{} → {}", arrow.source, arrow.target),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<J: Jet> Default for Delegates<J> {
+    fn default() -> Self {
+        Self(Arc::default())
+    }
+}
+
+impl<J: Jet> From<HashMap<WitnessName, Arc<RedeemNode<J>>>> for Delegates<J> {
+    fn from(value: HashMap<WitnessName, Arc<RedeemNode<J>>>) -> Self {
+        Self(Arc::new(value.into_iter().collect()))
+    }
+}
+
 impl ParseFromStr for ResolvedType {
     fn parse_from_str(s: &str) -> Result<Self, RichError> {
         let aliased = AliasedType::parse_from_str(s)?;
@@ -147,7 +246,7 @@ impl ParseFromStr for ResolvedType {
 /// A parameter is a named variable that resolves to a value of a given type.
 /// Parameters have a name and a type.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
-pub struct Parameters(Arc<HashMap<WitnessName, ResolvedType>>);
+pub struct Parameters(Arc<BTreeMap<WitnessName, ResolvedType>>);
 
 impl_name_type_map!(Parameters);
 
@@ -157,7 +256,7 @@ impl_name_type_map!(Parameters);
 /// Arguments have a name and a value of a given type.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
-pub struct Arguments(Arc<HashMap<WitnessName, Value>>);
+pub struct Arguments(Arc<BTreeMap<WitnessName, Value>>);
 
 impl_name_value_map!(Arguments, "param");
 
@@ -225,6 +324,85 @@ mod tests {
         }
     }
 
+    #[test]
+    fn witness_reuse_in_match_arms() {
+        let s = r#"fn f() -> u32 {
+    witness::A
+}
+
+fn main() {
+    match witness::CHOICE {
+        false => assert!(jet::is_zero_32(witness::A)),
+        true => match witness::CHOICE2 {
+            false => assert!(jet::is_zero_32(f())),
+            true => assert!(jet::is_zero_32(f())),
+        },
+    }
+}"#;
+
+        let witness = WitnessValues::from(HashMap::from([
+            (WitnessName::from_str_unchecked("CHOICE"), Value::from(true)),
+            (
+                WitnessName::from_str_unchecked("CHOICE2"),
+                Value::from(false),
+            ),
+            (WitnessName::from_str_unchecked("A"), Value::u32(0)),
+        ]));
+        if let Err(error) = SatisfiedProgram::new(s, Arguments::default(), witness, false) {
+            panic!("Witness reuse in match arms was falsely rejected: {error}");
+        }
+    }
+
+    #[test]
+    fn witness_reuse_on_same_path() {
+        let programs = [
+            r#"fn main() {
+    match witness::CHOICE {
+        false => assert!(jet::eq_32(witness::A, witness::A)),
+        true => {},
+    }
+}"#,
+            r#"fn main() {
+    match witness::A {
+        false => {},
+        true => assert!(witness::A),
+    }
+}"#,
+            r#"fn main() {
+    match witness::CHOICE {
+        false => assert!(jet::is_zero_32(witness::A)),
+        true => {},
+    };
+    assert!(jet::is_zero_32(witness::A));
+}"#,
+        ];
+
+        for s in programs {
+            let program = parse::Program::parse_from_str(s).expect("parsing works");
+            match ast::Program::analyze(&program).map_err(Error::from) {
+                Ok(_) => panic!("Witness reuse was falsely accepted"),
+                Err(Error::WitnessReused(..)) => {}
+                Err(error) => panic!("Unexpected error: {error}"),
+            }
+        }
+    }
+
+    #[test]
+    fn witness_reuse_in_match_arms_type_mismatch() {
+        let s = r#"fn main() {
+    match witness::CHOICE {
+        false => assert!(jet::is_zero_32(witness::A)),
+        true => assert!(jet::is_zero_16(witness::A)),
+    }
+}"#;
+        let program = parse::Program::parse_from_str(s).expect("parsing works");
+        match ast::Program::analyze(&program).map_err(Error::from) {
+            Ok(_) => panic!("Ill-typed witness reuse was falsely accepted"),
+            Err(Error::ExpressionTypeMismatch(..)) => {}
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
     #[test]
     fn witness_type_mismatch() {
         let s = r#"fn main() {
@@ -237,10 +415,11 @@ mod tests {
         )]));
         match SatisfiedProgram::new(s, Arguments::default(), witness, false) {
             Ok(_) => panic!("Ill-typed witness assignment was falsely accepted"),
-            Err(error) => assert_eq!(
+            Err(crate::Error::Witness(error)) => assert_eq!(
                 "Witness `A` was declared with type `u32` but its assigned value is of type `u16`",
-                error
+                error.to_string()
             ),
+            Err(error) => panic!("Unexpected error: {error}"),
         }
     }
 
@@ -250,19 +429,123 @@ mod tests {
     witness::OUTPUT_OF_F
 }
 
+fn g() -> u32 {
+    f()
+}
+
 fn main() {
-    assert!(jet::is_zero_32(f()));
+    assert!(jet::is_zero_32(g()));
 }"#;
 
-        match CompiledProgram::new(s, Arguments::default(), false) {
-            Ok(_) => panic!("Witness outside main was falsely accepted"),
-            Err(error) => {
-                assert!(error
-                    .contains("Witness expressions are not allowed outside the `main` function"))
+        let witness = WitnessValues::from(HashMap::from([(
+            WitnessName::from_str_unchecked("OUTPUT_OF_F"),
+            Value::u32(0),
+        )]));
+        if let Err(error) = SatisfiedProgram::new(s, Arguments::default(), witness, false) {
+            panic!("Witness outside main was falsely rejected: {error}");
+        }
+    }
+
+    #[test]
+    fn witness_function_called_twice() {
+        let programs = [
+            r#"fn f() -> u32 {
+    witness::OUTPUT_OF_F
+}
+
+fn main() {
+    assert!(jet::is_zero_32(f()));
+    assert!(jet::is_zero_32(f()));
+}"#,
+            r#"fn f() -> u32 {
+    witness::OUTPUT_OF_F
+}
+
+fn g() -> u32 {
+    f()
+}
+
+fn main() {
+    assert!(jet::is_zero_32(f()));
+    assert!(jet::is_zero_32(g()));
+}"#,
+        ];
+
+        for s in programs {
+            let program = parse::Program::parse_from_str(s).expect("parsing works");
+            match ast::Program::analyze(&program).map_err(Error::from) {
+                Ok(_) => panic!("Witness reuse was falsely accepted"),
+                Err(Error::WitnessFunctionCalledTwice(..)) => {}
+                Err(error) => panic!("Unexpected error: {error}"),
             }
         }
     }
 
+    #[test]
+    fn witness_function_in_loop() {
+        let s = r#"fn add_witness(element: u8, acc: u8) -> u8 {
+    let (_, sum): (bool, u8) = jet::add_8(acc, witness::OFFSET);
+    sum
+}
+
+fn main() {
+    let sum: u8 = array_fold::<add_witness, 3>([1, 2, 3], 0);
+}"#;
+        let program = parse::Program::parse_from_str(s).expect("parsing works");
+        match ast::Program::analyze(&program).map_err(Error::from) {
+            Ok(_) => panic!("Witness in loop was falsely accepted"),
+            Err(Error::WitnessInLoop(..)) => {}
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn delegate_reuse() {
+        let s = r#"fn forward(cmr: u256, x: u8) -> (u256, u8) {
+    (cmr, x)
+}
+
+fn main() {
+    let (_, _): (u256, ()) = disconnect::<forward, A>(witness::A);
+}"#;
+        let program = parse::Program::parse_from_str(s).expect("parsing works");
+        match ast::Program::analyze(&program).map_err(Error::from) {
+            Ok(_) => panic!("Delegate reuse was falsely accepted"),
+            Err(Error::WitnessReused(..)) => {}
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
+    #[test]
+    fn delegate_type_mismatch() {
+        let s = r#"fn forward(cmr: u256, x: u8) -> ((), u8) {
+    ((), x)
+}
+
+fn main() {
+    let (_, _): ((), ()) = disconnect::<forward, DELEGATE>(1);
+}"#;
+        let delegate = SatisfiedProgram::new(
+            "fn main() {}",
+            Arguments::default(),
+            WitnessValues::default(),
+            false,
+        )
+        .unwrap();
+        let delegates = Delegates::from(HashMap::from([(
+            WitnessName::from_str_unchecked("DELEGATE"),
+            Arc::clone(delegate.redeem()),
+        )]));
+        let program = CompiledProgram::new(s, Arguments::default(), false).unwrap();
+        match program.satisfy_with_delegates(WitnessValues::default(), delegates, None) {
+            Ok(_) => panic!("Ill-typed delegate assignment was falsely accepted"),
+            Err(crate::Error::Delegates(error)) => assert!(error
+                .to_string()
+                .starts_with("Delegate `DELEGATE` was declared with type `u8 → ()` but its assigned program is of type")),
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+
     #[test]
     fn missing_witness_module() {
         match WitnessValues::parse_from_str("") {
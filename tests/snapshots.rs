@@ -0,0 +1,135 @@
+//! Snapshot tests of the example programs.
+//!
+//! Each program `examples/NAME.simf` is compiled with the arguments in `examples/NAME.args`,
+//! if that file exists. The CMR, the length and the bytes of the encoded program
+//! are compared against the snapshot `examples/snapshots/NAME.snap`.
+//!
+//! A changed CMR changes the address of a contract, so coins that are locked
+//! in a deployed contract could no longer be spent with the output of the new compiler.
+//! If the change is intended, record the new snapshots with
+//!
+//! ```text
+//! cargo test --test snapshots -- --bless
+//! ```
+//!
+//! and explain the change in `CHANGELOG.md`. CI rejects changed snapshots without a changelog entry.
+
+use std::path::{Path, PathBuf};
+
+use base64::display::Base64Display;
+use base64::engine::general_purpose::STANDARD;
+use simplicityhl::{Arguments, TemplateProgram};
+
+/// Compile the given example program and return its snapshot.
+fn snapshot(prog_path: &Path) -> Result<String, String> {
+    let prog_text = std::fs::read_to_string(prog_path).map_err(|e| e.to_string())?;
+    let arguments = match std::fs::read_to_string(prog_path.with_extension("args")) {
+        Ok(args_text) => {
+            serde_json::from_str::<Arguments>(&args_text).map_err(|e| e.to_string())?
+        }
+        Err(..) => Arguments::default(),
+    };
+    let compile = || -> Result<(String, Vec<u8>), String> {
+        let template = TemplateProgram::new(prog_text.as_str()).map_err(|e| e.to_string())?;
+        let commit = template
+            .instantiate(arguments.clone(), false)
+            .map_err(|e| e.to_string())?
+            .commit();
+        Ok((commit.cmr().to_string(), commit.to_vec_without_witness()))
+    };
+
+    let compiled = compile()?;
+    if compiled != compile()? {
+        return Err("program compiles nondeterministically".to_string());
+    }
+    let (cmr, program_bytes) = compiled;
+
+    Ok(format!(
+        "cmr: {cmr}\nlength: {}\nprogram: {}\n",
+        program_bytes.len(),
+        Base64Display::new(&program_bytes, &STANDARD)
+    ))
+}
+
+/// Return the files in the given directory with the given extension, sorted by name.
+fn files_with_extension(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+fn main() {
+    let bless = std::env::args().any(|arg| arg == "--bless");
+    let examples_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let snapshots_dir = examples_dir.join("snapshots");
+    let prog_paths = files_with_extension(&examples_dir, "simf");
+    let mut failures = Vec::new();
+
+    for prog_path in &prog_paths {
+        let name = prog_path.file_stem().unwrap().to_string_lossy();
+        let snapshot_path = snapshots_dir.join(format!("{name}.snap"));
+        let actual = match snapshot(prog_path) {
+            Ok(actual) => actual,
+            Err(error) => {
+                failures.push(format!("{name}: {error}"));
+                continue;
+            }
+        };
+
+        if bless {
+            std::fs::create_dir_all(&snapshots_dir).unwrap();
+            std::fs::write(&snapshot_path, &actual).unwrap();
+            continue;
+        }
+        match std::fs::read_to_string(&snapshot_path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{name}: snapshot changed\n--- expected\n{expected}+++ actual\n{actual}"
+            )),
+            Err(..) => failures.push(format!(
+                "{name}: snapshot {} is missing",
+                snapshot_path.display()
+            )),
+        }
+    }
+
+    // Snapshots of removed examples
+    for snapshot_path in files_with_extension(&snapshots_dir, "snap") {
+        let name = snapshot_path.file_stem().unwrap().to_string_lossy();
+        if examples_dir.join(format!("{name}.simf")).exists() {
+            continue;
+        }
+        if bless {
+            std::fs::remove_file(&snapshot_path).unwrap();
+        } else {
+            failures.push(format!(
+                "{name}: example was removed, but its snapshot remains"
+            ));
+        }
+    }
+
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("{failure}\n");
+        }
+        eprintln!(
+            "{} of {} example snapshots failed. If the changes are intended, \
+            run `cargo test --test snapshots -- --bless` and add an entry to CHANGELOG.md.",
+            failures.len(),
+            prog_paths.len()
+        );
+        std::process::exit(1);
+    }
+    if bless {
+        println!("recorded {} example snapshots", prog_paths.len());
+    } else {
+        println!("{} example snapshots ok", prog_paths.len());
+    }
+}